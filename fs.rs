@@ -17,13 +17,22 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
+use cmp::{self, Ordering};
+use env;
+use error;
 use fmt;
 use ffi::OsString;
-use io::{self, SeekFrom, Seek, Read, Initializer, Write};
+use io::{self, SeekFrom, Seek, Read, Initializer, Write, IoSlice, IoSliceMut, ReadBuf};
+use mem;
+use ops::{Deref, DerefMut};
 use path::{Path, PathBuf};
+use slice;
+use sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use sys;
 use sys::fs as fs_imp;
 use sys_common::{AsInnerMut, FromInner, AsInner, IntoInner};
 use time::SystemTime;
+use vec;
 
 /// A reference to an open file on the filesystem.
 ///
@@ -133,6 +142,202 @@ pub struct ReadDir(fs_imp::ReadDir);
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct DirEntry(fs_imp::DirEntry);
 
+/// Recursive iterator over the entries of a directory tree.
+///
+/// This struct is returned from the [`walk_dir`] function of this module,
+/// whose documentation describes it in detail. The methods of `WalkDir`
+/// configure the walk before it is started, and [`skip_current_dir`] controls
+/// it as it goes.
+///
+/// [`walk_dir`]: fn.walk_dir.html
+/// [`skip_current_dir`]: #method.skip_current_dir
+#[unstable(feature = "walk_dir", issue = "0")]
+pub struct WalkDir {
+    root: PathBuf,
+    min_depth: usize,
+    max_depth: usize,
+    follow_links: bool,
+    contents_first: bool,
+    sorter: Option<Box<FnMut(&DirEntry, &DirEntry) -> Ordering + Send + Sync>>,
+
+    started: bool,
+    // The directories currently being read, the innermost one last.
+    stack: Vec<WalkFrame>,
+    // A directory which was just found and which will be read next, unless
+    // `skip_current_dir` is called first.
+    pending: Option<PendingDir>,
+    // An entry to return before going on with the walk.
+    queued: Option<WalkDirEntry>,
+}
+
+struct WalkFrame {
+    path: PathBuf,
+    list: WalkList,
+    // Only recorded when following symbolic links, for loop detection.
+    id: Option<DirId>,
+    // The entry of the directory itself in contents-first order, returned
+    // once everything in it has been.
+    entry: Option<WalkDirEntry>,
+}
+
+enum WalkList {
+    Unsorted(ReadDir),
+    Sorted(vec::IntoIter<io::Result<DirEntry>>),
+}
+
+struct PendingDir {
+    path: PathBuf,
+    entry: Option<WalkDirEntry>,
+}
+
+/// An entry found by [`WalkDir`].
+///
+/// This is a [`DirEntry`] together with its depth in the walk and, when
+/// symbolic links are followed, the type of the file they point to.
+///
+/// [`WalkDir`]: struct.WalkDir.html
+/// [`DirEntry`]: struct.DirEntry.html
+#[unstable(feature = "walk_dir", issue = "0")]
+#[derive(Debug)]
+pub struct WalkDirEntry {
+    entry: DirEntry,
+    file_type: FileType,
+    depth: usize,
+    followed: bool,
+}
+
+/// A named temporary file, which is removed when the `TempFile` is dropped.
+///
+/// The file is created with a unique, random name, and is only accessible by
+/// its owner on Unix. Use [`persist`] to keep it under another name instead.
+///
+/// `TempFile` implements [`Read`], [`Write`] and [`Seek`] like [`File`], and
+/// [`as_file`] gives access to the rest of its methods.
+///
+/// [`persist`]: #method.persist
+/// [`as_file`]: #method.as_file
+/// [`Read`]: ../io/trait.Read.html
+/// [`Write`]: ../io/trait.Write.html
+/// [`Seek`]: ../io/trait.Seek.html
+/// [`File`]: struct.File.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(temp_file)]
+/// use std::fs::TempFile;
+/// use std::io::Write;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// let mut tmp = TempFile::new()?;
+/// writeln!(tmp, "scratch data")?;
+/// // The file is removed here.
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "temp_file", issue = "0")]
+#[derive(Debug)]
+pub struct TempFile {
+    // The file has to be closed before its path can be removed on Windows.
+    file: File,
+    path: TempPath,
+}
+
+/// The error returned by [`TempFile::persist`], which hands back the
+/// temporary file so that the data written to it isn't lost.
+///
+/// [`TempFile::persist`]: struct.TempFile.html#method.persist
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(temp_file)]
+/// use std::fs::TempFile;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// let tmp = TempFile::new()?;
+/// let file = match tmp.persist("/mnt/other/report.txt") {
+///     Ok(file) => file,
+///     // Try again somewhere else, with the same data.
+///     Err(e) => e.into_inner().persist("report.txt")?,
+/// };
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "temp_file", issue = "0")]
+#[derive(Debug)]
+pub struct PersistError {
+    error: io::Error,
+    file: TempFile,
+}
+
+/// A temporary directory, which is removed along with its contents when the
+/// `TempDir` is dropped.
+///
+/// The directory is created with a unique, random name, and is only
+/// accessible by its owner on Unix.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(temp_file)]
+/// use std::fs::{self, TempDir};
+///
+/// # fn foo() -> std::io::Result<()> {
+/// let dir = TempDir::new()?;
+/// fs::write(dir.path().join("data.txt"), "scratch data")?;
+/// // The directory and `data.txt` are removed here.
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "temp_file", issue = "0")]
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+/// A writer which atomically replaces a file with the data written to it once
+/// it is [`commit`]ted.
+///
+/// Data is written to a temporary file next to the target, which is flushed
+/// to the disk and renamed over the target by [`commit`], after which the
+/// parent directory is flushed as well. Other processes thus only ever see
+/// either the previous contents of the file or the complete new ones, even
+/// if the system crashes halfway through. Dropping an `AtomicWriter` without
+/// committing it leaves the target untouched.
+///
+/// If the target already exists, the new file gets its permissions.
+///
+/// [`commit`]: #method.commit
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(atomic_write)]
+/// use std::fs::AtomicWriter;
+/// use std::io::Write;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// let mut writer = AtomicWriter::new("app.conf")?;
+/// writeln!(writer, "threads = 4")?;
+/// writeln!(writer, "verbose = false")?;
+/// writer.commit()?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "atomic_write", issue = "0")]
+#[derive(Debug)]
+pub struct AtomicWriter {
+    temp: TempFile,
+    path: PathBuf,
+    dir: PathBuf,
+}
+
+// The path of a `TempFile`, removing the file when dropped unless it was
+// emptied by `persist`.
+#[derive(Debug)]
+struct TempPath(PathBuf);
+
 /// Options and flags which can be used to configure how a file is opened.
 ///
 /// This builder exposes the ability to configure how a [`File`] is opened and
@@ -201,6 +406,220 @@ pub struct Permissions(fs_imp::FilePermissions);
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct FileType(fs_imp::FileType);
 
+/// Representation of the various timestamps on a file, used to change them
+/// with [`File::set_times`] or [`set_times`].
+///
+/// Timestamps which aren't set are left unchanged.
+///
+/// [`File::set_times`]: struct.File.html#method.set_times
+/// [`set_times`]: fn.set_times.html
+#[unstable(feature = "file_set_times", issue = "0")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FileTimes(fs_imp::FileTimes);
+
+/// Options and flags which can be used to configure how a file is copied.
+///
+/// This builder exposes the ability to configure how a file is copied and
+/// what happens to its metadata. With the default options [`copy`] behaves
+/// like [`fs::copy`]: the data and the permission bits of `from` are copied,
+/// and an existing destination is overwritten.
+///
+/// Generally speaking, when using `CopyOptions`, you'll first call [`new`],
+/// then chain calls to methods to set each option, then call [`copy`],
+/// passing the paths of the source and the destination.
+///
+/// [`new`]: #method.new
+/// [`copy`]: #method.copy
+/// [`fs::copy`]: fn.copy.html
+///
+/// # Examples
+///
+/// Cloning a file on filesystems supporting it, keeping its timestamps and
+/// refusing to replace an existing file:
+///
+/// ```no_run
+/// #![feature(copy_options)]
+/// use std::fs::{CopyOptions, Reflink};
+///
+/// # fn foo() -> std::io::Result<()> {
+/// CopyOptions::new()
+///     .reflink(Reflink::Auto)
+///     .preserve_timestamps(true)
+///     .overwrite(false)
+///     .copy("foo.txt", "bar.txt")?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "copy_options", issue = "0")]
+#[derive(Clone, Debug)]
+pub struct CopyOptions(fs_imp::CopyOptions);
+
+/// Whether the destination of a copy shares its data with the source.
+///
+/// Filesystems with copy-on-write extents, such as Btrfs and XFS, can make a
+/// copy share the extents of the original file (a "reflink"), which takes
+/// neither time nor space until either of the files is modified.
+///
+/// This is used with [`CopyOptions::reflink`].
+///
+/// [`CopyOptions::reflink`]: struct.CopyOptions.html#method.reflink
+#[unstable(feature = "copy_options", issue = "0")]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Reflink {
+    /// Always copy the data. This is the default.
+    #[unstable(feature = "copy_options", issue = "0")]
+    Never,
+    /// Share the data when the filesystem supports it and copy it otherwise.
+    #[unstable(feature = "copy_options", issue = "0")]
+    Auto,
+    /// Share the data, and fail if the filesystem doesn't support it.
+    #[unstable(feature = "copy_options", issue = "0")]
+    Always,
+}
+
+/// Options and flags which can be used to map a file into memory.
+///
+/// A mapping makes the contents of a file accessible as a byte slice, with
+/// the operating system reading the data lazily as it is accessed. This
+/// avoids copying the data of large files, especially when only parts of
+/// them are accessed.
+///
+/// By default the whole file is mapped; [`offset`] and [`len`] restrict the
+/// mapping to a range of it. The mapping is created by one of [`map`],
+/// [`map_mut`] and [`map_copy`].
+///
+/// [`offset`]: #method.offset
+/// [`len`]: #method.len
+/// [`map`]: #method.map
+/// [`map_mut`]: #method.map_mut
+/// [`map_copy`]: #method.map_copy
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(mmap)]
+/// use std::fs::{File, MmapOptions};
+///
+/// # fn foo() -> std::io::Result<()> {
+/// let file = File::open("index.bin")?;
+/// // Nothing else modifies the index while it is mapped.
+/// let index = unsafe { MmapOptions::new().offset(4096).map(&file)? };
+/// println!("first entry: {:?}", &index[..16]);
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "mmap", issue = "0")]
+#[derive(Clone, Debug, Default)]
+pub struct MmapOptions {
+    offset: u64,
+    len: Option<usize>,
+}
+
+/// A read-only memory map of a file, created by [`MmapOptions::map`].
+///
+/// `Mmap` dereferences to `[u8]`. The file can be closed once it is mapped,
+/// the mapping is removed when the `Mmap` is dropped.
+///
+/// [`MmapOptions::map`]: struct.MmapOptions.html#method.map
+#[unstable(feature = "mmap", issue = "0")]
+pub struct Mmap {
+    inner: fs_imp::Mmap,
+}
+
+/// A writable memory map of a file, created by [`MmapOptions::map_mut`] or
+/// [`MmapOptions::map_copy`].
+///
+/// `MmapMut` dereferences to `[u8]` mutably. The mapping is removed when the
+/// `MmapMut` is dropped.
+///
+/// [`MmapOptions::map_mut`]: struct.MmapOptions.html#method.map_mut
+/// [`MmapOptions::map_copy`]: struct.MmapOptions.html#method.map_copy
+#[unstable(feature = "mmap", issue = "0")]
+pub struct MmapMut {
+    inner: fs_imp::Mmap,
+    // Whether this is a copy-on-write mapping created by `map_copy`.
+    private: bool,
+}
+
+/// How a memory map is going to be accessed, passed to [`Mmap::advise`].
+///
+/// The operating system uses this to decide what to read ahead and what to
+/// keep in memory; it doesn't change the contents of the mapping, except for
+/// [`DontNeed`].
+///
+/// [`Mmap::advise`]: struct.Mmap.html#method.advise
+/// [`DontNeed`]: #variant.DontNeed
+#[unstable(feature = "mmap", issue = "0")]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Advice {
+    /// No particular access pattern. This is the default.
+    #[unstable(feature = "mmap", issue = "0")]
+    Normal,
+    /// The mapping is accessed in random order, so reading ahead is useless.
+    #[unstable(feature = "mmap", issue = "0")]
+    Random,
+    /// The mapping is accessed sequentially, so it can be read ahead
+    /// aggressively and pages already accessed can be dropped.
+    #[unstable(feature = "mmap", issue = "0")]
+    Sequential,
+    /// The mapping is going to be accessed soon, so it should be read ahead.
+    #[unstable(feature = "mmap", issue = "0")]
+    WillNeed,
+    /// The mapping isn't going to be accessed soon, so its memory can be
+    /// freed. Modifications of a [`map_copy`] mapping are lost on Linux, so
+    /// this can only be passed to [`MmapMut::advise_unchecked`] for such a
+    /// mapping.
+    ///
+    /// [`map_copy`]: struct.MmapOptions.html#method.map_copy
+    /// [`MmapMut::advise_unchecked`]: struct.MmapMut.html#method.advise_unchecked
+    #[unstable(feature = "mmap", issue = "0")]
+    DontNeed,
+}
+
+/// An iterator over the regions of data and holes in a file.
+///
+/// This struct is created by [`File::extents`].
+///
+/// [`File::extents`]: struct.File.html#method.extents
+#[unstable(feature = "file_allocate", issue = "0")]
+#[derive(Debug)]
+pub struct Extents<'a> {
+    file: &'a mut File,
+    offset: u64,
+    end: u64,
+    fallback: bool,
+}
+
+/// A region of a file, returned by [`Extents`].
+///
+/// [`Extents`]: struct.Extents.html
+#[unstable(feature = "file_allocate", issue = "0")]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Extent {
+    offset: u64,
+    len: u64,
+    hole: bool,
+}
+
+/// Statistics about a mounted filesystem.
+///
+/// This structure is returned by [`space`] and [`File::fs_stats`]. Values
+/// which a platform doesn't report are zero.
+///
+/// [`space`]: fn.space.html
+/// [`File::fs_stats`]: struct.File.html#method.fs_stats
+#[unstable(feature = "fs_stats", issue = "0")]
+#[derive(Clone)]
+pub struct FsStats(fs_imp::FsStats);
+
+/// How a file is mapped into memory.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) enum MmapMode {
+    ReadOnly,
+    Shared,
+    Private,
+}
+
 /// A builder used to create directories in various manners.
 ///
 /// This builder also supports platform-specific options.
@@ -211,6 +630,58 @@ pub struct DirBuilder {
     recursive: bool,
 }
 
+/// A handle to an open directory.
+///
+/// Paths given to the methods of a `Dir` are resolved relative to the
+/// directory itself rather than to the current working directory, and keep
+/// referring to the same directory even if it is renamed or moved while the
+/// handle is open. This avoids races where a directory is replaced between
+/// two operations on paths inside of it.
+///
+/// A `Dir` can optionally refuse to follow symbolic links, see
+/// [`DirOptions::follow_symlinks`].
+///
+/// # Platform-specific behavior
+///
+/// This type currently corresponds to a descriptor opened with `O_DIRECTORY`
+/// on Unix, used with the `openat`, `mkdirat`, `unlinkat`, `renameat`,
+/// `symlinkat` and `fstatat` functions. Opening a `Dir` is not supported on
+/// other platforms yet and always returns an error there.
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+/// [`DirOptions::follow_symlinks`]: struct.DirOptions.html#method.follow_symlinks
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(dir_handle)]
+/// use std::fs::{Dir, OpenOptions};
+/// use std::io::Write;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// let dir = Dir::open("/var/cache/app")?;
+/// dir.create_dir("objects")?;
+/// let mut file = dir.open_file("objects/new", OpenOptions::new().write(true).create(true))?;
+/// file.write_all(b"data")?;
+/// dir.rename("objects/new", &dir, "objects/1")?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "dir_handle", issue = "0")]
+pub struct Dir {
+    inner: fs_imp::DirHandle,
+}
+
+/// Options and flags which can be used to configure how a [`Dir`] is opened.
+///
+/// [`Dir`]: struct.Dir.html
+#[unstable(feature = "dir_handle", issue = "0")]
+#[derive(Clone, Debug)]
+pub struct DirOptions {
+    follow_symlinks: bool,
+}
+
 impl File {
     /// Attempts to open a file in read-only mode.
     ///
@@ -345,92 +816,389 @@ impl File {
         self.inner.truncate(size)
     }
 
-    /// Queries metadata about the underlying file.
+    /// Allocates disk space for the `len` bytes of the file starting at
+    /// `offset`, extending the file if they go beyond its end.
+    ///
+    /// Unlike [`set_len`], which may leave the new part of the file without
+    /// any space allocated, this makes sure that writes to the range don't
+    /// fail for lack of space, and keeps the file from being fragmented by
+    /// growing it piecewise. The contents of the file are unchanged, and any
+    /// new part reads as zeros.
+    ///
+    /// [`set_len`]: #method.set_len
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `fallocate` on Linux,
+    /// `fcntl` with `F_PREALLOCATE` on macOS and `posix_fallocate` on FreeBSD
+    /// and Solaris. On Windows, only the part of the range beyond the end of
+    /// the file is allocated, by extending the file. Note that, this [may
+    /// change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file is not opened for
+    /// writing, if there isn't enough space, or with the `Unsupported` kind
+    /// on platforms and filesystems without support for preallocation.
     ///
     /// # Examples
     ///
     /// ```no_run
+    /// #![feature(file_allocate)]
     /// use std::fs::File;
     ///
     /// # fn foo() -> std::io::Result<()> {
-    /// let mut f = File::open("foo.txt")?;
-    /// let metadata = f.metadata()?;
+    /// let log = File::create("wal.log")?;
+    /// log.allocate(0, 64 * 1024 * 1024)?;
     /// # Ok(())
     /// # }
     /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
-    pub fn metadata(&self) -> io::Result<Metadata> {
-        self.inner.file_attr().map(Metadata)
+    #[unstable(feature = "file_allocate", issue = "0")]
+    pub fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        self.inner.allocate(offset, len)
     }
 
-    /// Creates a new independently owned handle to the underlying file.
+    /// Deallocates the disk space of the `len` bytes of the file starting at
+    /// `offset`, which then read as zeros.
     ///
-    /// The returned `File` is a reference to the same state that this object
-    /// references. Both handles will read and write with the same cursor
-    /// position.
+    /// The size of the file is unchanged, even if the range extends beyond
+    /// its end.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `fallocate` with
+    /// `FALLOC_FL_PUNCH_HOLE` on Linux, and makes the file sparse before
+    /// zeroing the range with `FSCTL_SET_ZERO_DATA` on Windows. Note that,
+    /// this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file is not opened for
+    /// writing, or with the `Unsupported` kind on platforms and filesystems
+    /// without support for sparse files.
+    #[unstable(feature = "file_allocate", issue = "0")]
+    pub fn punch_hole(&self, offset: u64, len: u64) -> io::Result<()> {
+        self.inner.punch_hole(offset, len)
+    }
+
+    /// Returns an iterator over the regions of data and holes in the file.
+    ///
+    /// The regions are returned in order and cover the file up to the size
+    /// it had when this function was called. Where looking for holes isn't
+    /// supported, the whole file is reported as a single region of data.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently uses `lseek` with `SEEK_DATA` and `SEEK_HOLE`
+    /// on Linux, macOS, FreeBSD and Solaris. The cursor of the file is moved
+    /// while looking for a region and restored afterwards, which is why the
+    /// iterator borrows the file mutably. Handles sharing the cursor, such as
+    /// ones created by [`try_clone`], shouldn't be used to read or write
+    /// meanwhile. Note that, this [may change in the future][changes].
+    ///
+    /// [`try_clone`]: #method.try_clone
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
     ///
     /// # Examples
     ///
     /// ```no_run
+    /// #![feature(file_allocate)]
     /// use std::fs::File;
     ///
     /// # fn foo() -> std::io::Result<()> {
-    /// let mut f = File::open("foo.txt")?;
-    /// let file_copy = f.try_clone()?;
+    /// let mut file = File::open("disk.img")?;
+    /// for extent in file.extents()? {
+    ///     let extent = extent?;
+    ///     if !extent.is_hole() {
+    ///         println!("data at {}..{}", extent.offset(), extent.offset() + extent.len());
+    ///     }
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    #[stable(feature = "file_try_clone", since = "1.9.0")]
-    pub fn try_clone(&self) -> io::Result<File> {
-        Ok(File {
-            inner: self.inner.duplicate()?
-        })
+    #[unstable(feature = "file_allocate", issue = "0")]
+    pub fn extents(&mut self) -> io::Result<Extents> {
+        let end = self.metadata()?.len();
+        Ok(Extents { file: self, offset: 0, end: end, fallback: false })
     }
 
-    /// Changes the permissions on the underlying file.
+    /// Queries statistics about the filesystem containing this file.
+    ///
+    /// This function behaves like [`fs::space`], without resolving a path.
+    ///
+    /// [`fs::space`]: fn.space.html
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn fs_stats(&self) -> io::Result<FsStats> {
+        self.inner.fs_stats().map(FsStats)
+    }
+
+    /// Acquires an exclusive advisory lock on the file, blocking until it
+    /// can be acquired.
+    ///
+    /// While the lock is held, no other handle can acquire a shared or an
+    /// exclusive lock on the file. The lock is released by [`unlock`] or
+    /// once all handles sharing it (such as ones created by [`try_clone`])
+    /// have been closed.
+    ///
+    /// If this handle already holds a shared lock it is converted into an
+    /// exclusive one, although this is not guaranteed to happen atomically.
     ///
     /// # Platform-specific behavior
     ///
-    /// This function currently corresponds to the `fchmod` function on Unix and
-    /// the `SetFileInformationByHandle` function on Windows. Note that, this
-    /// [may change in the future][changes].
+    /// This function currently corresponds to the `flock` function on Unix
+    /// with the `LOCK_EX` flag, and the `LockFileEx` function on Windows with
+    /// the `LOCKFILE_EXCLUSIVE_LOCK` flag. On Solaris, which lacks `flock`,
+    /// a whole-file `fcntl` record lock is used instead, which only excludes
+    /// other processes. The locks are advisory on Unix, meaning that they
+    /// don't prevent reading or writing, but mandatory on Windows.
+    /// Note that this [may change in the future][changes].
     ///
     /// [changes]: ../io/index.html#platform-specific-behavior
+    /// [`unlock`]: #method.unlock
+    /// [`try_clone`]: #method.try_clone
     ///
-    /// # Errors
+    /// # Examples
     ///
-    /// This function will return an error if the user lacks permission change
-    /// attributes on the underlying file. It may also return an error in other
-    /// os-specific unspecified cases.
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::create("foo.lock")?;
+    /// f.lock_exclusive()?;
+    /// // ... the shared state is ours now ...
+    /// f.unlock()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.inner.lock_exclusive()
+    }
+
+    /// Acquires a shared advisory lock on the file, blocking until it can be
+    /// acquired.
+    ///
+    /// Any number of handles can hold a shared lock at the same time, but no
+    /// handle can acquire an exclusive lock while one of them does.
+    ///
+    /// See [`lock_exclusive`] for how locks are released and for the
+    /// platform-specific behavior.
+    ///
+    /// [`lock_exclusive`]: #method.lock_exclusive
     ///
     /// # Examples
     ///
-    /// ```
-    /// # fn foo() -> std::io::Result<()> {
+    /// ```no_run
+    /// #![feature(file_lock)]
     /// use std::fs::File;
+    /// use std::io::Read;
     ///
-    /// let file = File::open("foo.txt")?;
-    /// let mut perms = file.metadata()?.permissions();
-    /// perms.set_readonly(true);
-    /// file.set_permissions(perms)?;
+    /// # fn foo() -> std::io::Result<()> {
+    /// let mut f = File::open("cache.index")?;
+    /// f.lock_shared()?;
+    /// let mut index = Vec::new();
+    /// f.read_to_end(&mut index)?;
+    /// f.unlock()?;
     /// # Ok(())
     /// # }
     /// ```
-    #[stable(feature = "set_permissions_atomic", since = "1.16.0")]
-    pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
-        self.inner.set_permissions(perm.0)
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock_shared()
     }
-}
 
-impl AsInner<fs_imp::File> for File {
-    fn as_inner(&self) -> &fs_imp::File { &self.inner }
-}
-impl FromInner<fs_imp::File> for File {
-    fn from_inner(f: fs_imp::File) -> File {
-        File { inner: f }
-    }
-}
-impl IntoInner<fs_imp::File> for File {
+    /// Tries to acquire an exclusive advisory lock on the file without
+    /// blocking.
+    ///
+    /// This behaves like [`lock_exclusive`], but fails with an error of the
+    /// kind [`ErrorKind::WouldBlock`] if another handle holds a lock on the
+    /// file.
+    ///
+    /// [`lock_exclusive`]: #method.lock_exclusive
+    /// [`ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    /// use std::io::ErrorKind;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::create("foo.lock")?;
+    /// match f.try_lock_exclusive() {
+    ///     Ok(()) => println!("got the lock"),
+    ///     Err(ref e) if e.kind() == ErrorKind::WouldBlock => println!("busy"),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        self.inner.try_lock_exclusive()
+    }
+
+    /// Tries to acquire a shared advisory lock on the file without blocking.
+    ///
+    /// This behaves like [`lock_shared`], but fails with an error of the kind
+    /// [`ErrorKind::WouldBlock`] if another handle holds an exclusive lock on
+    /// the file.
+    ///
+    /// [`lock_shared`]: #method.lock_shared
+    /// [`ErrorKind::WouldBlock`]: ../io/enum.ErrorKind.html#variant.WouldBlock
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        self.inner.try_lock_shared()
+    }
+
+    /// Releases a lock held by this handle.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Unix this succeeds even if no lock is held. On Windows it fails
+    /// with an error in that case, and if a handle acquired several locks
+    /// (which is only possible there), each of them has to be released
+    /// separately. Note that this [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    #[unstable(feature = "file_lock", issue = "0")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
+
+    /// Queries metadata about the underlying file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let mut f = File::open("foo.txt")?;
+    /// let metadata = f.metadata()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.inner.file_attr().map(Metadata)
+    }
+
+    /// Creates a new independently owned handle to the underlying file.
+    ///
+    /// The returned `File` is a reference to the same state that this object
+    /// references. Both handles will read and write with the same cursor
+    /// position.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let mut f = File::open("foo.txt")?;
+    /// let file_copy = f.try_clone()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[stable(feature = "file_try_clone", since = "1.9.0")]
+    pub fn try_clone(&self) -> io::Result<File> {
+        Ok(File {
+            inner: self.inner.duplicate()?
+        })
+    }
+
+    /// Changes the permissions on the underlying file.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `fchmod` function on Unix and
+    /// the `SetFileInformationByHandle` function on Windows. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the user lacks permission change
+    /// attributes on the underlying file. It may also return an error in other
+    /// os-specific unspecified cases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn foo() -> std::io::Result<()> {
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("foo.txt")?;
+    /// let mut perms = file.metadata()?.permissions();
+    /// perms.set_readonly(true);
+    /// file.set_permissions(perms)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[stable(feature = "set_permissions_atomic", since = "1.16.0")]
+    pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
+        self.inner.set_permissions(perm.0)
+    }
+
+    /// Changes the timestamps of the underlying file.
+    ///
+    /// Only the timestamps set in `times` are changed, the others keep their
+    /// current value.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `futimens` function on Unix
+    /// (falling back to `futimes` on older macOS versions) and the
+    /// `SetFileTime` function on Windows. Note that, this [may change in the
+    /// future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the user lacks permission to
+    /// change timestamps on the underlying file, or if a timestamp can't be
+    /// represented by the platform. It may also return an error in other
+    /// os-specific unspecified cases.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_set_times)]
+    /// # fn foo() -> std::io::Result<()> {
+    /// use std::fs::{FileTimes, OpenOptions};
+    /// use std::time::SystemTime;
+    ///
+    /// let file = OpenOptions::new().write(true).open("foo.txt")?;
+    /// let times = FileTimes::new().set_modified(SystemTime::now());
+    /// file.set_times(times)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "file_set_times", issue = "0")]
+    pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
+        self.inner.set_times(times.0)
+    }
+}
+
+impl AsInner<fs_imp::File> for File {
+    fn as_inner(&self) -> &fs_imp::File { &self.inner }
+}
+impl FromInner<fs_imp::File> for File {
+    fn from_inner(f: fs_imp::File) -> File {
+        File { inner: f }
+    }
+}
+impl IntoInner<fs_imp::File> for File {
     fn into_inner(self) -> fs_imp::File {
         self.inner
     }
@@ -449,6 +1217,14 @@ impl Read for File {
         self.inner.read(buf)
     }
 
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> io::Result<()> {
+        self.inner.read_buf(buf)
+    }
+
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -459,6 +1235,11 @@ impl Write for File {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.inner.write(buf)
     }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
+
     fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -473,6 +1254,14 @@ impl<'a> Read for &'a File {
         self.inner.read(buf)
     }
 
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> io::Result<()> {
+        self.inner.read_buf(buf)
+    }
+
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -483,6 +1272,11 @@ impl<'a> Write for &'a File {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.inner.write(buf)
     }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
+
     fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -491,6 +1285,12 @@ impl<'a> Seek for &'a File {
         self.inner.seek(pos)
     }
 }
+#[unstable(feature = "is_terminal", issue = "0")]
+impl io::IsTerminal for File {
+    fn is_terminal(&self) -> bool {
+        self.inner.is_terminal()
+    }
+}
 
 impl OpenOptions {
     /// Creates a blank new set of options ready for configuration.
@@ -717,7 +1517,7 @@ impl OpenOptions {
     }
 
     fn _open(&self, path: &Path) -> io::Result<File> {
-        let inner = fs_imp::File::open(path, &self.0)?;
+        let inner = fs_imp::File::open(path, &self.0).map_err(|e| e.with_path("open", path))?;
         Ok(File { inner: inner })
     }
 }
@@ -885,12 +1685,15 @@ impl Metadata {
     /// Returns the creation time listed in the this metadata.
     ///
     /// The returned value corresponds to the `birthtime` field of `stat` on
-    /// Unix platforms and the `ftCreationTime` field on Windows platforms.
+    /// Unix platforms, the `stx_btime` field of `statx` on Linux and the
+    /// `ftCreationTime` field on Windows platforms.
     ///
     /// # Errors
     ///
     /// This field may not be available on all platforms, and will return an
-    /// `Err` on platforms where it is not available.
+    /// `Err` on platforms where it is not available. On Linux it requires
+    /// `statx` (Linux 4.11, glibc 2.28) and a filesystem recording the time
+    /// a file was created.
     ///
     /// # Examples
     ///
@@ -1214,6 +2017,192 @@ impl AsInner<fs_imp::DirEntry> for DirEntry {
     fn as_inner(&self) -> &fs_imp::DirEntry { &self.0 }
 }
 
+// Files report their size through their metadata, so reading the whole file
+// can allocate its buffer once up front instead of growing it repeatedly.
+// One extra byte is allocated so that the final `read` which observes EOF
+// doesn't force a reallocation either. `usize` overflow is not a concern, as
+// reading would fail regardless in that case.
+fn initial_buffer_size(file: &File) -> usize {
+    file.metadata().map(|m| m.len() as usize + 1).unwrap_or(0)
+}
+
+/// Read the entire contents of a file into a bytes vector.
+///
+/// This is a convenience function for using [`File::open`] and [`read_to_end`]
+/// with fewer imports and without an intermediate variable. It pre-allocates
+/// a buffer based on the file size when available, so it is generally faster
+/// than reading into a vector created with `Vec::new()`.
+///
+/// [`File::open`]: struct.File.html#method.open
+/// [`read_to_end`]: ../io/trait.Read.html#method.read_to_end
+///
+/// # Errors
+///
+/// This function will return an error if `path` does not already exist.
+/// Other errors may also be returned according to [`OpenOptions::open`].
+///
+/// [`OpenOptions::open`]: struct.OpenOptions.html#method.open
+///
+/// It will also return an error if it encounters while reading an error
+/// of a kind other than [`ErrorKind::Interrupted`].
+///
+/// In all cases the message of the returned error names `path`, and its
+/// [`kind`] is that of the underlying error.
+///
+/// [`ErrorKind::Interrupted`]: ../../std/io/enum.ErrorKind.html#variant.Interrupted
+/// [`kind`]: ../../std/io/struct.Error.html#method.kind
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_read_write)]
+///
+/// use std::fs;
+/// use std::net::SocketAddr;
+///
+/// # fn foo() -> Result<(), Box<std::error::Error + 'static>> {
+/// let foo: SocketAddr = String::from_utf8_lossy(&fs::read("address.txt")?).parse()?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "fs_read_write", issue = "46588")]
+pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Vec<u8>> {
+    fn inner(path: &Path) -> io::Result<Vec<u8>> {
+        let mut file = File::open(path)?;
+        let mut bytes = Vec::with_capacity(initial_buffer_size(&file));
+        file.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+    let path = path.as_ref();
+    inner(path).map_err(|e| e.with_path("read", path))
+}
+
+/// Read the entire contents of a file into a string.
+///
+/// This is a convenience function for using [`File::open`] and [`read_to_string`]
+/// with fewer imports and without an intermediate variable. It pre-allocates
+/// a buffer based on the file size when available, so it is generally faster
+/// than reading into a string created with `String::new()`.
+///
+/// [`File::open`]: struct.File.html#method.open
+/// [`read_to_string`]: ../io/trait.Read.html#method.read_to_string
+///
+/// # Errors
+///
+/// This function will return an error if `path` does not already exist.
+/// Other errors may also be returned according to [`OpenOptions::open`].
+///
+/// [`OpenOptions::open`]: struct.OpenOptions.html#method.open
+///
+/// It will also return an error if it encounters while reading an error
+/// of a kind other than [`ErrorKind::Interrupted`],
+/// or if the contents of the file are not valid UTF-8.
+///
+/// In all cases the message of the returned error names `path`, and its
+/// [`kind`] is that of the underlying error.
+///
+/// [`ErrorKind::Interrupted`]: ../../std/io/enum.ErrorKind.html#variant.Interrupted
+/// [`kind`]: ../../std/io/struct.Error.html#method.kind
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_read_write)]
+///
+/// use std::fs;
+/// use std::net::SocketAddr;
+///
+/// # fn foo() -> Result<(), Box<std::error::Error + 'static>> {
+/// let foo: SocketAddr = fs::read_to_string("address.txt")?.parse()?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "fs_read_write", issue = "46588")]
+pub fn read_to_string<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    fn inner(path: &Path) -> io::Result<String> {
+        let mut file = File::open(path)?;
+        let mut string = String::with_capacity(initial_buffer_size(&file));
+        file.read_to_string(&mut string)?;
+        Ok(string)
+    }
+    let path = path.as_ref();
+    inner(path).map_err(|e| e.with_path("read", path))
+}
+
+/// Write a slice as the entire contents of a file.
+///
+/// This function will create a file if it does not exist,
+/// and will entirely replace its contents if it does.
+///
+/// This is a convenience function for using [`File::create`] and [`write_all`]
+/// with fewer imports.
+///
+/// [`File::create`]: struct.File.html#method.create
+/// [`write_all`]: ../io/trait.Write.html#method.write_all
+///
+/// # Errors
+///
+/// This function will return an error in the same situations as
+/// [`File::create`] and [`write_all`]. The message of the returned error
+/// names `path`, and its [`kind`] is that of the underlying error.
+///
+/// [`kind`]: ../../std/io/struct.Error.html#method.kind
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_read_write)]
+///
+/// use std::fs;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// fs::write("foo.txt", b"Lorem ipsum")?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "fs_read_write", issue = "46588")]
+pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    fn inner(path: &Path, contents: &[u8]) -> io::Result<()> {
+        File::create(path)?.write_all(contents)
+    }
+    let path = path.as_ref();
+    inner(path, contents.as_ref()).map_err(|e| e.with_path("write", path))
+}
+
+/// Atomically replaces the contents of a file with a slice.
+///
+/// Unlike [`write`], this never leaves a truncated or partially written file
+/// behind, even if the system crashes: the file at `path` keeps its previous
+/// contents until the new ones are completely on the disk. See
+/// [`AtomicWriter`] for details.
+///
+/// [`write`]: fn.write.html
+/// [`AtomicWriter`]: struct.AtomicWriter.html
+///
+/// # Errors
+///
+/// This function will return an error if the temporary file can't be
+/// created, written or renamed over `path`.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(atomic_write)]
+/// use std::fs;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// fs::write_atomic("app.conf", b"threads = 4\n")?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "atomic_write", issue = "0")]
+pub fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut writer = AtomicWriter::new(path)?;
+    writer.write_all(contents.as_ref()).map_err(|e| e.with_path("atomically write", path))?;
+    writer.commit()
+}
+
 /// Removes a file from the filesystem.
 ///
 /// Note that there is no
@@ -1248,7 +2237,8 @@ impl AsInner<fs_imp::DirEntry> for DirEntry {
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn remove_file<P: AsRef<Path>>(path: P) -> io::Result<()> {
-    fs_imp::unlink(path.as_ref())
+    let path = path.as_ref();
+    fs_imp::unlink(path).map_err(|e| e.with_path("remove file", path))
 }
 
 /// Given a path, query the file system to get information about a file,
@@ -1286,44 +2276,84 @@ pub fn remove_file<P: AsRef<Path>>(path: P) -> io::Result<()> {
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
-    fs_imp::stat(path.as_ref()).map(Metadata)
+    let path = path.as_ref();
+    fs_imp::stat(path).map(Metadata).map_err(|e| e.with_path("get metadata for", path))
 }
 
-/// Query the metadata about a file without following symlinks.
+/// Queries statistics about the filesystem containing `path`, such as its
+/// size and the space left on it.
 ///
 /// # Platform-specific behavior
 ///
-/// This function currently corresponds to the `lstat` function on Unix
-/// and the `GetFileAttributesEx` function on Windows.
-/// Note that, this [may change in the future][changes].
+/// This function currently corresponds to the `statvfs` function on Unix,
+/// and to `GetDiskFreeSpaceExW` and `GetVolumeInformationW` on the volume
+/// containing `path` on Windows. Note that, this [may change in the
+/// future][changes].
 ///
 /// [changes]: ../io/index.html#platform-specific-behavior
 ///
 /// # Errors
 ///
-/// This function will return an error in the following situations, but is not
-/// limited to just these cases:
-///
-/// * The user lacks permissions to perform `metadata` call on `path`.
-/// * `path` does not exist.
+/// This function will return an error if `path` does not exist or the user
+/// lacks permissions to access it.
 ///
 /// # Examples
 ///
-/// ```rust
-/// # fn foo() -> std::io::Result<()> {
+/// ```no_run
+/// #![feature(fs_stats)]
 /// use std::fs;
 ///
-/// let attr = fs::symlink_metadata("/some/file/path.txt")?;
-/// // inspect attr ...
+/// # fn foo() -> std::io::Result<()> {
+/// let stats = fs::space("/var/spool/jobs")?;
+/// if stats.available_space() < 10 * 1024 * 1024 * 1024 {
+///     println!("less than 10 GiB left, not starting");
+/// }
 /// # Ok(())
 /// # }
 /// ```
-#[stable(feature = "symlink_metadata", since = "1.1.0")]
-pub fn symlink_metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
-    fs_imp::lstat(path.as_ref()).map(Metadata)
+#[unstable(feature = "fs_stats", issue = "0")]
+pub fn space<P: AsRef<Path>>(path: P) -> io::Result<FsStats> {
+    let path = path.as_ref();
+    fs_imp::fs_stats(path).map(FsStats)
+        .map_err(|e| e.with_path("get filesystem statistics for", path))
 }
 
-/// Rename a file or directory to a new name, replacing the original file if
+/// Query the metadata about a file without following symlinks.
+///
+/// # Platform-specific behavior
+///
+/// This function currently corresponds to the `lstat` function on Unix
+/// and the `GetFileAttributesEx` function on Windows.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * The user lacks permissions to perform `metadata` call on `path`.
+/// * `path` does not exist.
+///
+/// # Examples
+///
+/// ```rust
+/// # fn foo() -> std::io::Result<()> {
+/// use std::fs;
+///
+/// let attr = fs::symlink_metadata("/some/file/path.txt")?;
+/// // inspect attr ...
+/// # Ok(())
+/// # }
+/// ```
+#[stable(feature = "symlink_metadata", since = "1.1.0")]
+pub fn symlink_metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
+    let path = path.as_ref();
+    fs_imp::lstat(path).map(Metadata).map_err(|e| e.with_path("get symlink metadata for", path))
+}
+
+/// Rename a file or directory to a new name, replacing the original file if
 /// `to` already exists.
 ///
 /// This will not work if the new name is on a different mount point.
@@ -1363,7 +2393,114 @@ pub fn symlink_metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
-    fs_imp::rename(from.as_ref(), to.as_ref())
+    let (from, to) = (from.as_ref(), to.as_ref());
+    fs_imp::rename(from, to).map_err(|e| e.with_paths("rename", from, to))
+}
+
+/// Rename a file or directory to a new name, failing if `to` already exists.
+///
+/// The check and the rename happen atomically, so unlike checking whether
+/// `to` exists before calling [`rename`], this can be used to publish a file
+/// only if no other process has done so yet.
+///
+/// This will not work if the new name is on a different mount point.
+///
+/// [`rename`]: fn.rename.html
+///
+/// # Platform-specific behavior
+///
+/// This function currently corresponds to the `renameat2` function with the
+/// `RENAME_NOREPLACE` flag on Linux, `renamex_np` with `RENAME_EXCL` on macOS
+/// and the `MoveFileEx` function without flags on Windows. It is not
+/// supported on other platforms.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * `to` already exists, with an error of kind [`AlreadyExists`].
+/// * The platform, or the filesystem holding the files, doesn't support this
+///   operation, with an error of kind [`Unsupported`] (or [`InvalidInput`]
+///   for filesystems on Linux).
+/// * `from` does not exist.
+/// * The user lacks permissions to view contents.
+/// * `from` and `to` are on separate filesystems.
+///
+/// [`AlreadyExists`]: ../io/enum.ErrorKind.html#variant.AlreadyExists
+/// [`Unsupported`]: ../io/enum.ErrorKind.html#variant.Unsupported
+/// [`InvalidInput`]: ../io/enum.ErrorKind.html#variant.InvalidInput
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(rename_variants)]
+/// use std::fs;
+/// use std::io::ErrorKind;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// match fs::rename_noreplace("draft.txt", "published.txt") {
+///     Ok(()) => println!("published"),
+///     Err(ref e) if e.kind() == ErrorKind::AlreadyExists => println!("already published"),
+///     Err(e) => return Err(e),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "rename_variants", issue = "0")]
+pub fn rename_noreplace<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    fs_imp::rename_noreplace(from, to).map_err(|e| e.with_paths("rename", from, to))
+}
+
+/// Atomically exchanges the files or directories at `a` and `b`.
+///
+/// Both paths must exist, and may be of different types. No process ever
+/// observes either path missing or both naming the same file.
+///
+/// # Platform-specific behavior
+///
+/// This function currently corresponds to the `renameat2` function with the
+/// `RENAME_EXCHANGE` flag on Linux and `renamex_np` with `RENAME_SWAP` on
+/// macOS. It is not supported on other platforms.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * The platform, or the filesystem holding the files, doesn't support this
+///   operation, with an error of kind [`Unsupported`] (or [`InvalidInput`]
+///   for filesystems on Linux).
+/// * `a` or `b` does not exist.
+/// * The user lacks permissions to view contents.
+/// * `a` and `b` are on separate filesystems.
+///
+/// [`Unsupported`]: ../io/enum.ErrorKind.html#variant.Unsupported
+/// [`InvalidInput`]: ../io/enum.ErrorKind.html#variant.InvalidInput
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(rename_variants)]
+/// use std::fs;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// // Switch to the new configuration directory, keeping the old one around.
+/// fs::rename_exchange("config", "config.new")?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "rename_variants", issue = "0")]
+pub fn rename_exchange<P: AsRef<Path>, Q: AsRef<Path>>(a: P, b: Q) -> io::Result<()> {
+    let (a, b) = (a.as_ref(), b.as_ref());
+    fs_imp::rename_exchange(a, b).map_err(|e| e.with_paths("exchange", a, b))
 }
 
 /// Copies the contents of one file to another. This function will also
@@ -1382,6 +2519,9 @@ pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()>
 /// This function currently corresponds to the `open` function in Unix
 /// with `O_RDONLY` for `from` and `O_WRONLY`, `O_CREAT`, and `O_TRUNC` for `to`.
 /// `O_CLOEXEC` is set for returned file descriptors.
+/// On Linux the data is then copied inside the kernel with `copy_file_range`,
+/// falling back to `sendfile` and finally to a userspace copy if the
+/// filesystems involved don't support it.
 /// On Windows, this function currently corresponds to `CopyFileEx`. Alternate
 /// NTFS streams are copied but only the size of the main stream is returned by
 /// this function.
@@ -1410,7 +2550,134 @@ pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()>
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<u64> {
-    fs_imp::copy(from.as_ref(), to.as_ref())
+    let (from, to) = (from.as_ref(), to.as_ref());
+    fs_imp::copy(from, to).map_err(|e| e.with_paths("copy", from, to))
+}
+
+impl CopyOptions {
+    /// Creates a blank new set of options, which copy like [`fs::copy`].
+    ///
+    /// [`fs::copy`]: fn.copy.html
+    #[unstable(feature = "copy_options", issue = "0")]
+    pub fn new() -> CopyOptions {
+        CopyOptions(fs_imp::CopyOptions::new())
+    }
+
+    /// Sets whether the copy shares its data with the source, see
+    /// [`Reflink`] for the possible values.
+    ///
+    /// This defaults to `Reflink::Never`.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// Reflinks are currently made with the `FICLONE` ioctl on Linux and are
+    /// not supported on other platforms, where `Reflink::Auto` always copies
+    /// the data and `Reflink::Always` makes [`copy`] fail.
+    ///
+    /// [`Reflink`]: enum.Reflink.html
+    /// [`copy`]: #method.copy
+    #[unstable(feature = "copy_options", issue = "0")]
+    pub fn reflink(&mut self, reflink: Reflink) -> &mut CopyOptions {
+        self.0.reflink(reflink); self
+    }
+
+    /// Sets whether an existing destination is replaced.
+    ///
+    /// If this is `false`, [`copy`] fails with an error of kind
+    /// [`AlreadyExists`] if the destination exists, without modifying it.
+    ///
+    /// This defaults to `true`.
+    ///
+    /// [`copy`]: #method.copy
+    /// [`AlreadyExists`]: ../io/enum.ErrorKind.html#variant.AlreadyExists
+    #[unstable(feature = "copy_options", issue = "0")]
+    pub fn overwrite(&mut self, overwrite: bool) -> &mut CopyOptions {
+        self.0.overwrite(overwrite); self
+    }
+
+    /// Sets whether a symbolic link is copied as a symbolic link pointing to
+    /// the same target, instead of copying the file it points to.
+    ///
+    /// This defaults to `false`.
+    #[unstable(feature = "copy_options", issue = "0")]
+    pub fn copy_symlinks(&mut self, copy_symlinks: bool) -> &mut CopyOptions {
+        self.0.copy_symlinks(copy_symlinks); self
+    }
+
+    /// Sets whether the last access and modification times of the source are
+    /// given to the copy.
+    ///
+    /// This defaults to `false`.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// Windows always copies the timestamps.
+    #[unstable(feature = "copy_options", issue = "0")]
+    pub fn preserve_timestamps(&mut self, preserve: bool) -> &mut CopyOptions {
+        self.0.preserve_timestamps(preserve); self
+    }
+
+    /// Sets whether the owner and group of the source are given to the copy.
+    ///
+    /// Changing the owner of a file usually requires elevated privileges.
+    ///
+    /// This defaults to `false`.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This is currently only supported on Unix, other platforms make
+    /// [`copy`] fail if it is set.
+    ///
+    /// [`copy`]: #method.copy
+    #[unstable(feature = "copy_options", issue = "0")]
+    pub fn preserve_ownership(&mut self, preserve: bool) -> &mut CopyOptions {
+        self.0.preserve_ownership(preserve); self
+    }
+
+    /// Sets whether the extended attributes of the source are copied.
+    ///
+    /// This defaults to `false`.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This is currently supported on Linux and macOS, and ignored for
+    /// symbolic links copied with [`copy_symlinks`]. Windows always copies
+    /// extended attributes and alternate data streams, and other platforms
+    /// make [`copy`] fail if it is set.
+    ///
+    /// [`copy_symlinks`]: #method.copy_symlinks
+    /// [`copy`]: #method.copy
+    #[unstable(feature = "copy_options", issue = "0")]
+    pub fn preserve_xattrs(&mut self, preserve: bool) -> &mut CopyOptions {
+        self.0.preserve_xattrs(preserve); self
+    }
+
+    /// Copies `from` to `to` with the options specified by `self`.
+    ///
+    /// On success, the number of bytes copied is returned. Symbolic links
+    /// copied as links count as zero bytes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error in the following situations, but
+    /// is not limited to just these cases:
+    ///
+    /// * The `from` path is not a file (or a symbolic link, when copying
+    ///   symbolic links as such).
+    /// * The `to` path exists and [`overwrite`] is `false`.
+    /// * An option was set that isn't supported on this platform.
+    /// * The current process does not have the permission rights to access
+    ///   `from` or write `to`, or to change the metadata of `to`.
+    ///
+    /// If an error occurs after the destination was opened, it may have been
+    /// created or truncated.
+    ///
+    /// [`overwrite`]: #method.overwrite
+    #[unstable(feature = "copy_options", issue = "0")]
+    pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to: Q) -> io::Result<u64> {
+        let (from, to) = (from.as_ref(), to.as_ref());
+        fs_imp::copy_with(from, to, &self.0).map_err(|e| e.with_paths("copy", from, to))
+    }
 }
 
 /// Creates a new hard link on the filesystem.
@@ -1445,7 +2712,8 @@ pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<u64> {
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn hard_link<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()> {
-    fs_imp::link(src.as_ref(), dst.as_ref())
+    let (src, dst) = (src.as_ref(), dst.as_ref());
+    fs_imp::link(src, dst).map_err(|e| e.with_paths("hard link", src, dst))
 }
 
 /// Creates a new symbolic link on the filesystem.
@@ -1471,7 +2739,8 @@ pub fn hard_link<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<(
              reason = "replaced with std::os::unix::fs::symlink and \
                        std::os::windows::fs::{symlink_file, symlink_dir}")]
 pub fn soft_link<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()> {
-    fs_imp::symlink(src.as_ref(), dst.as_ref())
+    let (src, dst) = (src.as_ref(), dst.as_ref());
+    fs_imp::symlink(src, dst).map_err(|e| e.with_paths("symlink", src, dst))
 }
 
 /// Reads a symbolic link, returning the file that the link points to.
@@ -1505,7 +2774,8 @@ pub fn soft_link<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<(
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn read_link<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
-    fs_imp::readlink(path.as_ref())
+    let path = path.as_ref();
+    fs_imp::readlink(path).map_err(|e| e.with_path("read link", path))
 }
 
 /// Returns the canonical form of a path with all intermediate components
@@ -1539,7 +2809,8 @@ pub fn read_link<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
 /// ```
 #[stable(feature = "fs_canonicalize", since = "1.5.0")]
 pub fn canonicalize<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
-    fs_imp::canonicalize(path.as_ref())
+    let path = path.as_ref();
+    fs_imp::canonicalize(path).map_err(|e| e.with_path("canonicalize", path))
 }
 
 /// Creates a new, empty directory at the provided path
@@ -1647,7 +2918,8 @@ pub fn create_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn remove_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
-    fs_imp::rmdir(path.as_ref())
+    let path = path.as_ref();
+    fs_imp::rmdir(path).map_err(|e| e.with_path("remove directory", path))
 }
 
 /// Removes a directory at this path, after removing all its contents. Use
@@ -1681,7 +2953,8 @@ pub fn remove_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn remove_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
-    fs_imp::remove_dir_all(path.as_ref())
+    let path = path.as_ref();
+    fs_imp::remove_dir_all(path).map_err(|e| e.with_path("remove directory tree", path))
 }
 
 /// Returns an iterator over the entries within a directory.
@@ -1734,150 +3007,1644 @@ pub fn remove_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<ReadDir> {
-    fs_imp::readdir(path.as_ref()).map(ReadDir)
+    let path = path.as_ref();
+    fs_imp::readdir(path).map(ReadDir).map_err(|e| e.with_path("read directory", path))
 }
 
-/// Changes the permissions found on a file or a directory.
+/// Returns an iterator over the entries within a directory and, recursively,
+/// all of its subdirectories.
 ///
-/// # Platform-specific behavior
+/// The iterator yields instances of [`io::Result`]`<`[`WalkDirEntry`]`>`. The
+/// entries of each directory are yielded before those of the next one in the
+/// same directory, and the directory `root` itself is not yielded: its direct
+/// entries are at depth 1, their entries at depth 2, and so on. By default
+/// directories are yielded before their contents, symbolic links aren't
+/// followed and entries are yielded in the order they're read from the
+/// filesystem, which the methods of [`WalkDir`] can change before the walk
+/// starts.
 ///
-/// This function currently corresponds to the `chmod` function on Unix
-/// and the `SetFileAttributes` function on Windows.
-/// Note that, this [may change in the future][changes].
+/// Errors don't end the walk. Failing to read a directory or an entry yields
+/// an `Err` carrying the path involved, after which the walk goes on with
+/// the next entry. Only an error reading `root` itself is necessarily the
+/// last item.
 ///
-/// [changes]: ../io/index.html#platform-specific-behavior
+/// [`io::Result`]: ../io/type.Result.html
+/// [`WalkDirEntry`]: struct.WalkDirEntry.html
+/// [`WalkDir`]: struct.WalkDir.html
 ///
-/// # Errors
+/// # Platform-specific behavior
 ///
-/// This function will return an error in the following situations, but is not
-/// limited to just these cases:
+/// Loops through symbolic links are detected by comparing the device and
+/// inode numbers of directories on Unix, and their canonical paths on other
+/// platforms. Note that, this [may change in the future][changes].
 ///
-/// * `path` does not exist.
-/// * The user lacks the permission to change attributes of the file.
+/// [changes]: ../io/index.html#platform-specific-behavior
 ///
 /// # Examples
 ///
-/// ```
-/// # fn foo() -> std::io::Result<()> {
+/// ```no_run
+/// #![feature(walk_dir)]
 /// use std::fs;
 ///
-/// let mut perms = fs::metadata("foo.txt")?.permissions();
-/// perms.set_readonly(true);
-/// fs::set_permissions("foo.txt", perms)?;
+/// # fn foo() -> std::io::Result<()> {
+/// // Lists the Rust sources below `src`, without looking into `target`
+/// // directories and in a stable order.
+/// let mut walk = fs::walk_dir("src").sort_by_file_name();
+/// while let Some(entry) = walk.next() {
+///     let entry = entry?;
+///     if entry.file_type().is_dir() && entry.file_name() == *"target" {
+///         walk.skip_current_dir();
+///     } else if entry.path().extension().map_or(false, |ext| ext == "rs") {
+///         println!("{}{}", "  ".repeat(entry.depth() - 1), entry.path().display());
+///     }
+/// }
 /// # Ok(())
 /// # }
 /// ```
-#[stable(feature = "set_permissions", since = "1.1.0")]
-pub fn set_permissions<P: AsRef<Path>>(path: P, perm: Permissions)
-                                       -> io::Result<()> {
-    fs_imp::set_perm(path.as_ref(), perm.0)
+#[unstable(feature = "walk_dir", issue = "0")]
+pub fn walk_dir<P: AsRef<Path>>(root: P) -> WalkDir {
+    WalkDir {
+        root: root.as_ref().to_path_buf(),
+        min_depth: 1,
+        max_depth: usize::max_value(),
+        follow_links: false,
+        contents_first: false,
+        sorter: None,
+        started: false,
+        stack: Vec::new(),
+        pending: None,
+        queued: None,
+    }
 }
 
-impl DirBuilder {
-    /// Creates a new set of options with default mode/security settings for all
-    /// platforms and also non-recursive.
-    ///
-    /// # Examples
+// Identifies a directory for loop detection.
+#[cfg(unix)]
+type DirId = (u64, u64);
+#[cfg(not(unix))]
+type DirId = PathBuf;
+
+#[cfg(unix)]
+fn dir_id(path: &Path) -> io::Result<DirId> {
+    use os::unix::fs::MetadataExt;
+    let metadata = metadata(path)?;
+    Ok((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_id(path: &Path) -> io::Result<DirId> {
+    canonicalize(path)
+}
+
+impl WalkDir {
+    /// Sets the minimum depth of the entries which are yielded.
     ///
-    /// ```
-    /// use std::fs::DirBuilder;
+    /// Entries of directories above this depth are still read, but not
+    /// yielded themselves. The entries of `root` are at depth 1, which is
+    /// also the default.
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn min_depth(mut self, depth: usize) -> WalkDir {
+        self.min_depth = depth;
+        self
+    }
+
+    /// Sets the maximum depth of the entries which are yielded.
     ///
-    /// let builder = DirBuilder::new();
-    /// ```
-    #[stable(feature = "dir_builder", since = "1.6.0")]
-    pub fn new() -> DirBuilder {
-        DirBuilder {
-            inner: fs_imp::DirBuilder::new(),
-            recursive: false,
-        }
+    /// Directories at this depth are yielded but not read. A maximum depth of
+    /// 0 yields nothing at all, and there is no limit by default.
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn max_depth(mut self, depth: usize) -> WalkDir {
+        self.max_depth = depth;
+        self
     }
 
-    /// Indicates that directories should be created recursively, creating all
-    /// parent directories. Parents that do not exist are created with the same
-    /// security and permissions settings.
+    /// Sets whether symbolic links are followed.
     ///
-    /// This option defaults to `false`.
+    /// When following symbolic links, a link to a directory is walked like the
+    /// directory itself and yielded with the [`file_type`] of its target.
+    /// Links leading back to one of the directories currently being walked
+    /// are yielded, but not followed: an error is yielded in place of their
+    /// contents. Broken links are yielded as symbolic links.
     ///
-    /// # Examples
+    /// Regardless of this setting, `root` itself is always followed if it is
+    /// a symbolic link. This defaults to `false`.
     ///
-    /// ```
-    /// use std::fs::DirBuilder;
+    /// [`file_type`]: struct.WalkDirEntry.html#method.file_type
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn follow_links(mut self, follow: bool) -> WalkDir {
+        self.follow_links = follow;
+        self
+    }
+
+    /// Sets whether the contents of a directory are yielded before the
+    /// directory itself (post-order) instead of after it (pre-order).
     ///
-    /// let mut builder = DirBuilder::new();
-    /// builder.recursive(true);
-    /// ```
-    #[stable(feature = "dir_builder", since = "1.6.0")]
-    pub fn recursive(&mut self, recursive: bool) -> &mut Self {
-        self.recursive = recursive;
+    /// This is useful to remove a directory tree, for example. This defaults
+    /// to `false`.
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn contents_first(mut self, contents_first: bool) -> WalkDir {
+        self.contents_first = contents_first;
         self
     }
 
-    /// Create the specified directory with the options configured in this
-    /// builder.
+    /// Sorts the entries of each directory with the comparison function
+    /// `cmp` before yielding them.
+    ///
+    /// Sorting requires reading a whole directory before yielding any of its
+    /// entries. Errors reading an entry are yielded before the entries of the
+    /// same directory.
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn sort_by<F>(mut self, cmp: F) -> WalkDir
+        where F: FnMut(&DirEntry, &DirEntry) -> Ordering + Send + Sync + 'static
+    {
+        self.sorter = Some(Box::new(cmp));
+        self
+    }
+
+    /// Sorts the entries of each directory by their file name before yielding
+    /// them.
     ///
-    /// It is considered an error if the directory already exists unless
-    /// recursive mode is enabled.
+    /// See [`sort_by`] for details.
+    ///
+    /// [`sort_by`]: #method.sort_by
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn sort_by_file_name(self) -> WalkDir {
+        self.sort_by(|a, b| a.file_name().cmp(&b.file_name()))
+    }
+
+    /// Skips the contents of the directory most recently yielded, or of the
+    /// directory containing the entry most recently yielded if that wasn't a
+    /// directory whose contents are yet to come.
+    ///
+    /// In other words, calling this right after a directory was yielded
+    /// prevents the walk from descending into it, and calling it after a file
+    /// was yielded skips the remaining entries next to that file. In
+    /// contents-first order, a directory whose remaining contents are skipped
+    /// is still yielded.
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn skip_current_dir(&mut self) {
+        if self.pending.take().is_some() {
+            return
+        }
+        if let Some(frame) = self.stack.pop() {
+            if self.queued.is_none() {
+                self.queued = frame.entry;
+            }
+        }
+    }
+
+    fn open_dir(&mut self, path: &Path) -> io::Result<(WalkList, Option<DirId>)> {
+        let id = if self.follow_links {
+            let id = dir_id(path)?;
+            if self.stack.iter().any(|frame| frame.id.as_ref() == Some(&id)) {
+                return Err(io::Error::new(io::ErrorKind::Other, "filesystem loop detected")
+                           .with_path("walk directory", path))
+            }
+            Some(id)
+        } else {
+            None
+        };
+        let dir = read_dir(path)?;
+        let list = match self.sorter {
+            Some(ref mut cmp) => {
+                let mut entries = dir.collect::<Vec<_>>();
+                entries.sort_by(|a, b| match (a, b) {
+                    (&Ok(ref a), &Ok(ref b)) => cmp(a, b),
+                    (&Ok(_), &Err(_)) => Ordering::Greater,
+                    (&Err(_), &Ok(_)) => Ordering::Less,
+                    (&Err(_), &Err(_)) => Ordering::Equal,
+                });
+                WalkList::Sorted(entries.into_iter())
+            }
+            None => WalkList::Unsorted(dir),
+        };
+        Ok((list, id))
+    }
+
+    fn push_dir(&mut self, path: PathBuf, entry: Option<WalkDirEntry>) -> io::Result<()> {
+        match self.open_dir(&path) {
+            Ok((list, id)) => {
+                self.stack.push(WalkFrame { path: path, list: list, id: id, entry: entry });
+                Ok(())
+            }
+            Err(e) => {
+                // The directory itself is still yielded in contents-first
+                // order, right after the error.
+                self.queued = entry;
+                Err(e)
+            }
+        }
+    }
+
+    fn make_entry(&self, entry: DirEntry, depth: usize) -> io::Result<WalkDirEntry> {
+        let mut file_type = entry.file_type()
+            .map_err(|e| e.with_path("get file type of", &entry.path()))?;
+        let mut followed = false;
+        if file_type.is_symlink() && self.follow_links {
+            if let Ok(target) = metadata(entry.path()) {
+                file_type = target.file_type();
+                followed = true;
+            }
+        }
+        Ok(WalkDirEntry { entry: entry, file_type: file_type, depth: depth, followed: followed })
+    }
+}
+
+#[unstable(feature = "walk_dir", issue = "0")]
+impl Iterator for WalkDir {
+    type Item = io::Result<WalkDirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<WalkDirEntry>> {
+        if !self.started {
+            self.started = true;
+            if self.max_depth == 0 {
+                return None
+            }
+            let root = self.root.clone();
+            if let Err(e) = self.push_dir(root, None) {
+                return Some(Err(e))
+            }
+        }
+
+        loop {
+            if let Some(entry) = self.queued.take() {
+                return Some(Ok(entry))
+            }
+            if let Some(dir) = self.pending.take() {
+                if let Err(e) = self.push_dir(dir.path, dir.entry) {
+                    return Some(Err(e))
+                }
+            }
+
+            // Entries of the innermost directory are one level deeper than
+            // the number of directories being read.
+            let depth = self.stack.len();
+            let next = match self.stack.last_mut() {
+                Some(frame) => match frame.list {
+                    WalkList::Unsorted(ref mut dir) => dir.next(),
+                    WalkList::Sorted(ref mut entries) => entries.next(),
+                }.map(|next| next.map_err(|e| e.with_path("read directory", &frame.path))),
+                None => return None,
+            };
+            let entry = match next {
+                Some(Ok(entry)) => entry,
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.queued = self.stack.pop().and_then(|frame| frame.entry);
+                    continue
+                }
+            };
+            let entry = match self.make_entry(entry, depth) {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let yielded = depth >= self.min_depth;
+            if entry.file_type.is_dir() && depth < self.max_depth {
+                let path = entry.path();
+                if self.contents_first {
+                    let entry = if yielded { Some(entry) } else { None };
+                    self.pending = Some(PendingDir { path: path, entry: entry });
+                    continue
+                }
+                self.pending = Some(PendingDir { path: path, entry: None });
+            }
+            if yielded {
+                return Some(Ok(entry))
+            }
+        }
+    }
+}
+
+#[unstable(feature = "walk_dir", issue = "0")]
+impl fmt::Debug for WalkDir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WalkDir")
+            .field("root", &self.root)
+            .field("min_depth", &self.min_depth)
+            .field("max_depth", &self.max_depth)
+            .field("follow_links", &self.follow_links)
+            .field("contents_first", &self.contents_first)
+            .field("sorted", &self.sorter.is_some())
+            .finish()
+    }
+}
+
+// Tries names until one that doesn't exist yet is found, `create` failing
+// with `AlreadyExists` for names which are taken.
+fn create_temp<T, F>(dir: &Path, mut create: F) -> io::Result<T>
+    where F: FnMut(PathBuf) -> io::Result<T>
+{
+    // Mixed into the random part of names, in case the platform doesn't
+    // provide any randomness.
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    const ATTEMPTS: usize = 1 << 16;
+
+    for _ in 0..ATTEMPTS {
+        let (k0, k1) = sys::hashmap_random_keys();
+        let n = COUNTER.fetch_add(1, AtomicOrdering::Relaxed) as u64;
+        let name = format!(".tmp{:016x}", k0 ^ k1.wrapping_add(n));
+        match create(dir.join(name)) {
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            result => return result,
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, "too many temporary files exist"))
+}
+
+impl TempFile {
+    /// Creates a new temporary file in [`env::temp_dir()`].
+    ///
+    /// [`env::temp_dir()`]: ../env/fn.temp_dir.html
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be created.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn new() -> io::Result<TempFile> {
+        TempFile::new_in(env::temp_dir())
+    }
+
+    /// Creates a new temporary file in `dir`.
+    ///
+    /// Creating the temporary file in the directory it will be persisted to
+    /// makes sure that [`persist`] can rename it there.
+    ///
+    /// [`persist`]: #method.persist
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// The file is created with `O_EXCL` and mode `0o600` on Unix, and
+    /// `CREATE_NEW` on Windows. Note that, this [may change in the
+    /// future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be created.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn new_in<P: AsRef<Path>>(dir: P) -> io::Result<TempFile> {
+        let dir = dir.as_ref();
+        TempFile::create_in(dir, &temp_file_options(false))
+            .map_err(|e| e.with_path("create temporary file in", dir))
+    }
+
+    fn create_in(dir: &Path, opts: &OpenOptions) -> io::Result<TempFile> {
+        create_temp(dir, |path| {
+            let file = opts.open(&path)?;
+            Ok(TempFile { file: file, path: TempPath(path) })
+        })
+    }
+
+    /// Creates a new temporary file in [`env::temp_dir()`] which never has a
+    /// name, or has one only briefly.
+    ///
+    /// As the file is anonymous it can't be persisted, and it is removed as
+    /// soon as the returned [`File`] and all of its clones are closed, even if
+    /// the process is killed before.
+    ///
+    /// [`env::temp_dir()`]: ../env/fn.temp_dir.html
+    /// [`File`]: struct.File.html
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn anonymous() -> io::Result<File> {
+        TempFile::anonymous_in(env::temp_dir())
+    }
+
+    /// Creates a new temporary file in `dir` which never has a name, or has
+    /// one only briefly.
+    ///
+    /// See [`anonymous`] for details.
+    ///
+    /// [`anonymous`]: #method.anonymous
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// The file is opened with `O_TMPFILE` on Linux when the filesystem
+    /// supports it. Otherwise it is created like by [`new_in`] and removed
+    /// right away on Unix, or opened with `FILE_FLAG_DELETE_ON_CLOSE` on
+    /// Windows. Note that, this [may change in the future][changes].
+    ///
+    /// [`new_in`]: #method.new_in
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn anonymous_in<P: AsRef<Path>>(dir: P) -> io::Result<File> {
+        let dir = dir.as_ref();
+        let result = fs_imp::open_tmpfile(dir).and_then(|file| {
+            if let Some(file) = file {
+                return Ok(File { inner: file })
+            }
+            create_temp(dir, |path| {
+                let file = temp_file_options(true).open(&path)?;
+                if cfg!(unix) {
+                    remove_file(&path)?;
+                }
+                Ok(file)
+            })
+        });
+        result.map_err(|e| e.with_path("create temporary file in", dir))
+    }
+
+    /// Returns the path of the temporary file.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn path(&self) -> &Path {
+        &self.path.0
+    }
+
+    /// Returns a reference to the underlying file.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn as_file(&self) -> &File {
+        &self.file
+    }
+
+    /// Returns a mutable reference to the underlying file.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn as_file_mut(&mut self) -> &mut File {
+        &mut self.file
+    }
+
+    /// Keeps the temporary file by moving it to `path`, returning the
+    /// underlying file.
+    ///
+    /// This atomically replaces any file at `path`, so that other processes
+    /// only ever see either the previous file or the complete new one there.
+    /// Data still buffered by the operating system isn't necessarily written
+    /// to the disk, call [`File::sync_all`] first to ensure that the new file
+    /// survives a crash.
+    ///
+    /// [`File::sync_all`]: struct.File.html#method.sync_all
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be renamed, for
+    /// example because `path` is on a different filesystem than the temporary
+    /// file. The temporary file is returned inside the error in that case,
+    /// and can be persisted somewhere else.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn persist<P: AsRef<Path>>(self, path: P) -> Result<File, PersistError> {
+        let result = rename(&self.path.0, path);
+        if let Err(e) = result {
+            return Err(PersistError { error: e, file: self })
+        }
+        let TempFile { file, path: mut temp_path } = self;
+        temp_path.0 = PathBuf::new();
+        Ok(file)
+    }
+}
+
+impl PersistError {
+    /// Returns the error which caused the temporary file not to be persisted.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn error(&self) -> &io::Error {
+        &self.error
+    }
+
+    /// Returns the temporary file which couldn't be persisted.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn into_inner(self) -> TempFile {
+        self.file
+    }
+}
+
+#[unstable(feature = "temp_file", issue = "0")]
+impl From<PersistError> for io::Error {
+    fn from(e: PersistError) -> io::Error {
+        e.error
+    }
+}
+
+#[unstable(feature = "temp_file", issue = "0")]
+impl error::Error for PersistError {
+    fn description(&self) -> &str {
+        error::Error::description(&self.error)
+    }
+}
+
+#[unstable(feature = "temp_file", issue = "0")]
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+fn temp_file_options(delete_on_close: bool) -> OpenOptions {
+    let mut opts = OpenOptions::new();
+    opts.read(true).write(true).create_new(true);
+    fs_imp::temp_file_options(opts.as_inner_mut(), delete_on_close);
+    opts
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if !self.0.as_os_str().is_empty() {
+            let _ = remove_file(&self.0);
+        }
+    }
+}
+
+#[unstable(feature = "temp_file", issue = "0")]
+impl Read for TempFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.file.read_vectored(bufs)
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> io::Result<()> {
+        self.file.read_buf(buf)
+    }
+
+    #[inline]
+    unsafe fn initializer(&self) -> Initializer {
+        Initializer::nop()
+    }
+}
+
+#[unstable(feature = "temp_file", issue = "0")]
+impl Write for TempFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.file.write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[unstable(feature = "temp_file", issue = "0")]
+impl Seek for TempFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file.seek(pos)
+    }
+}
+
+impl TempDir {
+    /// Creates a new temporary directory in [`env::temp_dir()`].
+    ///
+    /// [`env::temp_dir()`]: ../env/fn.temp_dir.html
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the directory can't be created.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn new() -> io::Result<TempDir> {
+        TempDir::new_in(env::temp_dir())
+    }
+
+    /// Creates a new temporary directory in `dir`.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// The directory is created with mode `0o700` on Unix. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the directory can't be created.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn new_in<P: AsRef<Path>>(dir: P) -> io::Result<TempDir> {
+        let dir = dir.as_ref();
+        let mut builder = fs_imp::DirBuilder::new();
+        fs_imp::temp_dir_builder(&mut builder);
+        create_temp(dir, |path| {
+            builder.mkdir(&path)?;
+            Ok(TempDir { path: path })
+        }).map_err(|e| e.with_path("create temporary directory in", dir))
+    }
+
+    /// Returns the path of the temporary directory.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keeps the temporary directory, returning its path.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn into_path(mut self) -> PathBuf {
+        mem::replace(&mut self.path, PathBuf::new())
+    }
+
+    /// Removes the temporary directory and its contents, reporting errors
+    /// which dropping the `TempDir` would ignore.
+    #[unstable(feature = "temp_file", issue = "0")]
+    pub fn close(mut self) -> io::Result<()> {
+        let path = mem::replace(&mut self.path, PathBuf::new());
+        remove_dir_all(&path)
+    }
+}
+
+#[unstable(feature = "temp_file", issue = "0")]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if !self.path.as_os_str().is_empty() {
+            let _ = remove_dir_all(&self.path);
+        }
+    }
+}
+
+impl AtomicWriter {
+    /// Creates a writer which will replace the file at `path`.
+    ///
+    /// Nothing happens to the file at `path` until [`commit`] is called.
+    ///
+    /// [`commit`]: #method.commit
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the temporary file can't be
+    /// created in the parent directory of `path`, or if the permissions of
+    /// an existing file at `path` can't be read or copied.
+    #[unstable(feature = "atomic_write", issue = "0")]
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<AtomicWriter> {
+        let path = path.as_ref();
+        AtomicWriter::create(path).map_err(|e| e.with_path("atomically write", path))
+    }
+
+    fn create(path: &Path) -> io::Result<AtomicWriter> {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let mut opts = OpenOptions::new();
+        opts.read(true).write(true).create_new(true);
+        fs_imp::replacement_file_options(opts.as_inner_mut());
+        let temp = TempFile::create_in(dir, &opts)?;
+        match metadata(path) {
+            Ok(m) => temp.as_file().set_permissions(m.permissions())?,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        Ok(AtomicWriter { temp: temp, path: path.to_path_buf(), dir: dir.to_path_buf() })
+    }
+
+    /// Replaces the target file with the data written so far.
+    ///
+    /// This flushes the new data to the disk, renames the temporary file
+    /// over the target and then flushes the parent directory, so that the
+    /// new file survives a crash once this function returns.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently uses `fsync` (`F_FULLFSYNC` on macOS) and
+    /// `rename` on Unix, and `FlushFileBuffers` and `MoveFileExW` on Windows.
+    /// Directories can't be flushed on Windows. Note that, this [may change
+    /// in the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// If any step fails, an error is returned and the temporary file is
+    /// removed. The target is only replaced if the rename succeeded, which
+    /// is the case if the error happened while flushing the directory.
+    #[unstable(feature = "atomic_write", issue = "0")]
+    pub fn commit(self) -> io::Result<()> {
+        let AtomicWriter { temp, path, dir } = self;
+        let result = temp.as_file().sync_all()
+            .and_then(|()| temp.persist(&path).map_err(io::Error::from))
+            .and_then(|_| fs_imp::sync_dir(&dir));
+        result.map_err(|e| e.with_path("atomically write", &path))
+    }
+}
+
+#[unstable(feature = "atomic_write", issue = "0")]
+impl Write for AtomicWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.temp.write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.temp.write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.temp.flush()
+    }
+}
+
+impl MmapOptions {
+    /// Creates a blank new set of options, which map the whole file.
+    #[unstable(feature = "mmap", issue = "0")]
+    pub fn new() -> MmapOptions {
+        MmapOptions { offset: 0, len: None }
+    }
+
+    /// Sets the offset in the file at which the mapping starts.
+    ///
+    /// The offset doesn't need to be aligned to a page boundary.
+    #[unstable(feature = "mmap", issue = "0")]
+    pub fn offset(&mut self, offset: u64) -> &mut MmapOptions {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets the length of the mapping.
+    ///
+    /// By default, the mapping extends to the end of the file.
+    #[unstable(feature = "mmap", issue = "0")]
+    pub fn len(&mut self, len: usize) -> &mut MmapOptions {
+        self.len = Some(len);
+        self
+    }
+
+    /// Maps `file` read-only.
+    ///
+    /// `file` needs to be opened for reading.
+    ///
+    /// # Safety
+    ///
+    /// The contents of the mapping change when the file is modified, by this
+    /// or any other process, while references to them exist. Accessing parts
+    /// of the mapping after the file was truncated to end before them kills
+    /// the process with `SIGBUS` on Unix. The caller has to make sure that
+    /// neither happens while the mapping exists.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `mmap` with `MAP_SHARED` on
+    /// Unix and `MapViewOfFile` on Windows. Note that, this [may change in
+    /// the future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the offset lies beyond the end
+    /// of the file while no length is set, or if the file can't be mapped.
+    #[unstable(feature = "mmap", issue = "0")]
+    pub unsafe fn map(&self, file: &File) -> io::Result<Mmap> {
+        self.map_inner(file, MmapMode::ReadOnly).map(|inner| Mmap { inner: inner })
+    }
+
+    /// Maps `file` for reading and writing, so that modifications of the
+    /// mapping are written back to the file.
+    ///
+    /// `file` needs to be opened for reading and writing. Modifications can
+    /// be written to the disk explicitly with [`MmapMut::flush`].
+    ///
+    /// [`MmapMut::flush`]: struct.MmapMut.html#method.flush
+    ///
+    /// # Safety
+    ///
+    /// See [`map`](#method.map).
+    #[unstable(feature = "mmap", issue = "0")]
+    pub unsafe fn map_mut(&self, file: &File) -> io::Result<MmapMut> {
+        self.map_inner(file, MmapMode::Shared).map(|inner| MmapMut { inner: inner, private: false })
+    }
+
+    /// Maps `file` copy-on-write, so that modifications of the mapping are
+    /// private to it and never written back to the file.
+    ///
+    /// `file` only needs to be opened for reading.
+    ///
+    /// # Safety
+    ///
+    /// See [`map`](#method.map). Parts of the file which weren't modified
+    /// through the mapping may still reflect later changes to the file.
+    #[unstable(feature = "mmap", issue = "0")]
+    pub unsafe fn map_copy(&self, file: &File) -> io::Result<MmapMut> {
+        self.map_inner(file, MmapMode::Private).map(|inner| MmapMut { inner: inner, private: true })
+    }
+
+    fn map_inner(&self, file: &File, mode: MmapMode) -> io::Result<fs_imp::Mmap> {
+        let len = match self.len {
+            Some(len) => len,
+            None => {
+                let file_len = file.metadata()?.len();
+                if self.offset > file_len {
+                    return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                              "mapping offset is beyond the end of the file"))
+                }
+                let len = file_len - self.offset;
+                if len > usize::max_value() as u64 {
+                    return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                              "file is too large to be mapped"))
+                }
+                len as usize
+            }
+        };
+        fs_imp::Mmap::new(&file.inner, mode, self.offset, len)
+    }
+}
+
+impl Mmap {
+    /// Advises the operating system how the mapping is going to be accessed.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `madvise` on Unix, and does
+    /// nothing on Windows. Note that, this [may change in the
+    /// future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    #[unstable(feature = "mmap", issue = "0")]
+    pub fn advise(&self, advice: Advice) -> io::Result<()> {
+        self.inner.advise(advice)
+    }
+}
+
+impl MmapMut {
+    /// Writes modifications of the mapping back to the disk, waiting until
+    /// they are written.
+    ///
+    /// Modifications are written back eventually even if this isn't called,
+    /// unless the mapping was created by [`map_copy`], in which case this
+    /// does nothing.
+    ///
+    /// [`map_copy`]: struct.MmapOptions.html#method.map_copy
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to `msync` with `MS_SYNC` on Unix
+    /// and `FlushViewOfFile` on Windows. Note that, this [may change in the
+    /// future][changes].
+    ///
+    /// [changes]: ../io/index.html#platform-specific-behavior
+    #[unstable(feature = "mmap", issue = "0")]
+    pub fn flush(&self) -> io::Result<()> {
+        self.inner.flush(0, self.inner.len())
+    }
+
+    /// Like [`flush`], but only writes back the `len` bytes of the mapping
+    /// starting at `offset`.
+    ///
+    /// [`flush`]: #method.flush
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of the `InvalidInput` kind if the
+    /// range isn't part of the mapping.
+    #[unstable(feature = "mmap", issue = "0")]
+    pub fn flush_range(&self, offset: usize, len: usize) -> io::Result<()> {
+        match offset.checked_add(len) {
+            Some(end) if end <= self.inner.len() => self.inner.flush(offset, len),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                    "range is out of bounds of the mapping")),
+        }
+    }
+
+    /// Advises the operating system how the mapping is going to be accessed.
+    ///
+    /// See [`Mmap::advise`].
+    ///
+    /// [`Mmap::advise`]: struct.Mmap.html#method.advise
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of the `InvalidInput` kind if
+    /// `advice` is [`DontNeed`] and the mapping was created by
+    /// [`MmapOptions::map_copy`], as that could discard modifications which
+    /// are still borrowed. Use [`advise_unchecked`] instead.
+    ///
+    /// [`DontNeed`]: enum.Advice.html#variant.DontNeed
+    /// [`MmapOptions::map_copy`]: struct.MmapOptions.html#method.map_copy
+    /// [`advise_unchecked`]: #method.advise_unchecked
+    #[unstable(feature = "mmap", issue = "0")]
+    pub fn advise(&self, advice: Advice) -> io::Result<()> {
+        if self.private && advice == Advice::DontNeed {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "`DontNeed` would discard the modifications of the mapping"))
+        }
+        self.inner.advise(advice)
+    }
+
+    /// Like [`advise`], but also accepts [`DontNeed`] for mappings created by
+    /// [`MmapOptions::map_copy`].
+    ///
+    /// [`advise`]: #method.advise
+    /// [`DontNeed`]: enum.Advice.html#variant.DontNeed
+    /// [`MmapOptions::map_copy`]: struct.MmapOptions.html#method.map_copy
+    ///
+    /// # Safety
+    ///
+    /// Passing [`DontNeed`] for such a mapping may reset its contents to
+    /// the ones of the file. The caller has to make sure that no references
+    /// into the mapping exist while that happens.
+    #[unstable(feature = "mmap", issue = "0")]
+    pub unsafe fn advise_unchecked(&self, advice: Advice) -> io::Result<()> {
+        self.inner.advise(advice)
+    }
+}
+
+#[unstable(feature = "mmap", issue = "0")]
+impl Deref for Mmap {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.inner.ptr(), self.inner.len()) }
+    }
+}
+
+#[unstable(feature = "mmap", issue = "0")]
+impl AsRef<[u8]> for Mmap {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+#[unstable(feature = "mmap", issue = "0")]
+impl fmt::Debug for Mmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Mmap")
+            .field("ptr", &self.inner.ptr())
+            .field("len", &self.inner.len())
+            .finish()
+    }
+}
+
+#[unstable(feature = "mmap", issue = "0")]
+impl Deref for MmapMut {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.inner.ptr(), self.inner.len()) }
+    }
+}
+
+#[unstable(feature = "mmap", issue = "0")]
+impl DerefMut for MmapMut {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.inner.ptr(), self.inner.len()) }
+    }
+}
+
+#[unstable(feature = "mmap", issue = "0")]
+impl AsRef<[u8]> for MmapMut {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+#[unstable(feature = "mmap", issue = "0")]
+impl AsMut<[u8]> for MmapMut {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+#[unstable(feature = "mmap", issue = "0")]
+impl fmt::Debug for MmapMut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MmapMut")
+            .field("ptr", &self.inner.ptr())
+            .field("len", &self.inner.len())
+            .finish()
+    }
+}
+
+#[unstable(feature = "file_allocate", issue = "0")]
+impl<'a> Iterator for Extents<'a> {
+    type Item = io::Result<Extent>;
+
+    fn next(&mut self) -> Option<io::Result<Extent>> {
+        if self.offset >= self.end {
+            return None
+        }
+        let offset = self.offset;
+        let data = if self.fallback {
+            Ok(Some((offset, self.end)))
+        } else {
+            self.file.inner.next_data(offset)
+        };
+        let (start, end, hole) = match data {
+            Ok(Some((start, _))) if start > offset => (offset, start, true),
+            Ok(Some((start, end))) => (start, end, false),
+            Ok(None) => (offset, self.end, true),
+            Err(ref e) if e.kind() == io::ErrorKind::Unsupported => {
+                self.fallback = true;
+                (offset, self.end, false)
+            }
+            Err(e) => {
+                self.offset = self.end;
+                return Some(Err(e))
+            }
+        };
+        // The file may have grown since the iteration started.
+        let end = cmp::min(end, self.end);
+        self.offset = end;
+        Some(Ok(Extent { offset: start, len: end - start, hole: hole }))
+    }
+}
+
+impl Extent {
+    /// Returns the offset in the file at which the region starts.
+    #[unstable(feature = "file_allocate", issue = "0")]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the length of the region in bytes.
+    #[unstable(feature = "file_allocate", issue = "0")]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the region is a hole, which has no disk space
+    /// allocated and reads as zeros.
+    #[unstable(feature = "file_allocate", issue = "0")]
+    pub fn is_hole(&self) -> bool {
+        self.hole
+    }
+}
+
+impl FsStats {
+    /// Returns the size of the filesystem in bytes.
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn total_space(&self) -> u64 {
+        self.0.total_space()
+    }
+
+    /// Returns the number of free bytes, including those reserved for
+    /// privileged users.
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn free_space(&self) -> u64 {
+        self.0.free_space()
+    }
+
+    /// Returns the number of bytes available to the current user.
+    ///
+    /// This is the value to check before writing to the filesystem. It can
+    /// be smaller than [`free_space`] because of blocks reserved for
+    /// privileged users, and on Windows because of disk quotas.
+    ///
+    /// [`free_space`]: #method.free_space
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn available_space(&self) -> u64 {
+        self.0.available_space()
+    }
+
+    /// Returns the total number of inodes, which limits the number of files
+    /// on the filesystem.
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn total_inodes(&self) -> u64 {
+        self.0.total_inodes()
+    }
+
+    /// Returns the number of free inodes.
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn free_inodes(&self) -> u64 {
+        self.0.free_inodes()
+    }
+
+    /// Returns the number of inodes available to the current user.
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn available_inodes(&self) -> u64 {
+        self.0.available_inodes()
+    }
+
+    /// Returns the preferred block size for I/O, which is the cluster size
+    /// on Windows.
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn block_size(&self) -> u64 {
+        self.0.block_size()
+    }
+
+    /// Returns an identifier of the filesystem, which is the volume serial
+    /// number on Windows.
+    ///
+    /// The identifier may not be unique across all mounted filesystems, and
+    /// may change when the filesystem is mounted again.
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn fs_id(&self) -> u64 {
+        self.0.fs_id()
+    }
+
+    /// Returns the maximum length of a file name on the filesystem.
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn max_name_len(&self) -> u64 {
+        self.0.max_name_len()
+    }
+
+    /// Returns `true` if the filesystem is mounted read-only.
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn is_read_only(&self) -> bool {
+        self.0.is_read_only()
+    }
+
+    /// Returns `true` if the filesystem is mounted with set-user-ID and
+    /// set-group-ID bits being ignored. This is always `false` on Windows.
+    #[unstable(feature = "fs_stats", issue = "0")]
+    pub fn is_nosuid(&self) -> bool {
+        self.0.is_nosuid()
+    }
+}
+
+#[unstable(feature = "fs_stats", issue = "0")]
+impl fmt::Debug for FsStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FsStats")
+            .field("total_space", &self.total_space())
+            .field("free_space", &self.free_space())
+            .field("available_space", &self.available_space())
+            .field("total_inodes", &self.total_inodes())
+            .field("free_inodes", &self.free_inodes())
+            .field("available_inodes", &self.available_inodes())
+            .field("block_size", &self.block_size())
+            .field("fs_id", &self.fs_id())
+            .field("max_name_len", &self.max_name_len())
+            .field("is_read_only", &self.is_read_only())
+            .field("is_nosuid", &self.is_nosuid())
+            .finish()
+    }
+}
+
+impl WalkDirEntry {
+    /// Returns the full path to the file that this entry represents.
+    ///
+    /// This is the path of the root of the walk joined with the names of the
+    /// entries leading to this one.
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn path(&self) -> PathBuf {
+        self.entry.path()
+    }
+
+    /// Returns the bare file name of this entry without any other leading
+    /// path component.
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn file_name(&self) -> OsString {
+        self.entry.file_name()
+    }
+
+    /// Returns the depth of this entry in the walk.
+    ///
+    /// The entries of the root of the walk are at depth 1.
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the type of the file that this entry represents.
+    ///
+    /// If symbolic links are followed this is the type of the file the link
+    /// points to, otherwise that of the link itself.
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// Returns whether this entry is a symbolic link which was followed.
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn path_is_symlink(&self) -> bool {
+        self.followed
+    }
+
+    /// Returns the metadata for the file that this entry represents.
+    ///
+    /// Like [`file_type`], this is the metadata of the target of a symbolic
+    /// link which was followed.
+    ///
+    /// [`file_type`]: #method.file_type
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        if self.followed {
+            metadata(self.entry.path())
+        } else {
+            self.entry.metadata().map_err(|e| e.with_path("get metadata for", &self.path()))
+        }
+    }
+
+    /// Consumes this entry, returning the underlying [`DirEntry`].
+    ///
+    /// [`DirEntry`]: struct.DirEntry.html
+    #[unstable(feature = "walk_dir", issue = "0")]
+    pub fn into_dir_entry(self) -> DirEntry {
+        self.entry
+    }
+}
+
+/// Changes the permissions found on a file or a directory.
+///
+/// # Platform-specific behavior
+///
+/// This function currently corresponds to the `chmod` function on Unix
+/// and the `SetFileAttributes` function on Windows.
+/// Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * `path` does not exist.
+/// * The user lacks the permission to change attributes of the file.
+///
+/// # Examples
+///
+/// ```
+/// # fn foo() -> std::io::Result<()> {
+/// use std::fs;
+///
+/// let mut perms = fs::metadata("foo.txt")?.permissions();
+/// perms.set_readonly(true);
+/// fs::set_permissions("foo.txt", perms)?;
+/// # Ok(())
+/// # }
+/// ```
+#[stable(feature = "set_permissions", since = "1.1.0")]
+pub fn set_permissions<P: AsRef<Path>>(path: P, perm: Permissions)
+                                       -> io::Result<()> {
+    let path = path.as_ref();
+    fs_imp::set_perm(path, perm.0).map_err(|e| e.with_path("set permissions of", path))
+}
+
+/// Changes the timestamps of the file or directory at `path`.
+///
+/// Only the timestamps set in `times` are changed, the others keep their
+/// current value. Symbolic links are followed.
+///
+/// # Platform-specific behavior
+///
+/// This function currently corresponds to the `utimensat` function on Unix
+/// (falling back to `utimes` on older macOS versions) and the `SetFileTime`
+/// function on Windows. Note that, this [may change in the future][changes].
+///
+/// [changes]: ../io/index.html#platform-specific-behavior
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not
+/// limited to just these cases:
+///
+/// * `path` does not exist.
+/// * The user lacks the permission to change timestamps of the file.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(file_set_times)]
+/// # fn foo() -> std::io::Result<()> {
+/// use std::fs::{self, FileTimes};
+/// use std::time::SystemTime;
+///
+/// let now = SystemTime::now();
+/// fs::set_times("foo.txt", FileTimes::new().set_accessed(now).set_modified(now))?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "file_set_times", issue = "0")]
+pub fn set_times<P: AsRef<Path>>(path: P, times: FileTimes) -> io::Result<()> {
+    let path = path.as_ref();
+    fs_imp::set_times(path, times.0).map_err(|e| e.with_path("set times of", path))
+}
+
+impl FileTimes {
+    /// Creates a new `FileTimes` with no times set.
+    ///
+    /// Using the resulting `FileTimes` in [`File::set_times`] leaves all the
+    /// timestamps of the file unchanged.
+    ///
+    /// [`File::set_times`]: struct.File.html#method.set_times
+    #[unstable(feature = "file_set_times", issue = "0")]
+    pub fn new() -> FileTimes {
+        FileTimes::default()
+    }
+
+    /// Sets the last access time of a file.
+    #[unstable(feature = "file_set_times", issue = "0")]
+    pub fn set_accessed(mut self, t: SystemTime) -> FileTimes {
+        self.0.set_accessed(*t.as_inner());
+        self
+    }
+
+    /// Sets the last modified time of a file.
+    #[unstable(feature = "file_set_times", issue = "0")]
+    pub fn set_modified(mut self, t: SystemTime) -> FileTimes {
+        self.0.set_modified(*t.as_inner());
+        self
+    }
+}
+
+impl DirBuilder {
+    /// Creates a new set of options with default mode/security settings for all
+    /// platforms and also non-recursive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fs::DirBuilder;
+    ///
+    /// let builder = DirBuilder::new();
+    /// ```
+    #[stable(feature = "dir_builder", since = "1.6.0")]
+    pub fn new() -> DirBuilder {
+        DirBuilder {
+            inner: fs_imp::DirBuilder::new(),
+            recursive: false,
+        }
+    }
+
+    /// Indicates that directories should be created recursively, creating all
+    /// parent directories. Parents that do not exist are created with the same
+    /// security and permissions settings.
+    ///
+    /// This option defaults to `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fs::DirBuilder;
+    ///
+    /// let mut builder = DirBuilder::new();
+    /// builder.recursive(true);
+    /// ```
+    #[stable(feature = "dir_builder", since = "1.6.0")]
+    pub fn recursive(&mut self, recursive: bool) -> &mut Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Create the specified directory with the options configured in this
+    /// builder.
+    ///
+    /// It is considered an error if the directory already exists unless
+    /// recursive mode is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::{self, DirBuilder};
+    ///
+    /// let path = "/tmp/foo/bar/baz";
+    /// DirBuilder::new()
+    ///     .recursive(true)
+    ///     .create(path).unwrap();
+    ///
+    /// assert!(fs::metadata(path).unwrap().is_dir());
+    /// ```
+    #[stable(feature = "dir_builder", since = "1.6.0")]
+    pub fn create<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self._create(path.as_ref())
+    }
+
+    fn _create(&self, path: &Path) -> io::Result<()> {
+        if self.recursive {
+            self.create_dir_all(path)
+        } else {
+            self.mkdir(path)
+        }
+    }
+
+    // Errors name the directory that actually failed to be created, which
+    // for a recursive creation may be any of the ancestors of the path given.
+    fn mkdir(&self, path: &Path) -> io::Result<()> {
+        self.inner.mkdir(path).map_err(|e| e.with_path("create directory", path))
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        if path == Path::new("") {
+            return Ok(())
+        }
+
+        match self.mkdir(path) {
+            Ok(()) => return Ok(()),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(_) if path.is_dir() => return Ok(()),
+            Err(e) => return Err(e),
+        }
+        match path.parent() {
+            Some(p) => try!(self.create_dir_all(p)),
+            None => {
+                let err = io::Error::new(io::ErrorKind::Other, "failed to create whole tree");
+                return Err(err.with_path("create directory", path))
+            }
+        }
+        match self.mkdir(path) {
+            Ok(()) => Ok(()),
+            Err(_) if path.is_dir() => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsInnerMut<fs_imp::DirBuilder> for DirBuilder {
+    fn as_inner_mut(&mut self) -> &mut fs_imp::DirBuilder {
+        &mut self.inner
+    }
+}
+
+impl DirOptions {
+    /// Creates a blank new set of options, which follow symbolic links.
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn new() -> DirOptions {
+        DirOptions { follow_symlinks: true }
+    }
+
+    /// Sets whether paths given to the methods of the opened [`Dir`] may
+    /// traverse symbolic links.
+    ///
+    /// When set to `false`, any path that goes through a symbolic link, or
+    /// that ends in one, fails to resolve, and so do absolute paths and paths
+    /// containing `..`. This guarantees that a path can't lead outside of the
+    /// directory, even if its contents are controlled by someone else.
+    /// Operations which act on a link itself rather than on what it points
+    /// to, such as [`Dir::remove_file`] or [`Dir::symlink_metadata`], still
+    /// work when the final component of the path is a symbolic link.
+    ///
+    /// The path the `Dir` itself is opened from is always resolved normally.
+    ///
+    /// This option defaults to `true`.
+    ///
+    /// [`Dir`]: struct.Dir.html
+    /// [`Dir::remove_file`]: struct.Dir.html#method.remove_file
+    /// [`Dir::symlink_metadata`]: struct.Dir.html#method.symlink_metadata
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dir_handle)]
+    /// use std::fs::{DirOptions, OpenOptions};
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let uploads = DirOptions::new().follow_symlinks(false).open("uploads")?;
+    /// // Fails rather than opening `/etc/passwd` if `name` is a symlink to it.
+    /// let file = uploads.open_file("name", OpenOptions::new().read(true))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn follow_symlinks(&mut self, follow: bool) -> &mut DirOptions {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Opens the directory at `path` with the options specified by `self`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` does not exist or is not
+    /// a directory.
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<Dir> {
+        let path = path.as_ref();
+        fs_imp::DirHandle::open(path, self.follow_symlinks)
+            .map(|inner| Dir { inner: inner })
+            .map_err(|e| e.with_path("open directory", path))
+    }
+}
+
+impl Dir {
+    /// Opens the directory at `path`, following symbolic links.
+    ///
+    /// See [`DirOptions`] for more options.
+    ///
+    /// [`DirOptions`]: struct.DirOptions.html
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Dir> {
+        DirOptions::new().open(path)
+    }
+
+    /// Opens a file relative to this directory with the given options.
+    ///
+    /// See [`OpenOptions::open`] for the meaning of the options.
+    ///
+    /// [`OpenOptions::open`]: struct.OpenOptions.html#method.open
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn open_file<P: AsRef<Path>>(&self, path: P, options: &OpenOptions) -> io::Result<File> {
+        let path = path.as_ref();
+        self.inner.open_file(path, &options.0)
+            .map(|inner| File { inner: inner })
+            .map_err(|e| e.with_path("open", path))
+    }
+
+    /// Opens a directory relative to this directory.
+    ///
+    /// The returned handle uses the same options as this one.
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn open_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<Dir> {
+        let path = path.as_ref();
+        self.inner.open_dir(path)
+            .map(|inner| Dir { inner: inner })
+            .map_err(|e| e.with_path("open directory", path))
+    }
+
+    /// Creates a new, empty directory relative to this directory.
+    ///
+    /// See [`fs::create_dir`] for more details.
+    ///
+    /// [`fs::create_dir`]: fn.create_dir.html
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn create_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        self.inner.create_dir(path).map_err(|e| e.with_path("create directory", path))
+    }
+
+    /// Removes a file or symbolic link relative to this directory.
+    ///
+    /// See [`fs::remove_file`] for more details.
+    ///
+    /// [`fs::remove_file`]: fn.remove_file.html
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn remove_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        self.inner.remove_file(path).map_err(|e| e.with_path("remove file", path))
+    }
+
+    /// Removes an empty directory relative to this directory.
     ///
-    /// # Examples
+    /// See [`fs::remove_dir`] for more details.
     ///
-    /// ```no_run
-    /// use std::fs::{self, DirBuilder};
+    /// [`fs::remove_dir`]: fn.remove_dir.html
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn remove_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        self.inner.remove_dir(path).map_err(|e| e.with_path("remove directory", path))
+    }
+
+    /// Renames `from`, relative to this directory, to `to`, relative to
+    /// `to_dir`.
     ///
-    /// let path = "/tmp/foo/bar/baz";
-    /// DirBuilder::new()
-    ///     .recursive(true)
-    ///     .create(path).unwrap();
+    /// `to_dir` may be the same handle as `self`. Both directories have to be
+    /// on the same filesystem. See [`fs::rename`] for more details.
     ///
-    /// assert!(fs::metadata(path).unwrap().is_dir());
-    /// ```
-    #[stable(feature = "dir_builder", since = "1.6.0")]
-    pub fn create<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        self._create(path.as_ref())
+    /// [`fs::rename`]: fn.rename.html
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(&self, from: P, to_dir: &Dir, to: Q)
+                                                  -> io::Result<()> {
+        let (from, to) = (from.as_ref(), to.as_ref());
+        self.inner.rename(from, &to_dir.inner, to)
+            .map_err(|e| e.with_paths("rename", from, to))
     }
 
-    fn _create(&self, path: &Path) -> io::Result<()> {
-        if self.recursive {
-            self.create_dir_all(path)
-        } else {
-            self.inner.mkdir(path)
-        }
+    /// Creates a new symbolic link at `link`, relative to this directory,
+    /// pointing to `original`.
+    ///
+    /// `original` is stored in the link as is and is resolved relative to
+    /// the directory containing the link when it is followed.
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(&self, original: P, link: Q)
+                                                   -> io::Result<()> {
+        let (original, link) = (original.as_ref(), link.as_ref());
+        self.inner.symlink(original, link)
+            .map_err(|e| e.with_paths("symlink", original, link))
     }
 
-    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
-        if path == Path::new("") {
-            return Ok(())
-        }
+    /// Queries the metadata of the file at `path`, relative to this
+    /// directory.
+    ///
+    /// If this handle doesn't follow symbolic links, this returns the
+    /// metadata of a symbolic link at `path` itself, like
+    /// [`symlink_metadata`].
+    ///
+    /// [`symlink_metadata`]: #method.symlink_metadata
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        let path = path.as_ref();
+        self.inner.metadata(path)
+            .map(Metadata)
+            .map_err(|e| e.with_path("get metadata for", path))
+    }
 
-        match self.inner.mkdir(path) {
-            Ok(()) => return Ok(()),
-            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
-            Err(_) if path.is_dir() => return Ok(()),
-            Err(e) => return Err(e),
-        }
-        match path.parent() {
-            Some(p) => try!(self.create_dir_all(p)),
-            None => return Err(io::Error::new(io::ErrorKind::Other, "failed to create whole tree")),
-        }
-        match self.inner.mkdir(path) {
-            Ok(()) => Ok(()),
-            Err(_) if path.is_dir() => Ok(()),
-            Err(e) => Err(e),
-        }
+    /// Queries the metadata of the file at `path`, relative to this
+    /// directory, without following a symbolic link at `path`.
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn symlink_metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        let path = path.as_ref();
+        self.inner.symlink_metadata(path)
+            .map(Metadata)
+            .map_err(|e| e.with_path("get symlink metadata for", path))
+    }
+
+    /// Returns an iterator over the entries of the directory at `path`,
+    /// relative to this directory. Pass `"."` to list this directory.
+    ///
+    /// The paths of the returned entries are built from the path this handle
+    /// was opened from, so they might be stale if the directory has been
+    /// moved since.
+    #[unstable(feature = "dir_handle", issue = "0")]
+    pub fn read_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<ReadDir> {
+        let path = path.as_ref();
+        self.inner.read_dir(path)
+            .map(ReadDir)
+            .map_err(|e| e.with_path("read directory", path))
     }
 }
 
-impl AsInnerMut<fs_imp::DirBuilder> for DirBuilder {
-    fn as_inner_mut(&mut self) -> &mut fs_imp::DirBuilder {
-        &mut self.inner
+#[unstable(feature = "dir_handle", issue = "0")]
+impl fmt::Debug for Dir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt(f)
     }
 }
 
+impl AsInner<fs_imp::DirHandle> for Dir {
+    fn as_inner(&self) -> &fs_imp::DirHandle { &self.inner }
+}
+
 #[cfg(all(test, not(target_os = "emscripten")))]
 mod tests {
     use io::prelude::*;
 
     use fs::{self, File, OpenOptions};
-    use io::{ErrorKind, SeekFrom};
+    use io::{self, ErrorKind, SeekFrom, IoSlice, IoSliceMut};
     use path::Path;
     use rand::{StdRng, Rng};
     use str;
@@ -2010,6 +4777,193 @@ mod tests {
         assert_eq!(read_str, message);
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn file_test_io_vectored() {
+        let tmpdir = tmpdir();
+        let filename = &tmpdir.join("file_rt_io_file_test_vectored.txt");
+        {
+            let mut f = check!(File::create(filename));
+            let n = check!(f.write_vectored(&[IoSlice::new(b"head"),
+                                              IoSlice::new(b""),
+                                              IoSlice::new(b"body")]));
+            assert_eq!(n, 8);
+        }
+        {
+            let mut f = check!(File::open(filename));
+            let mut a = [0; 3];
+            let mut b = [0; 16];
+            let n = check!(f.read_vectored(&mut [IoSliceMut::new(&mut a),
+                                                 IoSliceMut::new(&mut b)]));
+            assert_eq!(n, 8);
+            assert_eq!(&a, b"hea");
+            assert_eq!(&b[..5], b"dbody");
+        }
+    }
+
+    #[test]
+    fn write_then_read() {
+        let mut bytes = [0; 1024];
+        StdRng::new().unwrap().fill_bytes(&mut bytes);
+
+        let tmpdir = tmpdir();
+
+        check!(fs::write(&tmpdir.join("test"), &bytes[..]));
+        let v = check!(fs::read(&tmpdir.join("test")));
+        assert!(v == &bytes[..]);
+
+        check!(fs::write(&tmpdir.join("not-utf8"), &[0xFF]));
+        let err = fs::read_to_string(&tmpdir.join("not-utf8")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        let s = "𐁁𐀓𐀠𐀴𐀍";
+        check!(fs::write(&tmpdir.join("utf8"), s.as_bytes()));
+        let string = check!(fs::read_to_string(&tmpdir.join("utf8")));
+        assert_eq!(string, s);
+    }
+
+    #[test]
+    fn read_names_path_in_error() {
+        let tmpdir = tmpdir();
+        let filename = tmpdir.join("missing.txt");
+        let err = fs::read(&filename).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(err.to_string().contains(&*filename.to_string_lossy()));
+    }
+
+    #[test]
+    #[cfg(not(target_os = "solaris"))]
+    fn file_lock_multiple_handles() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("lockfile");
+        let f1 = check!(File::create(&path));
+        let f2 = check!(OpenOptions::new().write(true).open(&path));
+
+        check!(f1.lock_exclusive());
+        assert_eq!(f2.try_lock_shared().unwrap_err().kind(), ErrorKind::WouldBlock);
+        assert_eq!(f2.try_lock_exclusive().unwrap_err().kind(), ErrorKind::WouldBlock);
+        check!(f1.unlock());
+
+        check!(f1.lock_shared());
+        check!(f2.try_lock_shared());
+        assert_eq!(f2.try_lock_exclusive().unwrap_err().kind(), ErrorKind::WouldBlock);
+        check!(f1.unlock());
+        check!(f2.unlock());
+
+        check!(f2.try_lock_exclusive());
+        drop(f2);
+        // Closing the last handle releases the lock.
+        check!(f1.try_lock_exclusive());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn dir_handle_survives_rename() {
+        use fs::Dir;
+
+        let tmpdir = tmpdir();
+        let dir_path = tmpdir.join("d");
+        check!(fs::create_dir(&dir_path));
+        let dir = check!(Dir::open(&dir_path));
+
+        check!(dir.create_dir("sub"));
+        let mut f = check!(dir.open_file("sub/f", OpenOptions::new().write(true).create(true)));
+        check!(f.write_all(b"hello"));
+        drop(f);
+
+        // Move the directory away; the handle keeps pointing at it.
+        let moved = tmpdir.join("moved");
+        check!(fs::rename(&dir_path, &moved));
+        check!(fs::create_dir(&dir_path));
+
+        assert_eq!(check!(dir.metadata("sub/f")).len(), 5);
+        let names = check!(dir.read_dir("sub")).map(|e| check!(e).file_name())
+                                               .collect::<Vec<_>>();
+        assert_eq!(names, ["f"]);
+        check!(dir.rename("sub/f", &dir, "g"));
+        assert!(moved.join("g").exists());
+        check!(dir.remove_file("g"));
+        check!(dir.remove_dir("sub"));
+        assert_eq!(check!(fs::read_dir(&moved)).count(), 0);
+        assert_eq!(check!(fs::read_dir(&dir_path)).count(), 0);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn dir_handle_no_follow() {
+        use fs::DirOptions;
+
+        let tmpdir = tmpdir();
+        let outside = tmpdir.join("outside");
+        check!(fs::write(&outside, b"secret"));
+        let dir_path = tmpdir.join("d");
+        check!(fs::create_dir(&dir_path));
+        check!(fs::create_dir(dir_path.join("sub")));
+
+        let dir = check!(DirOptions::new().follow_symlinks(false).open(&dir_path));
+        check!(dir.symlink(&outside, "file_link"));
+        check!(dir.symlink(tmpdir.path(), "dir_link"));
+        check!(dir.symlink("sub", "sub_link"));
+
+        let read = OpenOptions::new().read(true).clone();
+        assert!(dir.open_file("file_link", &read).is_err());
+        assert!(dir.open_file("dir_link/outside", &read).is_err());
+        assert!(dir.open_dir("sub_link").is_err());
+        assert!(dir.read_dir("sub_link").is_err());
+        let e = dir.open_file("../outside", &read).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::PermissionDenied);
+        assert!(dir.open_file(&outside, &read).is_err());
+
+        assert!(check!(dir.metadata("file_link")).file_type().is_symlink());
+        check!(dir.open_dir("./sub"));
+        let e = dir.open_dir("sub/../sub").unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::PermissionDenied);
+        check!(dir.remove_file("file_link"));
+        assert!(outside.exists());
+    }
+
+    #[test]
+    fn regular_file_is_not_terminal() {
+        use io::IsTerminal;
+
+        let tmpdir = tmpdir();
+        let file = check!(File::create(tmpdir.join("not_a_tty")));
+        assert!(!file.is_terminal());
+    }
+
+    #[test]
+    fn errors_carry_operation_and_paths() {
+        let tmpdir = tmpdir();
+        let missing = tmpdir.join("missing");
+        let other = tmpdir.join("other");
+
+        let err = File::open(&missing).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(err.raw_os_error().is_some());
+        assert_eq!(err.operation(), Some("open"));
+        assert_eq!(err.path(), Some(&*missing));
+        assert_eq!(err.other_path(), None);
+        assert!(err.to_string().starts_with(
+            &format!("failed to open `{}`: ", missing.display())));
+
+        let err = fs::rename(&missing, &other).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(err.operation(), Some("rename"));
+        assert_eq!(err.path(), Some(&*missing));
+        assert_eq!(err.other_path(), Some(&*other));
+        assert!(err.to_string().starts_with(
+            &format!("failed to rename `{}` to `{}`: ", missing.display(), other.display())));
+
+        let err = missing.metadata().unwrap_err();
+        assert_eq!(err.operation(), Some("get metadata for"));
+        assert_eq!(err.path(), Some(&*missing));
+
+        // The error names the file which couldn't be opened rather than
+        // describing the whole operation.
+        let err = fs::read(&missing).unwrap_err();
+        assert_eq!(err.operation(), Some("open"));
+    }
+
     #[test]
     fn file_test_io_seek_and_tell_smoke_test() {
         let message = "ten-four";
@@ -2349,6 +5303,28 @@ mod tests {
         assert_eq!(e.kind(), ErrorKind::AlreadyExists);
     }
 
+    #[test]
+    fn rmdir_not_empty_error() {
+        let tmpdir = tmpdir();
+        let dir = &tmpdir.join("rmdir_not_empty");
+        check!(fs::create_dir(dir));
+        check!(File::create(dir.join("file")));
+        let e = fs::remove_dir(dir).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::DirectoryNotEmpty);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn path_through_file_error() {
+        let tmpdir = tmpdir();
+        let file = &tmpdir.join("not_a_dir");
+        check!(File::create(file));
+        let e = File::create(file.join("child")).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::NotADirectory);
+        let e = fs::read_dir(file).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::NotADirectory);
+    }
+
     #[test]
     fn recursive_mkdir() {
         let tmpdir = tmpdir();
@@ -2442,6 +5418,41 @@ mod tests {
         assert!(canary.exists());
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn recursive_rmdir_symlink_race() {
+        use ffi::CString;
+        use os::unix::io::AsRawFd;
+        use sys::fs::remove_dir_all_recursive;
+
+        // Swap a directory inside the tree being removed for a symlink
+        // pointing outside of it after its parent was opened, but before
+        // descending into it, which is the window a concurrent attacker
+        // would have to hit.
+        let tmpdir = tmpdir();
+        let outside = tmpdir.join("outside");
+        let canary = outside.join("canary");
+        check!(fs::create_dir(&outside));
+        check!(File::create(&canary));
+
+        let tree = tmpdir.join("tree");
+        let sub = tree.join("sub");
+        check!(fs::create_dir_all(&sub));
+        check!(File::create(sub.join("file")));
+        let parent = check!(File::open(&tree));
+        check!(fs::rename(&sub, tmpdir.join("aside")));
+        check!(symlink_dir(&outside, &sub));
+
+        let name = CString::new("sub").unwrap();
+        check!(remove_dir_all_recursive(parent.as_raw_fd(), &name, false));
+        assert!(canary.exists());
+        assert!(check!(fs::symlink_metadata(&outside)).is_dir());
+        assert!(check!(fs::read_dir(&tree)).next().is_none());
+
+        check!(fs::remove_dir_all(&tree));
+        assert!(canary.exists());
+    }
+
     #[test]
     // only Windows makes a distinction between file and directory symlinks.
     #[cfg(windows)]
@@ -2534,6 +5545,39 @@ mod tests {
                    check!(out.metadata()).permissions());
     }
 
+    #[test]
+    fn copy_file_large() {
+        let tmpdir = tmpdir();
+        let input = tmpdir.join("in.txt");
+        let out = tmpdir.join("out.txt");
+
+        // Large enough that a single read into a stack buffer can't cover it.
+        let mut bytes = vec![0; 1024 * 1024 + 17];
+        StdRng::new().unwrap().fill_bytes(&mut bytes);
+        check!(fs::write(&input, &bytes));
+
+        assert_eq!(check!(fs::copy(&input, &out)), bytes.len() as u64);
+        assert!(check!(fs::read(&out)) == bytes);
+    }
+
+    #[test]
+    fn io_copy_between_files_respects_offsets() {
+        let tmpdir = tmpdir();
+        let input = tmpdir.join("in.txt");
+        let out = tmpdir.join("out.txt");
+        check!(fs::write(&input, b"0123456789"));
+
+        let mut reader = check!(File::open(&input));
+        check!(reader.seek(SeekFrom::Start(4)));
+        let mut writer = check!(File::create(&out));
+        check!(writer.write_all(b"ab"));
+
+        assert_eq!(check!(io::copy(&mut reader, &mut writer)), 6);
+        assert_eq!(check!(reader.seek(SeekFrom::Current(0))), 10);
+        assert_eq!(check!(writer.seek(SeekFrom::Current(0))), 8);
+        assert_eq!(check!(fs::read(&out)), b"ab456789");
+    }
+
     #[test]
     fn copy_file_dst_dir() {
         let tmpdir = tmpdir();
@@ -3111,4 +6155,455 @@ mod tests {
             check!(b.created());
         }
     }
+
+    #[test]
+    fn copy_options() {
+        use fs::{CopyOptions, Reflink};
+        use time::{Duration, UNIX_EPOCH};
+
+        let tmpdir = tmpdir();
+        let src = tmpdir.join("src");
+        let dst = tmpdir.join("dst");
+        check!(check!(File::create(&src)).write_all(b"hello"));
+        let modified = UNIX_EPOCH + Duration::from_secs(54321);
+        check!(fs::set_times(&src, fs::FileTimes::new().set_modified(modified)));
+
+        let mut opts = CopyOptions::new();
+        opts.reflink(Reflink::Auto).preserve_timestamps(true).overwrite(false);
+        assert_eq!(check!(opts.copy(&src, &dst)), 5);
+        let mut v = Vec::new();
+        check!(check!(File::open(&dst)).read_to_end(&mut v));
+        assert_eq!(v, b"hello");
+        assert_eq!(check!(check!(fs::metadata(&dst)).modified()), modified);
+
+        let err = opts.copy(&src, &dst).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+
+        if cfg!(unix) {
+            let link = tmpdir.join("link");
+            let link_copy = tmpdir.join("link_copy");
+            check!(symlink_file(&src, &link));
+            assert_eq!(check!(opts.copy_symlinks(true).copy(&link, &link_copy)), 0);
+            assert!(check!(fs::symlink_metadata(&link_copy)).file_type().is_symlink());
+            assert_eq!(check!(fs::read_link(&link_copy)), src);
+        }
+    }
+
+    #[test]
+    fn walk_dir_orders_and_depths() {
+        let tmpdir = tmpdir();
+        check!(fs::create_dir_all(tmpdir.join("a/b")));
+        check!(File::create(tmpdir.join("a/b/c")));
+        check!(File::create(tmpdir.join("a/d")));
+        check!(File::create(tmpdir.join("e")));
+
+        let walk = |w: fs::WalkDir| {
+            w.map(|e| {
+                let e = check!(e);
+                let path = check!(e.path().strip_prefix(tmpdir.path())).to_path_buf();
+                (path.to_str().unwrap().replace("\\", "/"), e.depth())
+            }).collect::<Vec<_>>()
+        };
+        let v = |items: &[(&str, usize)]| {
+            items.iter().map(|&(p, d)| (p.to_string(), d)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(walk(fs::walk_dir(tmpdir.path()).sort_by_file_name()),
+                   v(&[("a", 1), ("a/b", 2), ("a/b/c", 3), ("a/d", 2), ("e", 1)]));
+        assert_eq!(walk(fs::walk_dir(tmpdir.path()).sort_by_file_name().contents_first(true)),
+                   v(&[("a/b/c", 3), ("a/b", 2), ("a/d", 2), ("a", 1), ("e", 1)]));
+        assert_eq!(walk(fs::walk_dir(tmpdir.path()).sort_by_file_name()
+                        .min_depth(2).max_depth(2)),
+                   v(&[("a/b", 2), ("a/d", 2)]));
+
+        let mut w = fs::walk_dir(tmpdir.path()).sort_by_file_name();
+        let mut seen = Vec::new();
+        while let Some(e) = w.next() {
+            let e = check!(e);
+            let name = e.file_name().into_string().unwrap();
+            if name == "a" {
+                w.skip_current_dir();
+            }
+            seen.push(name);
+        }
+        assert_eq!(seen, ["a", "e"]);
+
+        let err = fs::walk_dir(tmpdir.join("missing")).next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn walk_dir_symlink_loop() {
+        let tmpdir = tmpdir();
+        check!(fs::create_dir(tmpdir.join("a")));
+        check!(symlink_dir(tmpdir.path(), &tmpdir.join("a/up")));
+
+        // Without following links the walk stops at the link.
+        let n = fs::walk_dir(tmpdir.path()).map(|e| check!(e)).count();
+        assert_eq!(n, 2);
+
+        // Following it, the link is yielded but the loop is reported instead
+        // of being walked.
+        let results = fs::walk_dir(tmpdir.path()).follow_links(true).collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        let link = check!(results[1].as_ref());
+        assert!(link.path_is_symlink() && link.file_type().is_dir());
+        assert!(results[2].is_err());
+    }
+
+    #[test]
+    fn rename_variants() {
+        let tmpdir = tmpdir();
+        let a = tmpdir.join("a");
+        let b = tmpdir.join("b");
+        check!(check!(File::create(&a)).write_all(b"a"));
+        check!(check!(File::create(&b)).write_all(b"b"));
+
+        let err = fs::rename_noreplace(&a, &b).unwrap_err();
+        match err.kind() {
+            ErrorKind::Unsupported | ErrorKind::InvalidInput => return,
+            kind => assert_eq!(kind, ErrorKind::AlreadyExists),
+        }
+        assert_eq!(check!(fs::read(&b)), b"b");
+
+        match fs::rename_exchange(&a, &b) {
+            Ok(()) => {
+                assert_eq!(check!(fs::read(&a)), b"b");
+                assert_eq!(check!(fs::read(&b)), b"a");
+            }
+            Err(e) => assert!(e.kind() == ErrorKind::Unsupported ||
+                              e.kind() == ErrorKind::InvalidInput),
+        }
+
+        let c = tmpdir.join("c");
+        check!(fs::rename_noreplace(&a, &c));
+        assert!(!a.exists() && c.exists());
+    }
+
+    #[test]
+    fn set_get_file_times() {
+        use fs::FileTimes;
+        use time::{Duration, UNIX_EPOCH};
+
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("foo");
+        let file = check!(File::create(&path));
+
+        let accessed = UNIX_EPOCH + Duration::from_secs(12345);
+        let modified = UNIX_EPOCH + Duration::from_secs(54321);
+        check!(file.set_times(FileTimes::new().set_accessed(accessed).set_modified(modified)));
+        let metadata = check!(file.metadata());
+        assert_eq!(check!(metadata.accessed()), accessed);
+        assert_eq!(check!(metadata.modified()), modified);
+
+        // Times that aren't set are left alone.
+        let later = modified + Duration::from_secs(1);
+        check!(fs::set_times(&path, FileTimes::new().set_modified(later)));
+        let metadata = check!(fs::metadata(&path));
+        assert_eq!(check!(metadata.accessed()), accessed);
+        assert_eq!(check!(metadata.modified()), later);
+    }
+
+    #[test]
+    fn temp_file_persist() {
+        let tmpdir = tmpdir();
+        let mut tmp = check!(fs::TempFile::new_in(tmpdir.path()));
+        let temp_path = tmp.path().to_path_buf();
+        assert!(temp_path.starts_with(tmpdir.path()));
+        check!(tmp.write_all(b"persisted"));
+
+        // A failed persist keeps the file and its data around.
+        let e = tmp.persist(tmpdir.join("missing").join("target")).unwrap_err();
+        assert_eq!(e.error().kind(), ErrorKind::NotFound);
+        let tmp = e.into_inner();
+        assert!(temp_path.exists());
+
+        let target = tmpdir.join("target");
+        drop(check!(tmp.persist(&target)));
+        assert!(!temp_path.exists());
+        assert_eq!(check!(fs::read(&target)), b"persisted");
+
+        let tmp = check!(fs::TempFile::new_in(tmpdir.path()));
+        let temp_path = tmp.path().to_path_buf();
+        assert!(temp_path.exists());
+        drop(tmp);
+        assert!(!temp_path.exists());
+    }
+
+    #[test]
+    fn temp_file_anonymous() {
+        let tmpdir = tmpdir();
+        let mut file = check!(fs::TempFile::anonymous_in(tmpdir.path()));
+        check!(file.write_all(b"anonymous"));
+        check!(file.seek(SeekFrom::Start(0)));
+        let mut s = String::new();
+        check!(file.read_to_string(&mut s));
+        assert_eq!(s, "anonymous");
+        #[cfg(unix)]
+        assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 0);
+    }
+
+    #[test]
+    fn temp_dir_cleanup() {
+        let tmpdir = tmpdir();
+        let dir = check!(fs::TempDir::new_in(tmpdir.path()));
+        let path = dir.path().to_path_buf();
+        check!(fs::create_dir(path.join("sub")));
+        check!(fs::write(path.join("sub/file"), b"x"));
+        drop(dir);
+        assert!(!path.exists());
+
+        let dir = check!(fs::TempDir::new_in(tmpdir.path()));
+        let kept = dir.into_path();
+        assert!(kept.is_dir());
+        check!(fs::TempDir::new_in(tmpdir.path())).close().unwrap();
+    }
+
+    #[test]
+    fn write_atomic() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("config");
+        check!(fs::write_atomic(&path, b"first"));
+        assert_eq!(check!(fs::read(&path)), b"first");
+
+        #[cfg(unix)]
+        {
+            use os::unix::fs::PermissionsExt;
+            check!(fs::set_permissions(&path, fs::Permissions::from_mode(0o640)));
+        }
+
+        let mut writer = check!(fs::AtomicWriter::new(&path));
+        check!(writer.write_all(b"second"));
+        assert_eq!(check!(fs::read(&path)), b"first");
+        check!(writer.commit());
+        assert_eq!(check!(fs::read(&path)), b"second");
+        #[cfg(unix)]
+        {
+            use os::unix::fs::PermissionsExt;
+            assert_eq!(check!(fs::metadata(&path)).permissions().mode() & 0o777, 0o640);
+        }
+
+        // Dropping the writer leaves the file alone.
+        let mut writer = check!(fs::AtomicWriter::new(&path));
+        check!(writer.write_all(b"third"));
+        drop(writer);
+        assert_eq!(check!(fs::read(&path)), b"second");
+        assert_eq!(check!(fs::read_dir(tmpdir.path())).count(), 1);
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    fn xattrs() {
+        use os::unix::fs::{self as ufs, FileXattrExt};
+
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("tagged");
+        let file = check!(File::create(&path));
+        match file.set_xattr("user.build", b"1234") {
+            Ok(()) => {}
+            // The temporary directory doesn't support extended attributes.
+            Err(ref e) if e.raw_os_error() == Some(::libc::ENOTSUP) => return,
+            Err(e) => panic!("set_xattr failed with: {}", e),
+        }
+        assert_eq!(check!(ufs::get_xattr(&path, "user.build")), b"1234");
+        check!(ufs::set_xattr(&path, "user.empty", b""));
+        assert_eq!(check!(file.get_xattr("user.empty")), b"");
+
+        let mut names: Vec<String> = check!(file.list_xattr()).into_iter()
+            .filter_map(|name| name.into_string().ok())
+            .filter(|name| name.starts_with("user."))
+            .collect();
+        names.sort();
+        assert_eq!(names, ["user.build", "user.empty"]);
+
+        check!(ufs::remove_xattr(&path, "user.build"));
+        assert!(file.get_xattr("user.build").is_err());
+        assert!(ufs::remove_xattr_nofollow(&path, "user.build").is_err());
+    }
+
+    #[test]
+    fn mmap() {
+        use fs::MmapOptions;
+
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("mapped");
+        check!(fs::write(&path, b"hello, mapped world"));
+        let file = check!(OpenOptions::new().read(true).write(true).open(&path));
+
+        let map = check!(unsafe { MmapOptions::new().map(&file) });
+        assert_eq!(&map[..], b"hello, mapped world");
+        check!(map.advise(fs::Advice::Sequential));
+        let part = check!(unsafe { MmapOptions::new().offset(7).len(6).map(&file) });
+        assert_eq!(&part[..], b"mapped");
+
+        let mut copy = check!(unsafe { MmapOptions::new().map_copy(&file) });
+        copy[..5].copy_from_slice(b"HELLO");
+        assert_eq!(check!(fs::read(&path)), b"hello, mapped world");
+        let e = copy.advise(fs::Advice::DontNeed).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::InvalidInput);
+        assert_eq!(&copy[..5], b"HELLO");
+
+        let mut shared = check!(unsafe { MmapOptions::new().offset(14).map_mut(&file) });
+        shared.copy_from_slice(b"WORLD");
+        check!(shared.flush_range(0, 5));
+        assert!(shared.flush_range(1, 5).is_err());
+        assert_eq!(check!(fs::read(&path)), b"hello, mapped WORLD");
+        assert_eq!(&map[14..], b"WORLD");
+
+        let empty = check!(File::create(tmpdir.join("empty")));
+        let map = check!(unsafe { MmapOptions::new().map(&empty) });
+        assert!(map.is_empty());
+        assert!(unsafe { MmapOptions::new().offset(1).map(&empty) }.is_err());
+    }
+
+    #[test]
+    fn allocate_punch_hole_extents() {
+        let tmpdir = tmpdir();
+        let mut file = check!(OpenOptions::new().read(true).write(true).create(true)
+                                                .open(tmpdir.join("sparse")));
+        match file.allocate(0, 1 << 20) {
+            Ok(()) => {
+                assert_eq!(check!(file.metadata()).len(), 1 << 20);
+                // Allocating within the file doesn't shrink it.
+                check!(file.allocate(0, 10));
+                assert_eq!(check!(file.metadata()).len(), 1 << 20);
+            }
+            Err(ref e) if e.kind() == ErrorKind::Unsupported => check!(file.set_len(1 << 20)),
+            Err(e) => panic!("allocate failed with: {}", e),
+        }
+        check!((&file).write_all(&[1; 4096]));
+
+        match file.punch_hole(0, 1 << 16) {
+            Ok(()) => {
+                let mut buf = [1; 4096];
+                check!((&file).seek(SeekFrom::Start(0)));
+                check!((&file).read_exact(&mut buf));
+                assert!(buf.iter().all(|&b| b == 0));
+            }
+            Err(ref e) if e.kind() == ErrorKind::Unsupported => {}
+            Err(e) => panic!("punch_hole failed with: {}", e),
+        }
+        assert_eq!(check!(file.metadata()).len(), 1 << 20);
+
+        let pos = check!((&file).seek(SeekFrom::Start(1234)));
+        let mut next = 0;
+        for extent in check!(file.extents()) {
+            let extent = check!(extent);
+            assert_eq!(extent.offset(), next);
+            assert!(extent.len() > 0);
+            next += extent.len();
+        }
+        assert_eq!(next, 1 << 20);
+        assert_eq!(check!((&file).seek(SeekFrom::Current(0))), pos);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn unix_chown_mkfifo() {
+        use os::unix::fs::{self as ufs, FileOwnerExt, FileTypeExt, MetadataExt};
+
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("owned");
+        let file = check!(File::create(&path));
+        let metadata = check!(file.metadata());
+
+        // Setting the current owner and group is always allowed.
+        check!(file.chown(Some(metadata.uid()), None));
+        check!(ufs::chown(&path, None, Some(metadata.gid())));
+        check!(ufs::lchown(&path, None, None));
+        let after = check!(fs::metadata(&path));
+        assert_eq!((after.uid(), after.gid()), (metadata.uid(), metadata.gid()));
+
+        let fifo = tmpdir.join("fifo");
+        check!(ufs::mkfifo(&fifo, 0o600));
+        assert!(check!(fs::symlink_metadata(&fifo)).file_type().is_fifo());
+        assert_eq!(ufs::mkfifo(&fifo, 0o600).unwrap_err().kind(), ErrorKind::AlreadyExists);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn linux_watcher() {
+        use os::linux::fs::{Event, EventKind, Watcher};
+        use time::Duration;
+
+        let tmpdir = tmpdir();
+        let mut watcher = check!(Watcher::new());
+        check!(watcher.watch(tmpdir.path(), true));
+        fn next(watcher: &mut Watcher) -> Event {
+            check!(watcher.recv_timeout(Duration::from_secs(5))).expect("no event received")
+        }
+
+        let a = tmpdir.join("a");
+        check!(File::create(&a));
+        let event = next(&mut watcher);
+        assert_eq!((event.kind(), event.path()), (EventKind::Create, Some(&*a)));
+
+        let b = tmpdir.join("b");
+        check!(fs::rename(&a, &b));
+        let event = next(&mut watcher);
+        assert_eq!(event.kind(), EventKind::Rename);
+        assert_eq!((event.from_path(), event.path()), (Some(&*a), Some(&*b)));
+
+        // Directories created later on are watched as well.
+        let dir = tmpdir.join("dir");
+        check!(fs::create_dir(&dir));
+        let event = next(&mut watcher);
+        assert_eq!((event.kind(), event.is_dir()), (EventKind::Create, true));
+        check!(fs::write(dir.join("c"), b"c"));
+        let event = next(&mut watcher);
+        assert_eq!((event.kind(), event.path()), (EventKind::Create, Some(&*dir.join("c"))));
+
+        check!(watcher.unwatch(tmpdir.path()));
+        check!(fs::remove_file(&b));
+        while let Some(event) = check!(watcher.try_recv()) {
+            assert!(event.path() != Some(&*b), "event after unwatch: {:?}", event);
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn linux_watcher_file() {
+        use os::linux::fs::{EventKind, Watcher};
+        use time::Duration;
+
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("watched");
+        check!(File::create(&path));
+        let mut watcher = check!(Watcher::new());
+        check!(watcher.watch(&path, false));
+
+        // Truncating the file may be reported as an attribute change first.
+        check!(fs::write(&path, b"changed"));
+        loop {
+            let event = check!(watcher.recv_timeout(Duration::from_secs(5)))
+                .expect("no event received");
+            assert_eq!(event.path(), Some(&*path));
+            assert!(!event.is_dir());
+            if event.kind() == EventKind::Modify {
+                break
+            }
+        }
+    }
+
+    #[test]
+    fn fs_space() {
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("file");
+        let file = check!(File::create(&path));
+
+        let stats = check!(fs::space(&path));
+        assert!(stats.total_space() > 0);
+        assert!(stats.free_space() <= stats.total_space());
+        assert!(stats.available_space() <= stats.free_space());
+        assert!(stats.max_name_len() >= 12);
+        assert!(!stats.is_read_only());
+
+        let file_stats = check!(file.fs_stats());
+        assert_eq!(file_stats.fs_id(), stats.fs_id());
+        assert_eq!(file_stats.total_space(), stats.total_space());
+
+        let e = fs::space(tmpdir.join("missing")).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::NotFound);
+    }
 }
@@ -15,8 +15,10 @@ use io::prelude::*;
 use cmp;
 use error;
 use fmt;
-use io::{self, Initializer, DEFAULT_BUF_SIZE, Error, ErrorKind, SeekFrom};
+use io::{self, Initializer, DEFAULT_BUF_SIZE, Error, ErrorKind, SeekFrom, IoSlice, ReadBuf};
+use mem;
 use memchr;
+use ptr;
 
 /// The `BufReader` struct adds buffering to any reader.
 ///
@@ -172,6 +174,117 @@ impl<R: Read> BufReader<R> {
         self.pos == self.cap
     }
 
+    /// Returns a reference to the internally buffered data.
+    ///
+    /// Unlike `fill_buf`, this will not attempt to fill the buffer if it is
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(bufreader_buffer)]
+    /// use std::io::{BufReader, BufRead};
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::open("log.txt")?;
+    /// let mut reader = BufReader::new(f);
+    /// assert!(reader.buffer().is_empty());
+    ///
+    /// if reader.fill_buf()?.len() > 0 {
+    ///     assert!(!reader.buffer().is_empty());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "bufreader_buffer", issue = "0")]
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..self.cap]
+    }
+
+    /// Returns the number of bytes the internal buffer can hold at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(bufreader_buffer)]
+    /// use std::io::BufReader;
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::open("log.txt")?;
+    /// let reader = BufReader::with_capacity(10, f);
+    /// assert_eq!(reader.capacity(), 10);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "bufreader_buffer", issue = "0")]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Throws away all data in the internal buffer.
+    ///
+    /// The underlying reader is not touched, so the discarded data is lost:
+    /// the next read continues where the last read from the underlying
+    /// reader stopped.
+    #[unstable(feature = "bufreader_buffer", issue = "0")]
+    pub fn discard_buffer(&mut self) {
+        self.pos = 0;
+        self.cap = 0;
+    }
+
+    /// Reads more data from the underlying reader into the internal buffer,
+    /// keeping the data that is already buffered, and returns all of the
+    /// buffered data.
+    ///
+    /// Unlike `fill_buf`, this reads from the underlying reader even if the
+    /// buffer is not empty, which makes it possible to look further ahead.
+    /// Buffered data is first moved to the start of the buffer to make room.
+    /// If the buffer is already full, nothing is read.
+    ///
+    /// The returned slice ends up no longer than before if the underlying
+    /// reader has reached its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(bufreader_buffer)]
+    /// use std::io::{BufReader, BufRead};
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::open("log.txt")?;
+    /// let mut reader = BufReader::new(f);
+    ///
+    /// // Make sure at least a complete 8 byte header is buffered.
+    /// while reader.buffer().len() < 8 {
+    ///     let available = reader.buffer().len();
+    ///     if reader.refill()?.len() == available {
+    ///         break;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "bufreader_buffer", issue = "0")]
+    pub fn refill(&mut self) -> io::Result<&[u8]> {
+        if self.pos > 0 {
+            let len = self.cap - self.pos;
+            unsafe {
+                ptr::copy(self.buf.as_ptr().offset(self.pos as isize),
+                          self.buf.as_mut_ptr(),
+                          len);
+            }
+            self.pos = 0;
+            self.cap = len;
+        }
+        if self.cap < self.buf.len() {
+            self.cap += self.inner.read(&mut self.buf[self.cap..])?;
+        }
+        Ok(&self.buf[self.pos..self.cap])
+    }
+
     /// Unwraps this `BufReader`, returning the underlying reader.
     ///
     /// Note that any leftover data in the internal buffer is lost.
@@ -194,6 +307,49 @@ impl<R: Read> BufReader<R> {
     pub fn into_inner(self) -> R { self.inner }
 }
 
+impl<R: Seek> BufReader<R> {
+    /// Seeks relative to the current position. If the new position lies within
+    /// the buffer, the buffer will not be flushed, allowing for more efficient
+    /// seeks. This method does not return the location of the underlying
+    /// reader, so the caller must track this information themselves if it is
+    /// required.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(bufreader_buffer)]
+    /// use std::io::{BufReader, BufRead};
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> std::io::Result<()> {
+    /// let f = File::open("log.txt")?;
+    /// let mut reader = BufReader::new(f);
+    ///
+    /// // Skip a 4 byte record header without throwing the buffer away.
+    /// reader.fill_buf()?;
+    /// reader.seek_relative(4)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "bufreader_buffer", issue = "0")]
+    pub fn seek_relative(&mut self, offset: i64) -> io::Result<()> {
+        let pos = self.pos as u64;
+        if offset < 0 {
+            // `wrapping_neg` gives the right magnitude even for `i64::min_value()`.
+            if let Some(new_pos) = pos.checked_sub(offset.wrapping_neg() as u64) {
+                self.pos = new_pos as usize;
+                return Ok(())
+            }
+        } else if let Some(new_pos) = pos.checked_add(offset as u64) {
+            if new_pos <= self.cap as u64 {
+                self.pos = new_pos as usize;
+                return Ok(())
+            }
+        }
+        self.seek(SeekFrom::Current(offset)).map(|_| ())
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<R: Read> Read for BufReader<R> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
@@ -201,6 +357,9 @@ impl<R: Read> Read for BufReader<R> {
         // (larger than our internal buffer), bypass our internal buffer
         // entirely.
         if self.pos == self.cap && buf.len() >= self.buf.len() {
+            // The consumed data left in the buffer no longer precedes the
+            // position of the underlying reader.
+            self.discard_buffer();
             return self.inner.read(buf);
         }
         let nread = {
@@ -211,6 +370,24 @@ impl<R: Read> Read for BufReader<R> {
         Ok(nread)
     }
 
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> io::Result<()> {
+        // If we don't have any buffered data and we're doing a massive read
+        // (larger than our internal buffer), bypass our internal buffer
+        // entirely.
+        if self.pos == self.cap && buf.remaining() >= self.buf.len() {
+            self.discard_buffer();
+            return self.inner.read_buf(buf);
+        }
+        let nread = {
+            let mut rem = self.fill_buf()?;
+            let prev_filled = buf.filled_len();
+            rem.read_buf(buf)?;
+            buf.filled_len() - prev_filled
+        };
+        self.consume(nread);
+        Ok(())
+    }
+
     // we can't skip unconditionally because of the large buffer case in read.
     unsafe fn initializer(&self) -> Initializer {
         self.inner.initializer()
@@ -258,7 +435,8 @@ impl<R: Seek> Seek for BufReader<R> {
     /// Seeking always discards the internal buffer, even if the seek position
     /// would otherwise fall within it. This guarantees that calling
     /// `.into_inner()` immediately after a seek yields the underlying reader
-    /// at the same position.
+    /// at the same position. To seek without discarding the buffer, use
+    /// [`seek_relative`].
     ///
     /// See `std::io::Seek` for more details.
     ///
@@ -383,6 +561,19 @@ pub struct BufWriter<W: Write> {
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct IntoInnerError<W>(W, Error);
 
+/// Error returned by [`BufWriter::into_parts`] when the underlying writer
+/// panicked during an earlier write.
+///
+/// The buffered data may then have been partially written already, so it is
+/// only handed back through [`into_inner`] rather than written out.
+///
+/// [`BufWriter::into_parts`]: struct.BufWriter.html#method.into_parts
+/// [`into_inner`]: #method.into_inner
+#[unstable(feature = "bufwriter_into_parts", issue = "0")]
+pub struct WriterPanicked {
+    buf: Vec<u8>,
+}
+
 impl<W: Write> BufWriter<W> {
     /// Creates a new `BufWriter` with a default buffer capacity.
     ///
@@ -482,6 +673,84 @@ impl<W: Write> BufWriter<W> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn get_mut(&mut self) -> &mut W { self.inner.as_mut().unwrap() }
 
+    /// Returns a reference to the internally buffered data.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #![feature(bufreader_buffer)]
+    /// use std::io::BufWriter;
+    /// use std::net::TcpStream;
+    ///
+    /// let buf_writer = BufWriter::new(TcpStream::connect("127.0.0.1:34254").unwrap());
+    ///
+    /// // See how many bytes are currently buffered
+    /// let bytes_buffered = buf_writer.buffer().len();
+    /// ```
+    #[unstable(feature = "bufreader_buffer", issue = "0")]
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Returns the number of bytes the internal buffer can hold without
+    /// flushing.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #![feature(bufreader_buffer)]
+    /// use std::io::BufWriter;
+    /// use std::net::TcpStream;
+    ///
+    /// let buf_writer = BufWriter::new(TcpStream::connect("127.0.0.1:34254").unwrap());
+    ///
+    /// // Check the capacity of the inner buffer
+    /// let capacity = buf_writer.capacity();
+    /// // Calculate how many bytes can be written without flushing
+    /// let without_flush = capacity - buf_writer.buffer().len();
+    /// ```
+    #[unstable(feature = "bufreader_buffer", issue = "0")]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Disassembles this `BufWriter`, returning the underlying writer and
+    /// any buffered but unwritten data.
+    ///
+    /// Unlike [`into_inner`], this does not write out the buffer, so no data
+    /// is lost if the underlying writer is failing.
+    ///
+    /// If the underlying writer panicked during an earlier write, it is not
+    /// known what part of the buffer was written, and the data is returned
+    /// wrapped in a [`WriterPanicked`] error instead.
+    ///
+    /// [`into_inner`]: #method.into_inner
+    /// [`WriterPanicked`]: struct.WriterPanicked.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(bufwriter_into_parts)]
+    /// use std::io::{BufWriter, Write};
+    ///
+    /// let mut buffer = [0u8; 10];
+    /// let mut stream = BufWriter::new(buffer.as_mut());
+    /// write!(stream, "too much data").unwrap();
+    /// stream.flush().expect_err("it doesn't fit");
+    /// let (recovered_writer, buffered_data) = stream.into_parts();
+    /// assert_eq!(recovered_writer.len(), 0);
+    /// assert_eq!(&buffered_data.unwrap(), b"ata");
+    /// ```
+    #[unstable(feature = "bufwriter_into_parts", issue = "0")]
+    pub fn into_parts(mut self) -> (W, Result<Vec<u8>, WriterPanicked>) {
+        let buf = mem::replace(&mut self.buf, Vec::new());
+        let buf = if !self.panicked { Ok(buf) } else { Err(WriterPanicked { buf }) };
+
+        // Taking the writer out also stops the destructor from flushing.
+        let inner = self.inner.take().unwrap();
+        (inner, buf)
+    }
+
     /// Unwraps this `BufWriter`, returning the underlying writer.
     ///
     /// The buffer is written out before returning the writer.
@@ -521,6 +790,25 @@ impl<W: Write> Write for BufWriter<W> {
             Write::write(&mut self.buf, buf)
         }
     }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        let total_len = bufs.iter().map(|b| b.len()).sum::<usize>();
+        if self.buf.len() + total_len > self.buf.capacity() {
+            self.flush_buf()?;
+        }
+        if total_len >= self.buf.capacity() {
+            self.panicked = true;
+            let r = self.inner.as_mut().unwrap().write_vectored(bufs);
+            self.panicked = false;
+            r
+        } else {
+            for buf in bufs {
+                self.buf.extend_from_slice(buf);
+            }
+            Ok(total_len)
+        }
+    }
+
     fn flush(&mut self) -> io::Result<()> {
         self.flush_buf().and_then(|()| self.get_mut().flush())
     }
@@ -641,6 +929,39 @@ impl<W> fmt::Display for IntoInnerError<W> {
     }
 }
 
+impl WriterPanicked {
+    /// Returns the perhaps-unwritten data. Some of this data may have been
+    /// written by the panicking call(s) to the underlying writer, so simply
+    /// writing it again is not a good idea.
+    #[unstable(feature = "bufwriter_into_parts", issue = "0")]
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+#[unstable(feature = "bufwriter_into_parts", issue = "0")]
+impl error::Error for WriterPanicked {
+    fn description(&self) -> &str {
+        "BufWriter inner writer panicked, what data remains unwritten is not known"
+    }
+}
+
+#[unstable(feature = "bufwriter_into_parts", issue = "0")]
+impl fmt::Display for WriterPanicked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", error::Error::description(self))
+    }
+}
+
+#[unstable(feature = "bufwriter_into_parts", issue = "0")]
+impl fmt::Debug for WriterPanicked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WriterPanicked")
+            .field("buffer", &format_args!("{}/{}", self.buf.len(), self.buf.capacity()))
+            .finish()
+    }
+}
+
 /// Wraps a writer and buffers output to it, flushing whenever a newline
 /// (`0x0a`, `'\n'`) is detected.
 ///
@@ -870,7 +1191,7 @@ impl<W: Write> fmt::Debug for LineWriter<W> where W: fmt::Debug {
 #[cfg(test)]
 mod tests {
     use io::prelude::*;
-    use io::{self, BufReader, BufWriter, LineWriter, SeekFrom};
+    use io::{self, BufReader, BufWriter, LineWriter, SeekFrom, IoSlice};
     use sync::atomic::{AtomicUsize, Ordering};
     use thread;
     use test;
@@ -942,6 +1263,76 @@ mod tests {
         assert_eq!(reader.seek(SeekFrom::Current(-2)).ok(), Some(3));
     }
 
+    #[test]
+    fn test_buffered_reader_seek_relative() {
+        let inner: &[u8] = &[5, 6, 7, 0, 1, 2, 3, 4];
+        let mut reader = BufReader::with_capacity(2, io::Cursor::new(inner));
+
+        assert!(reader.seek_relative(3).is_ok());
+        assert_eq!(reader.fill_buf().ok(), Some(&[0, 1][..]));
+        assert!(reader.seek_relative(0).is_ok());
+        assert_eq!(reader.fill_buf().ok(), Some(&[0, 1][..]));
+        // Stays within the buffer, so the underlying reader doesn't move.
+        assert!(reader.seek_relative(1).is_ok());
+        assert_eq!(reader.buffer(), &[1][..]);
+        assert_eq!(reader.get_ref().position(), 5);
+        assert!(reader.seek_relative(-1).is_ok());
+        assert_eq!(reader.buffer(), &[0, 1][..]);
+        assert!(reader.seek_relative(-2).is_ok());
+        assert_eq!(reader.fill_buf().ok(), Some(&[6, 7][..]));
+    }
+
+    #[test]
+    fn test_buffered_reader_seek_relative_after_large_read() {
+        let inner: Vec<u8> = (0..16).collect();
+        let mut reader = BufReader::with_capacity(4, io::Cursor::new(inner));
+
+        assert_eq!(reader.fill_buf().ok(), Some(&[0, 1, 2, 3][..]));
+        reader.consume(4);
+        // Bypasses the buffer, which must not be seeked back into afterwards.
+        let mut buf = [0; 8];
+        assert_eq!(reader.read(&mut buf).ok(), Some(8));
+        assert_eq!(buf, [4, 5, 6, 7, 8, 9, 10, 11]);
+        assert!(reader.seek_relative(-1).is_ok());
+        let mut byte = [0];
+        assert_eq!(reader.read(&mut byte).ok(), Some(1));
+        assert_eq!(byte, [11]);
+    }
+
+    #[test]
+    fn test_buffered_reader_buffer_and_refill() {
+        let inner: &[u8] = &[5, 6, 7, 0, 1, 2, 3, 4];
+        let mut reader = BufReader::with_capacity(4, inner);
+        assert_eq!(reader.capacity(), 4);
+        assert_eq!(reader.buffer(), &[][..]);
+
+        assert_eq!(reader.fill_buf().ok(), Some(&[5, 6, 7, 0][..]));
+        reader.consume(3);
+        assert_eq!(reader.buffer(), &[0][..]);
+        assert_eq!(reader.refill().ok(), Some(&[0, 1, 2, 3][..]));
+        // A full buffer is left alone.
+        assert_eq!(reader.refill().ok(), Some(&[0, 1, 2, 3][..]));
+
+        reader.discard_buffer();
+        assert_eq!(reader.buffer(), &[][..]);
+        assert_eq!(reader.fill_buf().ok(), Some(&[4][..]));
+        reader.consume(1);
+        assert_eq!(reader.refill().ok(), Some(&[][..]));
+    }
+
+    #[test]
+    fn test_buffered_writer_into_parts() {
+        let mut w = BufWriter::with_capacity(4, Vec::new());
+        assert_eq!(w.capacity(), 4);
+        w.write_all(&[0, 1, 2, 3, 4, 5]).unwrap();
+        w.write_all(&[6, 7]).unwrap();
+        assert_eq!(w.buffer(), &[6, 7][..]);
+
+        let (inner, buf) = w.into_parts();
+        assert_eq!(inner, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(buf.unwrap(), [6, 7]);
+    }
+
     #[test]
     fn test_buffered_reader_seek_underflow() {
         // gimmick reader that yields its position modulo 256 for each byte
@@ -1022,6 +1413,27 @@ mod tests {
         assert_eq!(*writer.get_ref(), [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
     }
 
+    #[test]
+    fn test_buffered_writer_vectored() {
+        let inner = Vec::new();
+        let mut writer = BufWriter::with_capacity(4, inner);
+
+        let n = writer.write_vectored(&[IoSlice::new(&[0]), IoSlice::new(&[1, 2])]).unwrap();
+        assert_eq!(n, 3);
+        assert_eq!(*writer.get_ref(), []);
+
+        // Too large for the buffer: flushes and goes straight to the writer.
+        let n = writer.write_vectored(&[IoSlice::new(&[3, 4]),
+                                        IoSlice::new(&[]),
+                                        IoSlice::new(&[5, 6, 7])]).unwrap();
+        assert_eq!(n, 5);
+        assert_eq!(*writer.get_ref(), [0, 1, 2, 3, 4, 5, 6, 7]);
+
+        writer.write_vectored(&[IoSlice::new(&[8])]).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(*writer.get_ref(), [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
     #[test]
     fn test_buffered_writer_inner_flushes() {
         let mut w = BufWriter::with_capacity(3, Vec::new());
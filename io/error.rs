@@ -10,6 +10,7 @@
 
 use error;
 use fmt;
+use path::{Path, PathBuf};
 use result;
 use sys;
 use convert::From;
@@ -58,6 +59,15 @@ pub type Result<T> = result::Result<T, Error>;
 /// `Error` can be created with crafted error messages and a particular value of
 /// [`ErrorKind`].
 ///
+/// Errors returned by the functions in [`std::fs`] additionally record the
+/// operation that failed and the path(s) it was working on, which are
+/// available through [`operation`], [`path`] and [`other_path`] and are
+/// included in the error's `Display` output.
+///
+/// [`std::fs`]: ../fs/index.html
+/// [`operation`]: #method.operation
+/// [`path`]: #method.path
+/// [`other_path`]: #method.other_path
 /// [`Read`]: ../io/trait.Read.html
 /// [`Write`]: ../io/trait.Write.html
 /// [`Seek`]: ../io/trait.Seek.html
@@ -72,6 +82,7 @@ enum Repr {
     Os(i32),
     Simple(ErrorKind),
     Custom(Box<Custom>),
+    Context(Box<Context>),
 }
 
 #[derive(Debug)]
@@ -80,6 +91,16 @@ struct Custom {
     error: Box<error::Error+Send+Sync>,
 }
 
+// An error annotated with what was being done when it occurred. Everything
+// other than the accessors for the context itself is forwarded to `error`.
+#[derive(Debug)]
+struct Context {
+    error: Error,
+    operation: &'static str,
+    path: PathBuf,
+    other_path: Option<PathBuf>,
+}
+
 /// A list specifying general categories of I/O error.
 ///
 /// This list is intended to grow over time and it is not recommended to
@@ -175,6 +196,84 @@ pub enum ErrorKind {
     #[stable(feature = "read_exact", since = "1.6.0")]
     UnexpectedEof,
 
+    /// A filesystem object is, unexpectedly, not a directory.
+    ///
+    /// For example, a filesystem path was specified where one of the
+    /// intermediate directory components was, in fact, a plain file.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    NotADirectory,
+    /// The filesystem object is, unexpectedly, a directory.
+    ///
+    /// A directory was specified when a non-directory was expected.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    IsADirectory,
+    /// A non-empty directory was specified where an empty directory was
+    /// expected.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    DirectoryNotEmpty,
+    /// The filesystem or storage medium is read-only, but a write operation
+    /// was attempted.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    ReadOnlyFilesystem,
+    /// The underlying storage (typically, a filesystem) is full.
+    ///
+    /// This does not include out of quota errors, which are reported as
+    /// `QuotaExceeded`.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    StorageFull,
+    /// Filesystem quota was exceeded.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    QuotaExceeded,
+    /// File larger than allowed or supported.
+    ///
+    /// This might arise from a hard limit of the underlying filesystem or
+    /// file access API, or from an administratively imposed resource
+    /// limitation.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    FileTooLarge,
+    /// Resource is busy.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    ResourceBusy,
+    /// Cross-device or cross-filesystem (hard) link or rename.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    CrossesDevices,
+    /// Too many (hard) links to the same filesystem object.
+    ///
+    /// The filesystem does not support making so many hardlinks to the same
+    /// file.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    TooManyLinks,
+    /// A filename was invalid.
+    ///
+    /// This error can also be caused by a filename that is too long.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    InvalidFilename,
+    /// Program argument list too long.
+    ///
+    /// When trying to run an external program, a system or process limit on
+    /// the size of the arguments would have been exceeded.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    ArgumentListTooLong,
+    /// Deadlock (avoided).
+    ///
+    /// A file locking operation would result in deadlock. This situation is
+    /// typically detected, if at all, on a best-effort basis.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    Deadlock,
+    /// The remote host is not reachable.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    HostUnreachable,
+    /// The network containing the remote host is not reachable.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    NetworkUnreachable,
+    /// This operation is unsupported on this platform, or by the filesystem
+    /// or device involved.
+    ///
+    /// This means that the operation can never succeed, unlike an invalid
+    /// input which depends on the values passed.
+    #[unstable(feature = "io_error_more", issue = "0")]
+    Unsupported,
+
     /// A marker variant that tells the compiler that users of this enum cannot
     /// match it exhaustively.
     #[unstable(feature = "io_error_internals",
@@ -206,6 +305,22 @@ impl ErrorKind {
             ErrorKind::Interrupted => "operation interrupted",
             ErrorKind::Other => "other os error",
             ErrorKind::UnexpectedEof => "unexpected end of file",
+            ErrorKind::NotADirectory => "not a directory",
+            ErrorKind::IsADirectory => "is a directory",
+            ErrorKind::DirectoryNotEmpty => "directory not empty",
+            ErrorKind::ReadOnlyFilesystem => "read-only filesystem or storage medium",
+            ErrorKind::StorageFull => "no storage space",
+            ErrorKind::QuotaExceeded => "filesystem quota exceeded",
+            ErrorKind::FileTooLarge => "file too large",
+            ErrorKind::ResourceBusy => "resource busy",
+            ErrorKind::CrossesDevices => "cross-device link or rename",
+            ErrorKind::TooManyLinks => "too many links",
+            ErrorKind::InvalidFilename => "invalid filename",
+            ErrorKind::ArgumentListTooLong => "argument list too long",
+            ErrorKind::Deadlock => "deadlock",
+            ErrorKind::HostUnreachable => "host unreachable",
+            ErrorKind::NetworkUnreachable => "network unreachable",
+            ErrorKind::Unsupported => "unsupported",
             ErrorKind::__Nonexhaustive => unreachable!()
         }
     }
@@ -338,6 +453,7 @@ impl Error {
             Repr::Os(i) => Some(i),
             Repr::Custom(..) => None,
             Repr::Simple(..) => None,
+            Repr::Context(ref c) => c.error.raw_os_error(),
         }
     }
 
@@ -372,6 +488,7 @@ impl Error {
             Repr::Os(..) => None,
             Repr::Simple(..) => None,
             Repr::Custom(ref c) => Some(&*c.error),
+            Repr::Context(ref c) => c.error.get_ref(),
         }
     }
 
@@ -443,6 +560,7 @@ impl Error {
             Repr::Os(..) => None,
             Repr::Simple(..) => None,
             Repr::Custom(ref mut c) => Some(&mut *c.error),
+            Repr::Context(ref mut c) => c.error.get_mut(),
         }
     }
 
@@ -476,7 +594,8 @@ impl Error {
         match self.repr {
             Repr::Os(..) => None,
             Repr::Simple(..) => None,
-            Repr::Custom(c) => Some(c.error)
+            Repr::Custom(c) => Some(c.error),
+            Repr::Context(c) => c.error.into_inner(),
         }
     }
 
@@ -504,6 +623,106 @@ impl Error {
             Repr::Os(code) => sys::decode_error_kind(code),
             Repr::Custom(ref c) => c.kind,
             Repr::Simple(kind) => kind,
+            Repr::Context(ref c) => c.error.kind(),
+        }
+    }
+
+    /// Returns a description of the operation that failed (if known).
+    ///
+    /// Errors returned by the functions in [`std::fs`] record what they were
+    /// doing, for example `"open"` or `"remove directory"`, in addition to
+    /// the underlying error. For all other errors this returns `None`.
+    ///
+    /// [`std::fs`]: ../fs/index.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(io_error_context)]
+    /// use std::fs;
+    ///
+    /// let err = fs::remove_file("/this/file/does/not/exist").unwrap_err();
+    /// assert_eq!(err.operation(), Some("remove file"));
+    /// ```
+    #[unstable(feature = "io_error_context", issue = "0")]
+    pub fn operation(&self) -> Option<&str> {
+        match self.repr {
+            Repr::Context(ref c) => Some(c.operation),
+            _ => None,
+        }
+    }
+
+    /// Returns the path the failed operation was working on (if known).
+    ///
+    /// This is set on errors returned by the functions in [`std::fs`]. For
+    /// operations involving two paths, such as [`fs::rename`], this is the
+    /// first one and the second is available from [`other_path`].
+    ///
+    /// [`std::fs`]: ../fs/index.html
+    /// [`fs::rename`]: ../fs/fn.rename.html
+    /// [`other_path`]: #method.other_path
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(io_error_context)]
+    /// use std::fs::File;
+    /// use std::path::Path;
+    ///
+    /// let err = File::open("/this/file/does/not/exist").unwrap_err();
+    /// assert_eq!(err.path(), Some(Path::new("/this/file/does/not/exist")));
+    /// ```
+    #[unstable(feature = "io_error_context", issue = "0")]
+    pub fn path(&self) -> Option<&Path> {
+        match self.repr {
+            Repr::Context(ref c) => Some(&c.path),
+            _ => None,
+        }
+    }
+
+    /// Returns the second path the failed operation was working on (if any).
+    ///
+    /// This is the destination of operations such as [`fs::rename`] and
+    /// [`fs::copy`], and `None` for operations which only involve a single
+    /// path.
+    ///
+    /// [`fs::rename`]: ../fs/fn.rename.html
+    /// [`fs::copy`]: ../fs/fn.copy.html
+    #[unstable(feature = "io_error_context", issue = "0")]
+    pub fn other_path(&self) -> Option<&Path> {
+        match self.repr {
+            Repr::Context(ref c) => c.other_path.as_ref().map(|p| &**p),
+            _ => None,
+        }
+    }
+
+    /// Records that this error happened while performing `operation` on
+    /// `path`.
+    ///
+    /// Errors which already carry a context keep it, as it was recorded
+    /// closer to the actual failure.
+    pub(crate) fn with_path(self, operation: &'static str, path: &Path) -> Error {
+        self.with_context(operation, path, None)
+    }
+
+    /// Like `with_path`, for operations involving two paths.
+    pub(crate) fn with_paths(self, operation: &'static str, path: &Path, other: &Path)
+                             -> Error {
+        self.with_context(operation, path, Some(other))
+    }
+
+    fn with_context(self, operation: &'static str, path: &Path, other: Option<&Path>)
+                    -> Error {
+        if let Repr::Context(..) = self.repr {
+            return self
+        }
+        Error {
+            repr: Repr::Context(Box::new(Context {
+                error: self,
+                operation,
+                path: path.to_path_buf(),
+                other_path: other.map(|p| p.to_path_buf()),
+            }))
         }
     }
 }
@@ -516,6 +735,7 @@ impl fmt::Debug for Repr {
                    .field("message", &sys::os::error_string(*code)).finish(),
             Repr::Custom(ref c) => fmt.debug_tuple("Custom").field(c).finish(),
             Repr::Simple(kind) => fmt.debug_tuple("Kind").field(&kind).finish(),
+            Repr::Context(ref c) => fmt.debug_tuple("Context").field(c).finish(),
         }
     }
 }
@@ -530,6 +750,13 @@ impl fmt::Display for Error {
             }
             Repr::Custom(ref c) => c.error.fmt(fmt),
             Repr::Simple(kind) => write!(fmt, "{}", kind.as_str()),
+            Repr::Context(ref c) => {
+                write!(fmt, "failed to {} `{}`", c.operation, c.path.display())?;
+                if let Some(ref other) = c.other_path {
+                    write!(fmt, " to `{}`", other.display())?;
+                }
+                write!(fmt, ": {}", c.error)
+            }
         }
     }
 }
@@ -540,6 +767,7 @@ impl error::Error for Error {
         match self.repr {
             Repr::Os(..) | Repr::Simple(..) => self.kind().as_str(),
             Repr::Custom(ref c) => c.error.description(),
+            Repr::Context(ref c) => c.error.description(),
         }
     }
 
@@ -548,6 +776,7 @@ impl error::Error for Error {
             Repr::Os(..) => None,
             Repr::Simple(..) => None,
             Repr::Custom(ref c) => c.error.cause(),
+            Repr::Context(ref c) => c.error.cause(),
         }
     }
 }
@@ -562,6 +791,7 @@ mod test {
     use super::{Error, ErrorKind};
     use error;
     use fmt;
+    use path::Path;
     use sys::os::error_string;
 
     #[test]
@@ -573,6 +803,30 @@ mod test {
         assert_eq!(format!("{:?}", err), expected);
     }
 
+    #[test]
+    fn test_context() {
+        let path = Path::new("/some/file");
+        let err = Error::from_raw_os_error(6).with_path("open", path);
+        assert_eq!(err.raw_os_error(), Some(6));
+        assert_eq!(err.kind(), Error::from_raw_os_error(6).kind());
+        assert_eq!(err.operation(), Some("open"));
+        assert_eq!(err.path(), Some(path));
+        assert_eq!(err.to_string(),
+                   format!("failed to open `/some/file`: {} (os error 6)", error_string(6)));
+
+        // Context is only recorded once, closest to where the error happened.
+        let err = err.with_paths("copy", Path::new("/a"), Path::new("/b"));
+        assert_eq!(err.operation(), Some("open"));
+        assert_eq!(err.other_path(), None);
+
+        let err = Error::new(ErrorKind::InvalidData, "oh no")
+            .with_paths("copy", Path::new("/a"), Path::new("/b"));
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.other_path(), Some(Path::new("/b")));
+        assert_eq!(err.to_string(), "failed to copy `/a` to `/b`: oh no");
+        assert_eq!(err.into_inner().unwrap().to_string(), "oh no");
+    }
+
     #[test]
     fn test_downcasting() {
         #[derive(Debug)]
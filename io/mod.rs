@@ -277,22 +277,35 @@ use fmt;
 use result;
 use str;
 use memchr;
+use ops::{Deref, DerefMut};
 use ptr;
+use sys;
 
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::buffered::{BufReader, BufWriter, LineWriter};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::buffered::IntoInnerError;
+#[unstable(feature = "bufwriter_into_parts", issue = "0")]
+pub use self::buffered::WriterPanicked;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::cursor::Cursor;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::error::{Result, Error, ErrorKind};
+#[unstable(feature = "read_buf", issue = "0")]
+pub use self::readbuf::ReadBuf;
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+pub use self::pipe::{pipe, PipeReader, PipeWriter};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::util::{copy, sink, Sink, empty, Empty, repeat, Repeat};
+pub(crate) use self::util::generic_copy;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::stdio::{stdin, stdout, stderr, Stdin, Stdout, Stderr};
 #[stable(feature = "rust1", since = "1.0.0")]
 pub use self::stdio::{StdoutLock, StderrLock, StdinLock};
+#[unstable(feature = "is_terminal", issue = "0")]
+pub use self::stdio::IsTerminal;
+#[unstable(feature = "stdout_buffer_mode", issue = "0")]
+pub use self::stdio::BufferMode;
 #[unstable(feature = "print_internals", issue = "0")]
 pub use self::stdio::{_print, _eprint};
 #[unstable(feature = "libstd_io_internals", issue = "42788")]
@@ -305,6 +318,8 @@ mod cursor;
 mod error;
 mod impls;
 mod lazy;
+mod pipe;
+mod readbuf;
 mod util;
 mod stdio;
 
@@ -361,11 +376,17 @@ fn append_to_string<F>(buf: &mut String, f: F) -> Result<usize>
 // time is 4,500 times (!) slower than this if the reader has a very small
 // amount of data to return.
 //
-// Because we're extending the buffer with uninitialized data for trusted
-// readers, we need to make sure to truncate that if any of this panics.
+// The spare capacity is handed to the reader as a `ReadBuf`, and the number of
+// bytes it initialized without filling is carried over to the next iteration,
+// so each byte of the vector is zeroed at most once (and never, for readers
+// which write straight into uninitialized memory).
+//
+// Because we're extending the buffer with uninitialized data, we need to make
+// sure to truncate that if any of this panics.
 fn read_to_end<R: Read + ?Sized>(r: &mut R, buf: &mut Vec<u8>) -> Result<usize> {
     let start_len = buf.len();
     let mut g = Guard { len: buf.len(), buf: buf };
+    let mut initialized = 0;
     let ret;
     loop {
         if g.len == g.buf.len() {
@@ -373,17 +394,23 @@ fn read_to_end<R: Read + ?Sized>(r: &mut R, buf: &mut Vec<u8>) -> Result<usize>
                 g.buf.reserve(32);
                 let capacity = g.buf.capacity();
                 g.buf.set_len(capacity);
-                r.initializer().initialize(&mut g.buf[g.len..]);
             }
         }
 
-        match r.read(&mut g.buf[g.len..]) {
-            Ok(0) => {
+        let mut read_buf = unsafe { ReadBuf::uninit(&mut g.buf[g.len..]) };
+        unsafe { read_buf.assume_init(initialized); }
+
+        let result = r.read_buf(&mut read_buf);
+        let n = read_buf.filled_len();
+        initialized = read_buf.initialized_len() - n;
+
+        match result {
+            Ok(()) if n == 0 => {
                 ret = Ok(g.len - start_len);
                 break;
             }
-            Ok(n) => g.len += n,
-            Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+            Ok(()) => g.len += n,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => g.len += n,
             Err(e) => {
                 ret = Err(e);
                 break;
@@ -394,6 +421,46 @@ fn read_to_end<R: Read + ?Sized>(r: &mut R, buf: &mut Vec<u8>) -> Result<usize>
     ret
 }
 
+// The fallback used by the default `read_buf` method. Readers which promise
+// not to look at the buffers passed to them, through `Read::initializer`, are
+// handed the uninitialized part of the buffer directly; everything else gets
+// a zeroed slice.
+pub(crate) fn default_read_buf<R: Read + ?Sized>(r: &mut R, buf: &mut ReadBuf) -> Result<()> {
+    let n = unsafe {
+        if r.initializer().should_initialize() {
+            r.read(buf.initialize_unfilled())?
+        } else {
+            let n = r.read(buf.unfilled_mut())?;
+            buf.assume_init(n);
+            n
+        }
+    };
+    buf.add_filled(n);
+    Ok(())
+}
+
+// The fallbacks used by the default `read_vectored` and `write_vectored`
+// methods: readers and writers without native scatter/gather support just
+// operate on the first non-empty buffer, which is always a valid (if short)
+// result.
+pub(crate) fn default_read_vectored<F>(read: F, bufs: &mut [IoSliceMut]) -> Result<usize>
+    where F: FnOnce(&mut [u8]) -> Result<usize>
+{
+    let buf = bufs.iter_mut()
+                  .find(|b| !b.is_empty())
+                  .map_or(&mut [][..], |b| &mut **b);
+    read(buf)
+}
+
+pub(crate) fn default_write_vectored<F>(write: F, bufs: &[IoSlice]) -> Result<usize>
+    where F: FnOnce(&[u8]) -> Result<usize>
+{
+    let buf = bufs.iter()
+                  .find(|b| !b.is_empty())
+                  .map_or(&[][..], |b| &**b);
+    write(buf)
+}
+
 /// The `Read` trait allows for reading bytes from a source.
 ///
 /// Implementors of the `Read` trait are called 'readers'.
@@ -525,6 +592,85 @@ pub trait Read {
     #[stable(feature = "rust1", since = "1.0.0")]
     fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
 
+    /// Like [`read`], except that it reads into a slice of buffers.
+    ///
+    /// Data is copied to fill each buffer in order, with the final buffer
+    /// written to possibly being only partially filled. This method must
+    /// behave as a single call to [`read`] with the buffers concatenated
+    /// would.
+    ///
+    /// The default implementation calls [`read`] with the first nonempty
+    /// buffer provided, or an empty one if none exists. Readers backed by a
+    /// file descriptor override it to issue a single `readv` call.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(iovec)]
+    /// use std::io::{self, IoSliceMut};
+    /// use std::io::prelude::*;
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> io::Result<()> {
+    /// let mut f = File::open("foo.txt")?;
+    /// let mut header = [0; 4];
+    /// let mut body = [0; 512];
+    ///
+    /// let n = f.read_vectored(&mut [IoSliceMut::new(&mut header),
+    ///                               IoSliceMut::new(&mut body)])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`read`]: #tymethod.read
+    #[unstable(feature = "iovec", issue = "0")]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> Result<usize> {
+        default_read_vectored(|b| self.read(b), bufs)
+    }
+
+    /// Pull some bytes from this source into the specified buffer.
+    ///
+    /// This is equivalent to the [`read`] method, except that it is passed a
+    /// [`ReadBuf`] rather than `[u8]` to allow use with uninitialized
+    /// buffers. The new data will be appended to any existing contents of
+    /// `buf`, and the number of bytes read is the growth of
+    /// [`ReadBuf::filled_len`]. Leaving it unchanged while `buf` had room
+    /// left means the reader reached end of file.
+    ///
+    /// The default implementation zeroes the unfilled part of `buf` (at most
+    /// once, as `ReadBuf` remembers what has been initialized) and passes it
+    /// to [`read`], unless [`initializer`] says that isn't needed. Readers
+    /// backed by a file descriptor or socket, as well as the adapters in this
+    /// module, override it to read into the buffer directly.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(read_buf)]
+    /// use std::io::{self, ReadBuf};
+    /// use std::io::prelude::*;
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> io::Result<()> {
+    /// let mut f = File::open("foo.txt")?;
+    /// let mut storage = [0; 512];
+    /// let mut buf = ReadBuf::new(&mut storage);
+    ///
+    /// f.read_buf(&mut buf)?;
+    /// println!("The bytes: {:?}", buf.filled());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`read`]: #tymethod.read
+    /// [`initializer`]: #method.initializer
+    /// [`ReadBuf`]: struct.ReadBuf.html
+    /// [`ReadBuf::filled_len`]: struct.ReadBuf.html#method.filled_len
+    #[unstable(feature = "read_buf", issue = "0")]
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> Result<()> {
+        default_read_buf(self, buf)
+    }
+
     /// Determines if this `Read`er can work with buffers of uninitialized
     /// memory.
     ///
@@ -950,6 +1096,90 @@ impl Initializer {
     }
 }
 
+/// A buffer type used with `Read::read_vectored`.
+///
+/// It is semantically a wrapper around an `&mut [u8]`, but is guaranteed to
+/// be ABI compatible with the `iovec` type on Unix platforms.
+#[unstable(feature = "iovec", issue = "0")]
+#[repr(C)]
+pub struct IoSliceMut<'a>(sys::io::IoSliceMut<'a>);
+
+#[unstable(feature = "iovec", issue = "0")]
+impl<'a> fmt::Debug for IoSliceMut<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.0.as_slice(), fmt)
+    }
+}
+
+impl<'a> IoSliceMut<'a> {
+    /// Creates a new `IoSliceMut` wrapping a byte slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics on Windows if the slice is larger than 4GB.
+    #[unstable(feature = "iovec", issue = "0")]
+    #[inline]
+    pub fn new(buf: &'a mut [u8]) -> IoSliceMut<'a> {
+        IoSliceMut(sys::io::IoSliceMut::new(buf))
+    }
+}
+
+#[unstable(feature = "iovec", issue = "0")]
+impl<'a> Deref for IoSliceMut<'a> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+#[unstable(feature = "iovec", issue = "0")]
+impl<'a> DerefMut for IoSliceMut<'a> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.0.as_mut_slice()
+    }
+}
+
+/// A buffer type used with `Write::write_vectored`.
+///
+/// It is semantically a wrapper around an `&[u8]`, but is guaranteed to be
+/// ABI compatible with the `iovec` type on Unix platforms.
+#[unstable(feature = "iovec", issue = "0")]
+#[repr(C)]
+pub struct IoSlice<'a>(sys::io::IoSlice<'a>);
+
+#[unstable(feature = "iovec", issue = "0")]
+impl<'a> fmt::Debug for IoSlice<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.0.as_slice(), fmt)
+    }
+}
+
+impl<'a> IoSlice<'a> {
+    /// Creates a new `IoSlice` wrapping a byte slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics on Windows if the slice is larger than 4GB.
+    #[unstable(feature = "iovec", issue = "0")]
+    #[inline]
+    pub fn new(buf: &'a [u8]) -> IoSlice<'a> {
+        IoSlice(sys::io::IoSlice::new(buf))
+    }
+}
+
+#[unstable(feature = "iovec", issue = "0")]
+impl<'a> Deref for IoSlice<'a> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
 /// A trait for objects which are byte-oriented sinks.
 ///
 /// Implementors of the `Write` trait are sometimes called 'writers'.
@@ -1032,6 +1262,40 @@ pub trait Write {
     #[stable(feature = "rust1", since = "1.0.0")]
     fn write(&mut self, buf: &[u8]) -> Result<usize>;
 
+    /// Like [`write`], except that it writes from a slice of buffers.
+    ///
+    /// Data is copied from each buffer in order, with the final buffer read
+    /// from possibly being only partially consumed. This method must behave
+    /// as a call to [`write`] with the buffers concatenated would.
+    ///
+    /// The default implementation calls [`write`] with the first nonempty
+    /// buffer provided, or an empty one if none exists. Writers backed by a
+    /// file descriptor override it to issue a single `writev` call.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(iovec)]
+    /// use std::io::{self, IoSlice};
+    /// use std::io::prelude::*;
+    /// use std::fs::File;
+    ///
+    /// # fn foo() -> io::Result<()> {
+    /// let mut f = File::create("foo.txt")?;
+    ///
+    /// // write a header and a body without copying them into one buffer
+    /// let n = f.write_vectored(&[IoSlice::new(b"HEAD"),
+    ///                            IoSlice::new(b"some body")])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`write`]: #tymethod.write
+    #[unstable(feature = "iovec", issue = "0")]
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> Result<usize> {
+        default_write_vectored(|b| self.write(b), bufs)
+    }
+
     /// Flush this output stream, ensuring that all intermediately buffered
     /// contents reach their destination.
     ///
@@ -1729,6 +1993,18 @@ impl<T: Read, U: Read> Read for Chain<T, U> {
         self.second.read(buf)
     }
 
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> Result<()> {
+        if !self.done_first {
+            let prev_filled = buf.filled_len();
+            self.first.read_buf(buf)?;
+            if buf.filled_len() != prev_filled || buf.remaining() == 0 {
+                return Ok(());
+            }
+            self.done_first = true;
+        }
+        self.second.read_buf(buf)
+    }
+
     unsafe fn initializer(&self) -> Initializer {
         let initializer = self.first.initializer();
         if initializer.should_initialize() {
@@ -1927,6 +2203,36 @@ impl<T: Read> Read for Take<T> {
         Ok(n)
     }
 
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> Result<()> {
+        // Don't call into inner reader at all at EOF because it may still block
+        if self.limit == 0 {
+            return Ok(());
+        }
+
+        if self.limit < buf.remaining() as u64 {
+            // The inner reader gets a buffer cut down to the limit, carrying
+            // over what is known to be initialized in that part.
+            let limit = self.limit as usize;
+            let extra_init = cmp::min(limit, buf.initialized_len() - buf.filled_len());
+
+            let (filled, initialized) = {
+                let mut sliced = unsafe { ReadBuf::uninit(&mut buf.unfilled_mut()[..limit]) };
+                unsafe { sliced.assume_init(extra_init); }
+                self.inner.read_buf(&mut sliced)?;
+                (sliced.filled_len(), sliced.initialized_len())
+            };
+
+            unsafe { buf.assume_init(initialized); }
+            buf.add_filled(filled);
+            self.limit -= filled as u64;
+        } else {
+            let prev_filled = buf.filled_len();
+            self.inner.read_buf(buf)?;
+            self.limit -= (buf.filled_len() - prev_filled) as u64;
+        }
+        Ok(())
+    }
+
     unsafe fn initializer(&self) -> Initializer {
         self.inner.initializer()
     }
@@ -2362,6 +2668,80 @@ mod tests {
         assert_eq!("AB", s);
     }
 
+    #[test]
+    fn read_buf_tracks_initialized() {
+        let mut storage = [0xff; 8];
+        let mut buf = unsafe { io::ReadBuf::uninit(&mut storage) };
+        assert_eq!(buf.initialized_len(), 0);
+        assert_eq!(buf.remaining(), 8);
+
+        buf.append(b"ab");
+        assert_eq!(buf.filled(), b"ab");
+        assert_eq!(buf.initialized_len(), 2);
+
+        assert_eq!(buf.initialize_unfilled_to(3), [0, 0, 0]);
+        assert_eq!(buf.filled_len(), 2);
+        assert_eq!(buf.initialized_len(), 5);
+
+        buf.add_filled(1);
+        assert_eq!(buf.filled(), b"ab\0");
+        buf.clear();
+        assert_eq!(buf.filled_len(), 0);
+        assert_eq!(buf.initialized_len(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn read_buf_fill_past_initialized() {
+        let mut storage = [0; 8];
+        let mut buf = unsafe { io::ReadBuf::uninit(&mut storage) };
+        buf.add_filled(1);
+    }
+
+    #[test]
+    fn read_buf_adapters() {
+        let mut storage = [0; 8];
+
+        let mut buf = io::ReadBuf::new(&mut storage);
+        let mut c = Cursor::new(&b"12345"[..]).take(3);
+        c.read_buf(&mut buf).unwrap();
+        assert_eq!(buf.filled(), b"123");
+        c.read_buf(&mut buf).unwrap();
+        assert_eq!(buf.filled(), b"123");
+        assert_eq!(c.into_inner().position(), 3);
+
+        let mut buf = io::ReadBuf::new(&mut storage);
+        let mut chain = (&b"ab"[..]).chain(&b"cdefghijkl"[..]);
+        chain.read_buf(&mut buf).unwrap();
+        assert_eq!(buf.filled(), b"ab");
+        chain.read_buf(&mut buf).unwrap();
+        assert_eq!(buf.filled(), b"abcdefgh");
+        chain.read_buf(&mut buf).unwrap();
+        assert_eq!(buf.filled(), b"abcdefgh");
+    }
+
+    #[test]
+    fn read_to_end_default_read_buf() {
+        // A reader only implementing `read`, which gets zeroed buffers.
+        struct R(u8);
+
+        impl Read for R {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                assert!(buf.iter().all(|&b| b == 0));
+                if self.0 == 0 {
+                    return Ok(0);
+                }
+                self.0 -= 1;
+                buf[0] = 1;
+                Ok(1)
+            }
+        }
+
+        let mut v = Vec::new();
+        assert_eq!(R(100).read_to_end(&mut v).unwrap(), 100);
+        assert_eq!(v, vec![1; 100]);
+    }
+
     #[bench]
     #[cfg_attr(target_os = "emscripten", ignore)]
     fn bench_read_to_end(b: &mut test::Bencher) {
@@ -0,0 +1,245 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use fmt;
+use io::{self, Read, Write, IoSlice, IoSliceMut};
+use sys::pipe as pipe_imp;
+use sys_common::{AsInner, FromInner, IntoInner};
+
+/// Creates an anonymous pipe.
+///
+/// Data written to the returned [`PipeWriter`] can be read back from the
+/// returned [`PipeReader`], in the order it was written. Reads block until
+/// data is available and return `Ok(0)` once every writer (including clones
+/// and copies held by child processes) has been dropped; writes block while
+/// the pipe's buffer is full and fail with [`BrokenPipe`] once every reader
+/// is gone.
+///
+/// Both ends can be cloned with `try_clone` and turned into a [`Stdio`], which
+/// makes it possible to connect several child processes to the same pipe, or
+/// to capture the output of a child together with output of our own.
+///
+/// # Platform-specific behavior
+///
+/// This function currently corresponds to the `pipe2` function on Linux (with
+/// `O_CLOEXEC`), `pipe` on other Unix platforms and `CreatePipe` on Windows.
+/// Neither handle is inherited by child processes unless it is explicitly
+/// passed to one through [`Stdio`]. Note that this [may change in the
+/// future][changes].
+///
+/// [changes]: index.html#platform-specific-behavior
+///
+/// # Deadlocks
+///
+/// The pipe's buffer is of limited size, and a process writing more than fits
+/// into it blocks until the data is read. Make sure that something is reading
+/// from the pipe while writing larger amounts of data to it, and that this
+/// process drops its own copy of the writer when handing it to a child, or
+/// reads will never see the end of the data.
+///
+/// [`PipeWriter`]: struct.PipeWriter.html
+/// [`PipeReader`]: struct.PipeReader.html
+/// [`BrokenPipe`]: enum.ErrorKind.html#variant.BrokenPipe
+/// [`Stdio`]: ../process/struct.Stdio.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(anonymous_pipe)]
+/// use std::io::{self, Read};
+/// use std::process::Command;
+///
+/// # fn foo() -> io::Result<()> {
+/// let (mut reader, writer) = io::pipe()?;
+///
+/// // Both children write to the same pipe.
+/// let mut first = Command::new("echo").arg("hello").stdout(writer.try_clone()?).spawn()?;
+/// let mut second = Command::new("echo").arg("world").stdout(writer).spawn()?;
+///
+/// // All writers are now owned by the children, so reading stops once both
+/// // of them have exited.
+/// let mut output = String::new();
+/// reader.read_to_string(&mut output)?;
+/// first.wait()?;
+/// second.wait()?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+pub fn pipe() -> io::Result<(PipeReader, PipeWriter)> {
+    pipe_imp::pipe().map(|(reader, writer)| (PipeReader(reader), PipeWriter(writer)))
+}
+
+/// The reading end of an anonymous pipe, created by [`pipe`].
+///
+/// [`pipe`]: fn.pipe.html
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+pub struct PipeReader(pipe_imp::AnonPipe);
+
+/// The writing end of an anonymous pipe, created by [`pipe`].
+///
+/// [`pipe`]: fn.pipe.html
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+pub struct PipeWriter(pipe_imp::AnonPipe);
+
+impl PipeReader {
+    /// Creates a new `PipeReader` reading from the same pipe as this one.
+    ///
+    /// The pipe is only considered to have no readers left once this handle
+    /// and all of its clones have been dropped.
+    #[unstable(feature = "anonymous_pipe", issue = "0")]
+    pub fn try_clone(&self) -> io::Result<PipeReader> {
+        self.0.duplicate().map(PipeReader)
+    }
+}
+
+impl PipeWriter {
+    /// Creates a new `PipeWriter` writing to the same pipe as this one.
+    ///
+    /// Readers only observe the end of the data once this handle and all of
+    /// its clones have been dropped.
+    #[unstable(feature = "anonymous_pipe", issue = "0")]
+    pub fn try_clone(&self) -> io::Result<PipeWriter> {
+        self.0.duplicate().map(PipeWriter)
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl fmt::Debug for PipeReader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("PipeReader { .. }")
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl fmt::Debug for PipeWriter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("PipeWriter { .. }")
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl Read for PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl<'a> Read for &'a PipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl<'a> Write for &'a PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsInner<pipe_imp::AnonPipe> for PipeReader {
+    fn as_inner(&self) -> &pipe_imp::AnonPipe { &self.0 }
+}
+
+impl IntoInner<pipe_imp::AnonPipe> for PipeReader {
+    fn into_inner(self) -> pipe_imp::AnonPipe { self.0 }
+}
+
+impl FromInner<pipe_imp::AnonPipe> for PipeReader {
+    fn from_inner(pipe: pipe_imp::AnonPipe) -> PipeReader { PipeReader(pipe) }
+}
+
+impl AsInner<pipe_imp::AnonPipe> for PipeWriter {
+    fn as_inner(&self) -> &pipe_imp::AnonPipe { &self.0 }
+}
+
+impl IntoInner<pipe_imp::AnonPipe> for PipeWriter {
+    fn into_inner(self) -> pipe_imp::AnonPipe { self.0 }
+}
+
+impl FromInner<pipe_imp::AnonPipe> for PipeWriter {
+    fn from_inner(pipe: pipe_imp::AnonPipe) -> PipeWriter { PipeWriter(pipe) }
+}
+
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests {
+    use io::prelude::*;
+    use io::{self, ErrorKind};
+    use thread;
+
+    #[test]
+    fn smoke() {
+        let (mut reader, mut writer) = io::pipe().unwrap();
+        writer.write_all(b"hello").unwrap();
+        drop(writer);
+
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "hello");
+    }
+
+    #[test]
+    fn clones_keep_pipe_open() {
+        let (mut reader, writer) = io::pipe().unwrap();
+        let mut clone = writer.try_clone().unwrap();
+        drop(writer);
+
+        let t = thread::spawn(move || {
+            clone.write_all(b"from a clone").unwrap();
+        });
+
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        assert_eq!(s, "from a clone");
+        t.join().unwrap();
+    }
+
+    #[test]
+    fn write_without_reader() {
+        let (reader, mut writer) = io::pipe().unwrap();
+        drop(reader);
+        // On Unix this relies on the runtime ignoring SIGPIPE.
+        let e = writer.write(b"x").unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::BrokenPipe);
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use cmp;
+use fmt;
+use ptr;
+
+/// A borrowed byte buffer which is incrementally filled and initialized.
+///
+/// This type is a sort of "double cursor". It tracks three regions in the
+/// buffer: a region at the beginning of the buffer that has been logically
+/// filled with data, a region that has been initialized at some point but not
+/// yet logically filled, and a region at the end that may be uninitialized.
+/// The filled region is guaranteed to be a subset of the initialized region.
+///
+/// In summary, the contents of the buffer can be visualized as:
+///
+/// ```not_rust
+/// [             capacity              ]
+/// [ filled |         unfilled         ]
+/// [    initialized    | uninitialized ]
+/// ```
+///
+/// It is used by [`Read::read_buf`] so that readers which never look at the
+/// buffer they are handed can write straight into uninitialized memory, while
+/// the remaining readers only pay for zeroing each part of the buffer once.
+///
+/// [`Read::read_buf`]: trait.Read.html#method.read_buf
+#[unstable(feature = "read_buf", issue = "0")]
+pub struct ReadBuf<'a> {
+    buf: &'a mut [u8],
+    filled: usize,
+    initialized: usize,
+}
+
+#[unstable(feature = "read_buf", issue = "0")]
+impl<'a> fmt::Debug for ReadBuf<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReadBuf")
+            .field("init", &self.initialized)
+            .field("filled", &self.filled)
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<'a> ReadBuf<'a> {
+    /// Creates a new `ReadBuf` from a fully initialized buffer.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn new(buf: &'a mut [u8]) -> ReadBuf<'a> {
+        let len = buf.len();
+        ReadBuf { buf: buf, filled: 0, initialized: len }
+    }
+
+    /// Creates a new `ReadBuf` from a buffer whose contents may be
+    /// uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// The buffer's contents must not be read through the returned value
+    /// until they have been initialized, which the safe methods of this type
+    /// guarantee. The caller must not read the part of `buf` beyond
+    /// [`initialized_len`] once the `ReadBuf` is gone either.
+    ///
+    /// [`initialized_len`]: #method.initialized_len
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub unsafe fn uninit(buf: &'a mut [u8]) -> ReadBuf<'a> {
+        ReadBuf { buf: buf, filled: 0, initialized: 0 }
+    }
+
+    /// Returns the total capacity of the buffer.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns a shared reference to the filled portion of the buffer.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn filled(&self) -> &[u8] {
+        &self.buf[..self.filled]
+    }
+
+    /// Returns a mutable reference to the filled portion of the buffer.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn filled_mut(&mut self) -> &mut [u8] {
+        &mut self.buf[..self.filled]
+    }
+
+    /// Returns a shared reference to the initialized portion of the buffer.
+    ///
+    /// This includes the filled portion.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn initialized(&self) -> &[u8] {
+        &self.buf[..self.initialized]
+    }
+
+    /// Returns a mutable reference to the initialized portion of the buffer.
+    ///
+    /// This includes the filled portion.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn initialized_mut(&mut self) -> &mut [u8] {
+        &mut self.buf[..self.initialized]
+    }
+
+    /// Returns a mutable reference to the unfilled part of the buffer without
+    /// ensuring that it has been fully initialized.
+    ///
+    /// # Safety
+    ///
+    /// The caller must not read from the returned slice before writing to
+    /// it, and must not write uninitialized bytes into the initialized
+    /// portion of the buffer.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub unsafe fn unfilled_mut(&mut self) -> &mut [u8] {
+        &mut self.buf[self.filled..]
+    }
+
+    /// Returns a mutable reference to the unfilled part of the buffer,
+    /// ensuring it is fully initialized.
+    ///
+    /// Since `ReadBuf` tracks the region of the buffer that has been
+    /// initialized, this is effectively "free" after the first use.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn initialize_unfilled(&mut self) -> &mut [u8] {
+        let n = self.remaining();
+        self.initialize_unfilled_to(n)
+    }
+
+    /// Returns a mutable reference to the first `n` bytes of the unfilled
+    /// part of the buffer, ensuring it is fully initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.remaining()` is less than `n`.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn initialize_unfilled_to(&mut self, n: usize) -> &mut [u8] {
+        assert!(self.remaining() >= n);
+
+        let extra_init = self.initialized - self.filled;
+        if n > extra_init {
+            let uninit = n - extra_init;
+            unsafe {
+                ptr::write_bytes(self.buf.as_mut_ptr().offset(self.initialized as isize),
+                                 0,
+                                 uninit);
+            }
+            self.initialized += uninit;
+        }
+
+        let filled = self.filled;
+        &mut self.buf[filled..filled + n]
+    }
+
+    /// Returns the number of bytes at the end of the slice that have not yet
+    /// been filled.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.filled
+    }
+
+    /// Clears the buffer, resetting the filled region to empty.
+    ///
+    /// The number of initialized bytes is not changed, and the contents of
+    /// the buffer are not modified.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn clear(&mut self) {
+        self.filled = 0;
+    }
+
+    /// Increases the size of the filled region of the buffer.
+    ///
+    /// The number of initialized bytes is not changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the filled region of the buffer would become larger than
+    /// the initialized region.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn add_filled(&mut self, n: usize) {
+        let filled = self.filled + n;
+        self.set_filled(filled);
+    }
+
+    /// Sets the size of the filled region of the buffer.
+    ///
+    /// The number of initialized bytes is not changed.
+    ///
+    /// Note that this can be used to *shrink* the filled region of the
+    /// buffer in addition to growing it (for example, by a `Read`
+    /// implementation that compresses data in-place).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the filled region of the buffer would become larger than
+    /// the initialized region.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn set_filled(&mut self, n: usize) {
+        assert!(n <= self.initialized);
+        self.filled = n;
+    }
+
+    /// Asserts that the first `n` unfilled bytes of the buffer are
+    /// initialized.
+    ///
+    /// `ReadBuf` assumes that bytes are never de-initialized, so this method
+    /// does nothing when called with fewer bytes than are already known to
+    /// be initialized.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the first `n` unfilled bytes of the buffer
+    /// have already been initialized.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub unsafe fn assume_init(&mut self, n: usize) {
+        self.initialized = cmp::max(self.initialized, self.filled + n);
+    }
+
+    /// Appends data to the buffer, advancing the written position and
+    /// possibly also the initialized position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.remaining()` is less than `buf.len()`.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn append(&mut self, buf: &[u8]) {
+        assert!(self.remaining() >= buf.len());
+
+        let filled = self.filled;
+        self.buf[filled..filled + buf.len()].copy_from_slice(buf);
+        unsafe { self.assume_init(buf.len()); }
+        self.add_filled(buf.len());
+    }
+
+    /// Returns the amount of bytes that have been filled.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn filled_len(&self) -> usize {
+        self.filled
+    }
+
+    /// Returns the amount of bytes that have been initialized.
+    #[unstable(feature = "read_buf", issue = "0")]
+    #[inline]
+    pub fn initialized_len(&self) -> usize {
+        self.initialized
+    }
+}
@@ -13,9 +13,11 @@ use io::prelude::*;
 use cell::RefCell;
 use fmt;
 use io::lazy::Lazy;
-use io::{self, Initializer, BufReader, LineWriter};
+use io::{self, Initializer, BufReader, BufWriter, LineWriter};
+use mem;
 use sync::{Arc, Mutex, MutexGuard};
 use sys::stdio;
+use sys_common;
 use sys_common::remutex::{ReentrantMutex, ReentrantMutexGuard};
 use thread::{LocalKey, LocalKeyState};
 
@@ -120,6 +122,67 @@ impl<R: io::Read> io::Read for Maybe<R> {
     }
 }
 
+/// The writer behind the global stdout handle, buffering according to the
+/// currently selected `BufferMode`.
+enum StdoutWriter {
+    Unbuffered(Maybe<StdoutRaw>),
+    Line(LineWriter<Maybe<StdoutRaw>>),
+    Block(BufWriter<Maybe<StdoutRaw>>),
+}
+
+impl StdoutWriter {
+    fn new(raw: Maybe<StdoutRaw>, mode: BufferMode) -> StdoutWriter {
+        match mode {
+            BufferMode::Unbuffered => StdoutWriter::Unbuffered(raw),
+            BufferMode::Line => StdoutWriter::Line(LineWriter::new(raw)),
+            BufferMode::Block => StdoutWriter::Block(BufWriter::new(raw)),
+        }
+    }
+
+    fn mode(&self) -> BufferMode {
+        match *self {
+            StdoutWriter::Unbuffered(..) => BufferMode::Unbuffered,
+            StdoutWriter::Line(..) => BufferMode::Line,
+            StdoutWriter::Block(..) => BufferMode::Block,
+        }
+    }
+
+    fn set_mode(&mut self, mode: BufferMode) -> io::Result<()> {
+        if self.mode() == mode {
+            return Ok(())
+        }
+        self.flush()?;
+
+        // The buffer was flushed above, so unwrapping the raw handle can't
+        // fail anymore.
+        let raw = match mem::replace(self, StdoutWriter::Unbuffered(Maybe::Fake)) {
+            StdoutWriter::Unbuffered(w) => w,
+            StdoutWriter::Line(w) => w.into_inner().ok().expect("stdout was flushed"),
+            StdoutWriter::Block(w) => w.into_inner().ok().expect("stdout was flushed"),
+        };
+        *self = StdoutWriter::new(raw, mode);
+        Ok(())
+    }
+}
+
+impl Write for StdoutWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            StdoutWriter::Unbuffered(ref mut w) => w.write(buf),
+            StdoutWriter::Line(ref mut w) => w.write(buf),
+            StdoutWriter::Block(ref mut w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            StdoutWriter::Unbuffered(ref mut w) => w.flush(),
+            StdoutWriter::Line(ref mut w) => w.flush(),
+            StdoutWriter::Block(ref mut w) => w.flush(),
+        }
+    }
+}
+
 fn handle_ebadf<T>(r: io::Result<T>, default: T) -> io::Result<T> {
     match r {
         Err(ref e) if stdio::is_ebadf(e) => Ok(default),
@@ -333,16 +396,20 @@ impl<'a> fmt::Debug for StdinLock<'a> {
 /// output stream. Access is also synchronized via a lock and explicit control
 /// over locking is available via the [`lock`] method.
 ///
+/// The buffer is line buffered if the standard output stream is a terminal,
+/// and block buffered otherwise. This can be changed at runtime with
+/// [`set_buffer_mode`]. Any data still buffered when the process exits
+/// normally, including through [`process::exit`], is flushed.
+///
 /// Created by the [`io::stdout`] method.
 ///
 /// [`lock`]: #method.lock
+/// [`set_buffer_mode`]: #method.set_buffer_mode
 /// [`io::stdout`]: fn.stdout.html
+/// [`process::exit`]: ../process/fn.exit.html
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct Stdout {
-    // FIXME: this should be LineWriter or BufWriter depending on the state of
-    //        stdout (tty or not). Note that if this is not line buffered it
-    //        should also flush-on-panic or some form of flush-on-abort.
-    inner: Arc<ReentrantMutex<RefCell<LineWriter<Maybe<StdoutRaw>>>>>,
+    inner: Arc<ReentrantMutex<RefCell<StdoutWriter>>>,
 }
 
 /// A locked reference to the `Stdout` handle.
@@ -354,7 +421,32 @@ pub struct Stdout {
 /// [`Stdout::lock`]: struct.Stdout.html#method.lock
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct StdoutLock<'a> {
-    inner: ReentrantMutexGuard<'a, RefCell<LineWriter<Maybe<StdoutRaw>>>>,
+    inner: ReentrantMutexGuard<'a, RefCell<StdoutWriter>>,
+}
+
+/// The buffering strategy used by the global standard output stream.
+///
+/// See [`Stdout::set_buffer_mode`] for more details.
+///
+/// [`Stdout::set_buffer_mode`]: struct.Stdout.html#method.set_buffer_mode
+#[unstable(feature = "stdout_buffer_mode", issue = "0")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BufferMode {
+    /// Data is written to the underlying stream as soon as it's received.
+    #[unstable(feature = "stdout_buffer_mode", issue = "0")]
+    Unbuffered,
+    /// Data is buffered until a newline is written, in the same way as by a
+    /// [`LineWriter`].
+    ///
+    /// [`LineWriter`]: struct.LineWriter.html
+    #[unstable(feature = "stdout_buffer_mode", issue = "0")]
+    Line,
+    /// Data is buffered until the buffer is full, in the same way as by a
+    /// [`BufWriter`].
+    ///
+    /// [`BufWriter`]: struct.BufWriter.html
+    #[unstable(feature = "stdout_buffer_mode", issue = "0")]
+    Block,
 }
 
 /// Constructs a new handle to the standard output of the current process.
@@ -395,18 +487,39 @@ pub struct StdoutLock<'a> {
 /// ```
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn stdout() -> Stdout {
-    static INSTANCE: Lazy<ReentrantMutex<RefCell<LineWriter<Maybe<StdoutRaw>>>>>
+    static INSTANCE: Lazy<ReentrantMutex<RefCell<StdoutWriter>>>
         = Lazy::new(stdout_init);
     return Stdout {
         inner: INSTANCE.get().expect("cannot access stdout during shutdown"),
     };
 
-    fn stdout_init() -> Arc<ReentrantMutex<RefCell<LineWriter<Maybe<StdoutRaw>>>>> {
-        let stdout = match stdout_raw() {
-            Ok(stdout) => Maybe::Real(stdout),
-            _ => Maybe::Fake,
+    fn stdout_init() -> Arc<ReentrantMutex<RefCell<StdoutWriter>>> {
+        let (stdout, mode) = match stdout_raw() {
+            Ok(stdout) => {
+                let mode = if stdout.0.is_terminal() {
+                    BufferMode::Line
+                } else {
+                    BufferMode::Block
+                };
+                (Maybe::Real(stdout), mode)
+            }
+            _ => (Maybe::Fake, BufferMode::Line),
         };
-        Arc::new(ReentrantMutex::new(RefCell::new(LineWriter::new(stdout))))
+        let writer = StdoutWriter::new(stdout, mode);
+        let stdout = Arc::new(ReentrantMutex::new(RefCell::new(writer)));
+
+        // Handles to stdout may well outlive the lazy instance, so explicitly
+        // flush whatever is still buffered on exit. Other threads may still
+        // be writing at that point; don't block on them.
+        let at_exit_stdout = stdout.clone();
+        let _ = sys_common::at_exit(move || {
+            if let Ok(guard) = at_exit_stdout.try_lock() {
+                if let Ok(mut writer) = guard.try_borrow_mut() {
+                    let _ = writer.flush();
+                }
+            }
+        });
+        stdout
     }
 }
 
@@ -435,6 +548,48 @@ impl Stdout {
     pub fn lock(&self) -> StdoutLock {
         StdoutLock { inner: self.inner.lock().unwrap_or_else(|e| e.into_inner()) }
     }
+
+    /// Returns the buffering mode currently used by the global standard
+    /// output stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(stdout_buffer_mode)]
+    /// use std::io;
+    ///
+    /// println!("stdout is in {:?} mode", io::stdout().buffer_mode());
+    /// ```
+    #[unstable(feature = "stdout_buffer_mode", issue = "0")]
+    pub fn buffer_mode(&self) -> BufferMode {
+        self.lock().inner.borrow().mode()
+    }
+
+    /// Changes the buffering mode of the global standard output stream.
+    ///
+    /// Any data that is currently buffered is flushed first. If that fails
+    /// the error is returned and the mode is left unchanged.
+    ///
+    /// The mode applies to all handles to stdout, including the ones used by
+    /// `print!` and `println!`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(stdout_buffer_mode)]
+    /// use std::io::{self, BufferMode};
+    ///
+    /// # fn foo() -> io::Result<()> {
+    /// // Print progress as soon as it is available, even when piped.
+    /// io::stdout().set_buffer_mode(BufferMode::Unbuffered)?;
+    /// print!("working...");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "stdout_buffer_mode", issue = "0")]
+    pub fn set_buffer_mode(&self, mode: BufferMode) -> io::Result<()> {
+        self.lock().inner.borrow_mut().set_mode(mode)
+    }
 }
 
 #[stable(feature = "std_debug", since = "1.16.0")]
@@ -611,6 +766,88 @@ impl<'a> fmt::Debug for StderrLock<'a> {
     }
 }
 
+/// Trait to determine if a descriptor or handle refers to a terminal.
+///
+/// This is implemented for the standard streams and their locks, [`File`],
+/// the ends of anonymous pipes, the standard streams of child processes and
+/// TCP and Unix domain streams. Other types wrapping a descriptor or handle,
+/// such as pseudo-terminals, can implement it as well.
+///
+/// # Platform-specific behavior
+///
+/// On Unix this corresponds to the `isatty` function, and on Windows to
+/// checking whether `GetConsoleMode` succeeds on the handle. Note that this
+/// means terminal emulators on Windows which are not consoles, such as the
+/// ones shipped with MSYS2 or Cygwin, are reported as not being terminals.
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: index.html#platform-specific-behavior
+/// [`File`]: ../fs/struct.File.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(is_terminal)]
+/// use std::io::{self, IsTerminal};
+///
+/// if io::stdout().is_terminal() {
+///     println!("\x1b[1mhello\x1b[0m");
+/// } else {
+///     println!("hello");
+/// }
+/// ```
+#[unstable(feature = "is_terminal", issue = "0")]
+pub trait IsTerminal {
+    /// Returns `true` if the descriptor or handle refers to a terminal.
+    ///
+    /// Errors while checking, such as the stream having been closed, are
+    /// reported as `false`.
+    #[unstable(feature = "is_terminal", issue = "0")]
+    fn is_terminal(&self) -> bool;
+}
+
+#[unstable(feature = "is_terminal", issue = "0")]
+impl IsTerminal for Stdin {
+    fn is_terminal(&self) -> bool {
+        stdin_raw().map(|s| s.0.is_terminal()).unwrap_or(false)
+    }
+}
+
+#[unstable(feature = "is_terminal", issue = "0")]
+impl<'a> IsTerminal for StdinLock<'a> {
+    fn is_terminal(&self) -> bool {
+        stdin_raw().map(|s| s.0.is_terminal()).unwrap_or(false)
+    }
+}
+
+#[unstable(feature = "is_terminal", issue = "0")]
+impl IsTerminal for Stdout {
+    fn is_terminal(&self) -> bool {
+        stdout_raw().map(|s| s.0.is_terminal()).unwrap_or(false)
+    }
+}
+
+#[unstable(feature = "is_terminal", issue = "0")]
+impl<'a> IsTerminal for StdoutLock<'a> {
+    fn is_terminal(&self) -> bool {
+        stdout_raw().map(|s| s.0.is_terminal()).unwrap_or(false)
+    }
+}
+
+#[unstable(feature = "is_terminal", issue = "0")]
+impl IsTerminal for Stderr {
+    fn is_terminal(&self) -> bool {
+        stderr_raw().map(|s| s.0.is_terminal()).unwrap_or(false)
+    }
+}
+
+#[unstable(feature = "is_terminal", issue = "0")]
+impl<'a> IsTerminal for StderrLock<'a> {
+    fn is_terminal(&self) -> bool {
+        stderr_raw().map(|s| s.0.is_terminal()).unwrap_or(false)
+    }
+}
+
 /// Resets the thread-local stderr handle to the specified writer
 ///
 /// This will replace the current thread's stderr handle, returning the old
@@ -713,6 +950,17 @@ mod tests {
     use thread;
     use super::*;
 
+    #[test]
+    fn stdout_buffer_mode() {
+        let stdout = stdout();
+        let original = stdout.buffer_mode();
+        for &mode in &[BufferMode::Unbuffered, BufferMode::Block, BufferMode::Line] {
+            stdout.set_buffer_mode(mode).unwrap();
+            assert_eq!(stdout.buffer_mode(), mode);
+        }
+        stdout.set_buffer_mode(original).unwrap();
+    }
+
     #[test]
     #[cfg_attr(target_os = "emscripten", ignore)]
     fn panic_doesnt_poison() {
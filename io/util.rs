@@ -11,9 +11,14 @@
 #![allow(missing_copy_implementations)]
 
 use fmt;
-use io::{self, Read, Initializer, Write, ErrorKind, BufRead};
+use io::{self, Read, Initializer, Write, ErrorKind, BufRead, ReadBuf};
 use mem;
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use sys::kernel_copy::copy_spec as copy_impl;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+use self::generic_copy as copy_impl;
+
 /// Copies the entire contents of a reader into a writer.
 ///
 /// This function will continuously read data from `reader` and then
@@ -23,6 +28,18 @@ use mem;
 /// On success, the total number of bytes that were copied from
 /// `reader` to `writer` is returned.
 ///
+/// # Platform-specific behavior
+///
+/// On Linux (including Android), this function uses `copy_file_range`,
+/// `sendfile` or `splice` to let the kernel move the data directly when both
+/// `reader` and `writer` are known to be backed by file descriptors, such as
+/// copying from a [`File`] to another [`File`] or to a [`TcpStream`], or
+/// from a pipe to a socket. Note that this [may change in the future][changes].
+///
+/// [`File`]: ../fs/struct.File.html
+/// [`TcpStream`]: ../net/struct.TcpStream.html
+/// [changes]: index.html#platform-specific-behavior
+///
 /// # Errors
 ///
 /// This function will return an error immediately if any call to `read` or
@@ -49,22 +66,31 @@ use mem;
 pub fn copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W) -> io::Result<u64>
     where R: Read, W: Write
 {
-    let mut buf = unsafe {
-        let mut buf: [u8; super::DEFAULT_BUF_SIZE] = mem::uninitialized();
-        reader.initializer().initialize(&mut buf);
-        buf
-    };
+    copy_impl(reader, writer)
+}
+
+/// The portable implementation of `copy`, shuffling data through a buffer on
+/// the stack.
+pub(crate) fn generic_copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W)
+                                                 -> io::Result<u64>
+    where R: Read, W: Write
+{
+    let mut storage: [u8; super::DEFAULT_BUF_SIZE] = unsafe { mem::uninitialized() };
+    let mut buf = unsafe { ReadBuf::uninit(&mut storage) };
 
     let mut written = 0;
     loop {
-        let len = match reader.read(&mut buf) {
-            Ok(0) => return Ok(written),
-            Ok(len) => len,
+        match reader.read_buf(&mut buf) {
+            Ok(()) => {}
             Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
             Err(e) => return Err(e),
-        };
-        writer.write_all(&buf[..len])?;
-        written += len as u64;
+        }
+        if buf.filled_len() == 0 {
+            return Ok(written);
+        }
+        writer.write_all(buf.filled())?;
+        written += buf.filled_len() as u64;
+        buf.clear();
     }
 }
 
@@ -11,7 +11,7 @@
 use io::prelude::*;
 
 use fmt;
-use io::{self, Initializer};
+use io::{self, Initializer, IoSlice, IoSliceMut, ReadBuf};
 use net::{ToSocketAddrs, SocketAddr, Shutdown};
 use sys_common::net as net_imp;
 use sys_common::{AsInner, FromInner, IntoInner};
@@ -548,6 +548,14 @@ impl TcpStream {
 impl Read for TcpStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.read(buf) }
 
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> io::Result<()> {
+        self.0.read_buf(buf)
+    }
+
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -556,12 +564,23 @@ impl Read for TcpStream {
 #[stable(feature = "rust1", since = "1.0.0")]
 impl Write for TcpStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.write(buf) }
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
     fn flush(&mut self) -> io::Result<()> { Ok(()) }
 }
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<'a> Read for &'a TcpStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.read(buf) }
 
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> io::Result<()> {
+        self.0.read_buf(buf)
+    }
+
     #[inline]
     unsafe fn initializer(&self) -> Initializer {
         Initializer::nop()
@@ -570,6 +589,9 @@ impl<'a> Read for &'a TcpStream {
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<'a> Write for &'a TcpStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.0.write(buf) }
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
     fn flush(&mut self) -> io::Result<()> { Ok(()) }
 }
 
@@ -989,6 +1011,35 @@ mod tests {
         })
     }
 
+    #[test]
+    fn copy_file_to_stream() {
+        use fs;
+        use io;
+        use sys_common::io::test::tmpdir;
+
+        let tmpdir = tmpdir();
+        let path = tmpdir.join("payload");
+        let payload = (0..100_000).map(|i| i as u8).collect::<Vec<u8>>();
+        t!(fs::write(&path, &payload));
+
+        each_ip(&mut |addr| {
+            let acceptor = t!(TcpListener::bind(&addr));
+            let path = path.clone();
+
+            let t = thread::spawn(move|| {
+                let mut stream = t!(TcpStream::connect(&addr));
+                let mut file = t!(fs::File::open(&path));
+                t!(io::copy(&mut file, &mut stream))
+            });
+
+            let mut stream = t!(acceptor.accept()).0;
+            let mut received = Vec::new();
+            t!(stream.read_to_end(&mut received));
+            assert_eq!(t.join().unwrap(), payload.len() as u64);
+            assert!(received == payload);
+        })
+    }
+
     #[test]
     fn read_eof() {
         each_ip(&mut |addr| {
@@ -1634,6 +1685,8 @@ mod tests {
         let addr = "10.255.255.1:80".parse().unwrap();
         let e = TcpStream::connect_timeout(&addr, Duration::from_millis(250)).unwrap_err();
         assert!(e.kind() == io::ErrorKind::TimedOut ||
+                e.kind() == io::ErrorKind::HostUnreachable ||
+                e.kind() == io::ErrorKind::NetworkUnreachable ||
                 e.kind() == io::ErrorKind::Other,
                 "bad error: {} {:?}", e, e.kind());
     }
@@ -15,6 +15,19 @@ use libc;
 use fs::Metadata;
 use sys_common::AsInner;
 
+#[cfg(target_os = "linux")]
+use fmt;
+#[cfg(target_os = "linux")]
+use io;
+#[cfg(target_os = "linux")]
+use os::unix::io::{AsRawFd, RawFd};
+#[cfg(target_os = "linux")]
+use path::Path;
+#[cfg(target_os = "linux")]
+use sys::inotify;
+#[cfg(target_os = "linux")]
+use time::Duration;
+
 #[allow(deprecated)]
 use os::linux::raw;
 
@@ -385,3 +398,251 @@ impl MetadataExt for Metadata {
         self.as_inner().as_inner().st_blocks as u64
     }
 }
+
+/// Watches files and directories for changes.
+///
+/// Each change of a watched file, or of a file in a watched directory, is
+/// reported as an [`Event`]. Events are received by [`recv`],
+/// [`recv_timeout`] or [`iter`], in the order in which they happened.
+///
+/// The watcher is built on inotify. Its descriptor is available through
+/// [`AsRawFd`], and becomes readable when events are pending, so that it can
+/// be used with `poll` or `epoll` together with [`try_recv`].
+///
+/// [`Event`]: struct.Event.html
+/// [`recv`]: #method.recv
+/// [`recv_timeout`]: #method.recv_timeout
+/// [`iter`]: #method.iter
+/// [`try_recv`]: #method.try_recv
+/// [`AsRawFd`]: ../../unix/io/trait.AsRawFd.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(fs_watcher)]
+/// use std::os::linux::fs::{EventKind, Watcher};
+///
+/// # fn foo() -> std::io::Result<()> {
+/// let mut watcher = Watcher::new()?;
+/// watcher.watch("src", true)?;
+/// for event in watcher.iter() {
+///     let event = event?;
+///     match event.kind() {
+///         EventKind::Overflow => println!("events were lost, rescanning"),
+///         kind => println!("{:?}: {:?}", kind, event.path()),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(target_os = "linux")]
+#[unstable(feature = "fs_watcher", issue = "0")]
+pub struct Watcher(inotify::Watcher);
+
+/// A change reported by a [`Watcher`].
+///
+/// [`Watcher`]: struct.Watcher.html
+#[cfg(target_os = "linux")]
+#[unstable(feature = "fs_watcher", issue = "0")]
+#[derive(Debug)]
+pub struct Event(inotify::Event);
+
+/// The kind of change reported by an [`Event`].
+///
+/// [`Event`]: struct.Event.html
+#[cfg(target_os = "linux")]
+#[unstable(feature = "fs_watcher", issue = "0")]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum EventKind {
+    /// A file or directory was created.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    Create,
+    /// The contents of a file were modified.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    Modify,
+    /// A file or directory was deleted.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    Delete,
+    /// The metadata of a file or directory, such as its permissions,
+    /// timestamps or owner, changed.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    Attrib,
+    /// A file or directory was renamed within the watched files.
+    /// [`Event::from_path`] returns the old path and [`Event::path`] the new
+    /// one.
+    ///
+    /// [`Event::from_path`]: struct.Event.html#method.from_path
+    /// [`Event::path`]: struct.Event.html#method.path
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    Rename,
+    /// A file or directory was moved away from the watched files.
+    ///
+    /// If it was moved to another watched place the kernel failed to report
+    /// both ends of the move together, and a `MovedTo` event with the same
+    /// [`cookie`] follows.
+    ///
+    /// [`cookie`]: struct.Event.html#method.cookie
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    MovedFrom,
+    /// A file or directory was moved into the watched files.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    MovedTo,
+    /// The kernel's event queue overflowed and events were lost. Any state
+    /// derived from events should be rebuilt by scanning the watched files.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    Overflow,
+}
+
+#[cfg(target_os = "linux")]
+impl Watcher {
+    /// Creates a watcher without any watches.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the per-user limit of inotify
+    /// instances was reached.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn new() -> io::Result<Watcher> {
+        inotify::Watcher::new().map(Watcher)
+    }
+
+    /// Starts watching `path`.
+    ///
+    /// If `path` is a directory, changes of its entries are reported.
+    /// Directories below it are watched as well if `recursive` is `true`,
+    /// including directories created or moved into it later on. Symbolic
+    /// links are followed for `path` itself, but not below it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` doesn't exist, or if the
+    /// per-user limit of watches (`/proc/sys/fs/inotify/max_user_watches`)
+    /// is reached.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P, recursive: bool) -> io::Result<()> {
+        self.0.watch(path.as_ref(), recursive)
+    }
+
+    /// Stops watching `path`, and the directories below it if it was
+    /// watched recursively.
+    ///
+    /// Events which were already queued are still received.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn unwatch<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.0.unwatch(path.as_ref())
+    }
+
+    /// Receives the next event, blocking until there is one.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if reading from the inotify descriptor fails, or
+    /// if a directory appearing in a recursively watched one can't be
+    /// watched. The events before and after the error are still received.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn recv(&mut self) -> io::Result<Event> {
+        self.0.recv().map(Event)
+    }
+
+    /// Receives the next event, waiting at most `timeout` for one and
+    /// returning `None` if there was none.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn recv_timeout(&mut self, timeout: Duration) -> io::Result<Option<Event>> {
+        self.0.recv_timeout(timeout).map(|event| event.map(Event))
+    }
+
+    /// Receives the next event if there is one, without blocking.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn try_recv(&mut self) -> io::Result<Option<Event>> {
+        self.recv_timeout(Duration::new(0, 0))
+    }
+
+    /// Returns an iterator blocking for each event, like [`recv`].
+    ///
+    /// The iterator never ends.
+    ///
+    /// [`recv`]: #method.recv
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn iter(&mut self) -> Events {
+        Events { watcher: self }
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[unstable(feature = "fs_watcher", issue = "0")]
+impl AsRawFd for Watcher {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.fd().raw()
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[unstable(feature = "fs_watcher", issue = "0")]
+impl fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Watcher").field("fd", &self.0.fd().raw()).finish()
+    }
+}
+
+/// A blocking iterator over the events of a [`Watcher`].
+///
+/// This struct is created by [`Watcher::iter`].
+///
+/// [`Watcher`]: struct.Watcher.html
+/// [`Watcher::iter`]: struct.Watcher.html#method.iter
+#[cfg(target_os = "linux")]
+#[unstable(feature = "fs_watcher", issue = "0")]
+#[derive(Debug)]
+pub struct Events<'a> {
+    watcher: &'a mut Watcher,
+}
+
+#[cfg(target_os = "linux")]
+#[unstable(feature = "fs_watcher", issue = "0")]
+impl<'a> Iterator for Events<'a> {
+    type Item = io::Result<Event>;
+
+    fn next(&mut self) -> Option<io::Result<Event>> {
+        Some(self.watcher.recv())
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Event {
+    /// Returns the kind of change.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn kind(&self) -> EventKind {
+        self.0.kind
+    }
+
+    /// Returns the path of the changed file, which starts with the path
+    /// passed to [`Watcher::watch`]. It is `None` for [`Overflow`] events.
+    ///
+    /// [`Watcher::watch`]: struct.Watcher.html#method.watch
+    /// [`Overflow`]: enum.EventKind.html#variant.Overflow
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn path(&self) -> Option<&Path> {
+        self.0.path.as_ref().map(|p| &**p)
+    }
+
+    /// Returns the old path of a renamed file for [`Rename`] events.
+    ///
+    /// [`Rename`]: enum.EventKind.html#variant.Rename
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn from_path(&self) -> Option<&Path> {
+        self.0.from.as_ref().map(|p| &**p)
+    }
+
+    /// Returns the cookie connecting both ends of a move, or 0 for events
+    /// other than moves.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn cookie(&self) -> u32 {
+        self.0.cookie
+    }
+
+    /// Returns `true` if the changed file is a directory.
+    #[unstable(feature = "fs_watcher", issue = "0")]
+    pub fn is_dir(&self) -> bool {
+        self.0.is_dir
+    }
+}
@@ -14,6 +14,7 @@
 
 use fs;
 use net;
+use process;
 use sys;
 use io;
 use sys_common::{self, AsInner, FromInner, IntoInner};
@@ -91,6 +92,46 @@ impl IntoRawFd for fs::File {
     }
 }
 
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl AsRawFd for io::PipeReader {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_inner().fd().raw()
+    }
+}
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl FromRawFd for io::PipeReader {
+    unsafe fn from_raw_fd(fd: RawFd) -> io::PipeReader {
+        let fd = sys::fd::FileDesc::new(fd);
+        io::PipeReader::from_inner(sys::pipe::AnonPipe::from_inner(fd))
+    }
+}
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl IntoRawFd for io::PipeReader {
+    fn into_raw_fd(self) -> RawFd {
+        self.into_inner().into_fd().into_raw()
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl AsRawFd for io::PipeWriter {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_inner().fd().raw()
+    }
+}
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl FromRawFd for io::PipeWriter {
+    unsafe fn from_raw_fd(fd: RawFd) -> io::PipeWriter {
+        let fd = sys::fd::FileDesc::new(fd);
+        io::PipeWriter::from_inner(sys::pipe::AnonPipe::from_inner(fd))
+    }
+}
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl IntoRawFd for io::PipeWriter {
+    fn into_raw_fd(self) -> RawFd {
+        self.into_inner().into_fd().into_raw()
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl AsRawFd for net::TcpStream {
     fn as_raw_fd(&self) -> RawFd {
@@ -165,3 +206,25 @@ impl IntoRawFd for net::UdpSocket {
         self.into_inner().into_inner().into_fd().into_raw()
     }
 }
+
+// The standard streams and `File` check their descriptors through `sys`, the
+// other types owning one are listed here.
+macro_rules! impl_is_terminal {
+    ($($t:ty)*) => ($(
+        #[unstable(feature = "is_terminal", issue = "0")]
+        impl io::IsTerminal for $t {
+            fn is_terminal(&self) -> bool {
+                let fd = sys::fd::FileDesc::new(self.as_raw_fd());
+                let ret = fd.is_terminal();
+                fd.into_raw();
+                ret
+            }
+        }
+    )*)
+}
+
+impl_is_terminal! {
+    io::PipeReader io::PipeWriter
+    net::TcpStream
+    process::ChildStdin process::ChildStdout process::ChildStderr
+}
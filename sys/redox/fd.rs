@@ -46,6 +46,17 @@ impl FileDesc {
         cvt(syscall::write(self.fd, buf))
     }
 
+    pub fn is_terminal(&self) -> bool {
+        // Only terminals can hand out a `termios` handle.
+        match syscall::dup(self.fd, b"termios") {
+            Ok(fd) => {
+                let _ = syscall::close(fd);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
     pub fn duplicate(&self) -> io::Result<FileDesc> {
         let new_fd = cvt(syscall::dup(self.fd, &[]))?;
         Ok(FileDesc::new(new_fd))
@@ -11,14 +11,15 @@
 use os::unix::prelude::*;
 
 use ffi::{OsString, OsStr};
+use fs::{Advice, MmapMode, Reflink};
 use fmt;
-use io::{self, Error, ErrorKind, SeekFrom};
+use io::{self, Error, ErrorKind, SeekFrom, IoSlice, IoSliceMut, ReadBuf};
 use path::{Path, PathBuf};
 use sync::Arc;
 use sys::fd::FileDesc;
 use sys::time::SystemTime;
 use sys::{cvt, syscall};
-use sys_common::{AsInner, FromInner};
+use sys_common::{AsInner, FromInner, IntoInner};
 
 pub struct File(FileDesc);
 
@@ -27,6 +28,11 @@ pub struct FileAttr {
     stat: syscall::Stat,
 }
 
+#[derive(Clone)]
+pub struct FsStats {
+    stat: syscall::StatVfs,
+}
+
 pub struct ReadDir {
     data: Vec<u8>,
     i: usize,
@@ -66,6 +72,22 @@ pub struct FileType { mode: u16 }
 #[derive(Debug)]
 pub struct DirBuilder { mode: u16 }
 
+#[derive(Clone, Debug)]
+pub struct CopyOptions {
+    reflink: Reflink,
+    overwrite: bool,
+    copy_symlinks: bool,
+    preserve_timestamps: bool,
+    preserve_ownership: bool,
+    preserve_xattrs: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FileTimes {
+    accessed: Option<SystemTime>,
+    modified: Option<SystemTime>,
+}
+
 impl FileAttr {
     pub fn size(&self) -> u64 { self.stat.st_size as u64 }
     pub fn perm(&self) -> FilePermissions {
@@ -267,6 +289,12 @@ impl File {
         Ok(FileAttr { stat: stat })
     }
 
+    pub fn fs_stats(&self) -> io::Result<FsStats> {
+        let mut stat = syscall::StatVfs::default();
+        cvt(syscall::fstatvfs(self.0.raw(), &mut stat))?;
+        Ok(FsStats { stat: stat })
+    }
+
     pub fn fsync(&self) -> io::Result<()> {
         cvt(syscall::fsync(self.0.raw()))?;
         Ok(())
@@ -281,14 +309,61 @@ impl File {
         Ok(())
     }
 
+    pub fn allocate(&self, _offset: u64, _len: u64) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn punch_hole(&self, _offset: u64, _len: u64) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn next_data(&mut self, _offset: u64) -> io::Result<Option<(u64, u64)>> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.read(buf)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        io::default_read_vectored(|b| self.read(b), bufs)
+    }
+
+    pub fn read_buf(&self, buf: &mut ReadBuf) -> io::Result<()> {
+        let n = self.read(unsafe { buf.unfilled_mut() })?;
+        unsafe { buf.assume_init(n); }
+        buf.add_filled(n);
+        Ok(())
+    }
+
     pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
         self.0.write(buf)
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        io::default_write_vectored(|b| self.write(b), bufs)
+    }
+
     pub fn flush(&self) -> io::Result<()> { Ok(()) }
 
     pub fn seek(&self, pos: SeekFrom) -> io::Result<u64> {
@@ -307,6 +382,10 @@ impl File {
         self.0.duplicate().map(File)
     }
 
+    pub fn is_terminal(&self) -> bool {
+        self.0.is_terminal()
+    }
+
     pub fn dup(&self, buf: &[u8]) -> io::Result<File> {
         let fd = cvt(syscall::dup(*self.fd().as_inner() as usize, buf))?;
         Ok(File(FileDesc::new(fd)))
@@ -316,6 +395,22 @@ impl File {
         set_perm(&self.path()?, perm)
     }
 
+    pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
+        // There is no way to leave one of the times alone, so the current
+        // ones are written back for those that weren't set.
+        let attr = self.file_attr()?;
+        let accessed = match times.accessed {
+            Some(t) => t,
+            None => attr.accessed()?,
+        };
+        let modified = match times.modified {
+            Some(t) => t,
+            None => attr.modified()?,
+        };
+        cvt(syscall::futimens(self.0.raw(), &[accessed.into_inner(), modified.into_inner()]))?;
+        Ok(())
+    }
+
     pub fn path(&self) -> io::Result<PathBuf> {
         let mut buf: [u8; 4096] = [0; 4096];
         let count = cvt(syscall::fpath(*self.fd().as_inner() as usize, &mut buf))?;
@@ -327,6 +422,80 @@ impl File {
     pub fn into_fd(self) -> FileDesc { self.0 }
 }
 
+#[derive(Debug)]
+pub enum DirHandle {}
+
+impl DirHandle {
+    pub fn open(_path: &Path, _follow_symlinks: bool) -> io::Result<DirHandle> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn open_file(&self, _path: &Path, _opts: &OpenOptions) -> io::Result<File> {
+        match *self {}
+    }
+
+    pub fn open_dir(&self, _path: &Path) -> io::Result<DirHandle> {
+        match *self {}
+    }
+
+    pub fn create_dir(&self, _path: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn remove_file(&self, _path: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn remove_dir(&self, _path: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn rename(&self, _from: &Path, _to_dir: &DirHandle, _to: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn symlink(&self, _original: &Path, _link: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn metadata(&self, _path: &Path) -> io::Result<FileAttr> {
+        match *self {}
+    }
+
+    pub fn symlink_metadata(&self, _path: &Path) -> io::Result<FileAttr> {
+        match *self {}
+    }
+
+    pub fn read_dir(&self, _path: &Path) -> io::Result<ReadDir> {
+        match *self {}
+    }
+}
+
+impl CopyOptions {
+    pub fn new() -> CopyOptions {
+        CopyOptions {
+            reflink: Reflink::Never,
+            overwrite: true,
+            copy_symlinks: false,
+            preserve_timestamps: false,
+            preserve_ownership: false,
+            preserve_xattrs: false,
+        }
+    }
+
+    pub fn reflink(&mut self, reflink: Reflink) { self.reflink = reflink; }
+    pub fn overwrite(&mut self, overwrite: bool) { self.overwrite = overwrite; }
+    pub fn copy_symlinks(&mut self, copy_symlinks: bool) { self.copy_symlinks = copy_symlinks; }
+    pub fn preserve_timestamps(&mut self, preserve: bool) { self.preserve_timestamps = preserve; }
+    pub fn preserve_ownership(&mut self, preserve: bool) { self.preserve_ownership = preserve; }
+    pub fn preserve_xattrs(&mut self, preserve: bool) { self.preserve_xattrs = preserve; }
+}
+
+impl FileTimes {
+    pub fn set_accessed(&mut self, t: SystemTime) { self.accessed = Some(t); }
+    pub fn set_modified(&mut self, t: SystemTime) { self.modified = Some(t); }
+}
+
 impl DirBuilder {
     pub fn new() -> DirBuilder {
         DirBuilder { mode: 0o777 }
@@ -389,11 +558,47 @@ pub fn rename(old: &Path, new: &Path) -> io::Result<()> {
     Ok(())
 }
 
+pub fn rename_noreplace(_old: &Path, _new: &Path) -> io::Result<()> {
+    Err(Error::from_raw_os_error(syscall::ENOSYS))
+}
+
+pub fn rename_exchange(_old: &Path, _new: &Path) -> io::Result<()> {
+    Err(Error::from_raw_os_error(syscall::ENOSYS))
+}
+
 pub fn set_perm(p: &Path, perm: FilePermissions) -> io::Result<()> {
     cvt(syscall::chmod(p.to_str().unwrap(), perm.mode as usize))?;
     Ok(())
 }
 
+pub fn set_times(p: &Path, times: FileTimes) -> io::Result<()> {
+    let fd = cvt(syscall::open(p.to_str().unwrap(), syscall::O_CLOEXEC | syscall::O_STAT))?;
+    let file = File(FileDesc::new(fd));
+    file.set_times(times)
+}
+
+pub fn temp_file_options(opts: &mut OpenOptions, _delete_on_close: bool) {
+    opts.mode(0o600);
+}
+
+pub fn replacement_file_options(_opts: &mut OpenOptions) {}
+
+pub fn temp_dir_builder(builder: &mut DirBuilder) {
+    builder.set_mode(0o700);
+}
+
+pub fn open_tmpfile(_dir: &Path) -> io::Result<Option<File>> {
+    Ok(None)
+}
+
+pub fn sync_dir(p: &Path) -> io::Result<()> {
+    let flags = syscall::O_CLOEXEC | syscall::O_RDONLY | syscall::O_DIRECTORY;
+    let fd = cvt(syscall::open(p.to_str().unwrap(), flags))?;
+    let dir = FileDesc::new(fd);
+    cvt(syscall::fsync(dir.raw()))?;
+    Ok(())
+}
+
 pub fn rmdir(p: &Path) -> io::Result<()> {
     cvt(syscall::rmdir(p.to_str().unwrap()))?;
     Ok(())
@@ -453,12 +658,76 @@ pub fn lstat(p: &Path) -> io::Result<FileAttr> {
     file.file_attr()
 }
 
+pub fn fs_stats(p: &Path) -> io::Result<FsStats> {
+    let fd = cvt(syscall::open(p.to_str().unwrap(), syscall::O_CLOEXEC | syscall::O_STAT))?;
+    let file = File(FileDesc::new(fd));
+    file.fs_stats()
+}
+
+// Redox schemes only report block counts.
+impl FsStats {
+    pub fn total_space(&self) -> u64 { self.stat.f_blocks * self.block_size() }
+    pub fn free_space(&self) -> u64 { self.stat.f_bfree * self.block_size() }
+    pub fn available_space(&self) -> u64 { self.stat.f_bavail * self.block_size() }
+    pub fn total_inodes(&self) -> u64 { 0 }
+    pub fn free_inodes(&self) -> u64 { 0 }
+    pub fn available_inodes(&self) -> u64 { 0 }
+    pub fn block_size(&self) -> u64 { self.stat.f_bsize as u64 }
+    pub fn fs_id(&self) -> u64 { 0 }
+    pub fn max_name_len(&self) -> u64 { 0 }
+    pub fn is_read_only(&self) -> bool { false }
+    pub fn is_nosuid(&self) -> bool { false }
+}
+
 pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     let fd = cvt(syscall::open(p.to_str().unwrap(), syscall::O_CLOEXEC | syscall::O_STAT))?;
     let file = File(FileDesc::new(fd));
     file.path()
 }
 
+pub fn copy_with(from: &Path, to: &Path, opts: &CopyOptions) -> io::Result<u64> {
+    use fs::{File, OpenOptions};
+
+    if opts.reflink == Reflink::Always || opts.preserve_ownership || opts.preserve_xattrs {
+        return Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    if opts.copy_symlinks && lstat(from)?.file_type().is_symlink() {
+        let target = readlink(from)?;
+        if opts.overwrite && lstat(to).is_ok() {
+            unlink(to)?;
+        }
+        symlink(&target, to)?;
+        return Ok(0)
+    }
+
+    if !from.is_file() {
+        return Err(Error::new(ErrorKind::InvalidInput,
+                              "the source path is not an existing regular file"))
+    }
+
+    let mut reader = File::open(from)?;
+    let mut wopts = OpenOptions::new();
+    wopts.write(true);
+    if opts.overwrite {
+        wopts.create(true).truncate(true);
+    } else {
+        wopts.create_new(true);
+    }
+    let mut writer = wopts.open(to)?;
+    let attr = reader.as_inner().file_attr()?;
+
+    let ret = io::copy(&mut reader, &mut writer)?;
+    writer.as_inner().set_permissions(attr.perm())?;
+    if opts.preserve_timestamps {
+        writer.as_inner().set_times(FileTimes {
+            accessed: Some(attr.accessed()?),
+            modified: Some(attr.modified()?),
+        })?;
+    }
+    Ok(ret)
+}
+
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     use fs::{File, set_permissions};
     if !from.is_file() {
@@ -474,3 +743,27 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     set_permissions(to, perm)?;
     Ok(ret)
 }
+
+pub enum Mmap {}
+
+impl Mmap {
+    pub fn new(_file: &File, _mode: MmapMode, _offset: u64, _len: usize) -> io::Result<Mmap> {
+        Err(Error::from_raw_os_error(syscall::ENOSYS))
+    }
+
+    pub fn ptr(&self) -> *mut u8 {
+        match *self {}
+    }
+
+    pub fn len(&self) -> usize {
+        match *self {}
+    }
+
+    pub fn flush(&self, _offset: usize, _len: usize) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn advise(&self, _advice: Advice) -> io::Result<()> {
+        match *self {}
+    }
+}
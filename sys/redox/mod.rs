@@ -25,6 +25,7 @@ pub mod ext;
 pub mod fast_thread_local;
 pub mod fd;
 pub mod fs;
+pub mod io;
 pub mod memchr;
 pub mod mutex;
 pub mod net;
@@ -60,6 +61,22 @@ pub fn decode_error_kind(errno: i32) -> ErrorKind {
         syscall::EINVAL => ErrorKind::InvalidInput,
         syscall::ETIMEDOUT => ErrorKind::TimedOut,
         syscall::EEXIST => ErrorKind::AlreadyExists,
+        syscall::ENOTDIR => ErrorKind::NotADirectory,
+        syscall::EISDIR => ErrorKind::IsADirectory,
+        syscall::ENOTEMPTY => ErrorKind::DirectoryNotEmpty,
+        syscall::EROFS => ErrorKind::ReadOnlyFilesystem,
+        syscall::ENOSPC => ErrorKind::StorageFull,
+        syscall::EDQUOT => ErrorKind::QuotaExceeded,
+        syscall::EFBIG => ErrorKind::FileTooLarge,
+        syscall::EBUSY => ErrorKind::ResourceBusy,
+        syscall::EXDEV => ErrorKind::CrossesDevices,
+        syscall::EMLINK => ErrorKind::TooManyLinks,
+        syscall::ENAMETOOLONG => ErrorKind::InvalidFilename,
+        syscall::E2BIG => ErrorKind::ArgumentListTooLong,
+        syscall::EDEADLK => ErrorKind::Deadlock,
+        syscall::EHOSTUNREACH => ErrorKind::HostUnreachable,
+        syscall::ENETUNREACH => ErrorKind::NetworkUnreachable,
+        syscall::ENOSYS | syscall::EOPNOTSUPP => ErrorKind::Unsupported,
 
         // These two constants can have the same value on some systems,
         // but different values on others, so we can't use a match
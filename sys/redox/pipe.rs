@@ -8,9 +8,10 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use io;
+use io::{self, IoSlice, IoSliceMut};
 use sys::{cvt, syscall};
 use sys::fd::FileDesc;
+use sys_common::FromInner;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Anonymous pipes
@@ -24,6 +25,12 @@ pub fn anon_pipe() -> io::Result<(AnonPipe, AnonPipe)> {
     Ok((AnonPipe(FileDesc::new(fds[0])), AnonPipe(FileDesc::new(fds[1]))))
 }
 
+/// Creates the pipe handed out by `io::pipe`, which is the same kind of pipe
+/// that is used to talk to child processes.
+pub fn pipe() -> io::Result<(AnonPipe, AnonPipe)> {
+    anon_pipe()
+}
+
 impl AnonPipe {
     pub fn from_fd(fd: FileDesc) -> io::Result<AnonPipe> {
         fd.set_cloexec()?;
@@ -34,14 +41,32 @@ impl AnonPipe {
         self.0.read(buf)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        io::default_read_vectored(|b| self.read(b), bufs)
+    }
+
     pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
         self.0.write(buf)
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        io::default_write_vectored(|b| self.write(b), bufs)
+    }
+
+    pub fn duplicate(&self) -> io::Result<AnonPipe> {
+        self.0.duplicate().map(AnonPipe)
+    }
+
     pub fn fd(&self) -> &FileDesc { &self.0 }
     pub fn into_fd(self) -> FileDesc { self.0 }
 }
 
+impl FromInner<FileDesc> for AnonPipe {
+    fn from_inner(fd: FileDesc) -> AnonPipe {
+        AnonPipe(fd)
+    }
+}
+
 pub fn read2(p1: AnonPipe,
              v1: &mut Vec<u8>,
              p2: AnonPipe,
@@ -19,6 +19,13 @@ pub struct Stderr(());
 impl Stdin {
     pub fn new() -> io::Result<Stdin> { Ok(Stdin(())) }
 
+    pub fn is_terminal(&self) -> bool {
+        let fd = FileDesc::new(0);
+        let ret = fd.is_terminal();
+        fd.into_raw();
+        ret
+    }
+
     pub fn read(&self, data: &mut [u8]) -> io::Result<usize> {
         let fd = FileDesc::new(0);
         let ret = fd.read(data);
@@ -30,6 +37,13 @@ impl Stdin {
 impl Stdout {
     pub fn new() -> io::Result<Stdout> { Ok(Stdout(())) }
 
+    pub fn is_terminal(&self) -> bool {
+        let fd = FileDesc::new(1);
+        let ret = fd.is_terminal();
+        fd.into_raw();
+        ret
+    }
+
     pub fn write(&self, data: &[u8]) -> io::Result<usize> {
         let fd = FileDesc::new(1);
         let ret = fd.write(data);
@@ -45,6 +59,13 @@ impl Stdout {
 impl Stderr {
     pub fn new() -> io::Result<Stderr> { Ok(Stderr(())) }
 
+    pub fn is_terminal(&self) -> bool {
+        let fd = FileDesc::new(2);
+        let ret = fd.is_terminal();
+        fd.into_raw();
+        ret
+    }
+
     pub fn write(&self, data: &[u8]) -> io::Result<usize> {
         let fd = FileDesc::new(2);
         let ret = fd.write(data);
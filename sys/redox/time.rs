@@ -11,6 +11,7 @@
 use cmp::Ordering;
 use fmt;
 use sys::{cvt, syscall};
+use sys_common::IntoInner;
 use time::Duration;
 use convert::TryInto;
 
@@ -183,6 +184,12 @@ impl From<syscall::TimeSpec> for SystemTime {
     }
 }
 
+impl IntoInner<syscall::TimeSpec> for SystemTime {
+    fn into_inner(self) -> syscall::TimeSpec {
+        self.t.t
+    }
+}
+
 impl fmt::Debug for SystemTime {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("SystemTime")
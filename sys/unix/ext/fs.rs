@@ -12,11 +12,13 @@
 
 #![stable(feature = "rust1", since = "1.0.0")]
 
+use ffi::{OsStr, OsString};
 use fs::{self, Permissions, OpenOptions};
 use io;
 use libc;
 use path::Path;
 use sys;
+use sys::xattr::Target;
 use sys_common::{FromInner, AsInner, AsInnerMut};
 use sys::platform::fs::MetadataExt as UnixMetadataExt;
 
@@ -105,6 +107,108 @@ impl FileExt for fs::File {
     }
 }
 
+/// Access to the extended attributes of an open [`File`].
+///
+/// Extended attributes are name-value pairs attached to files, with names
+/// like `user.checksum` on Linux. They are supported on Linux and macOS;
+/// other platforms, and filesystems without extended attributes, report
+/// `ENOTSUP`.
+///
+/// See the free functions like [`get_xattr`] for operating on paths instead.
+///
+/// [`File`]: ../../../../std/fs/struct.File.html
+/// [`get_xattr`]: fn.get_xattr.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(unix_xattr)]
+/// use std::fs::File;
+/// use std::os::unix::fs::FileXattrExt;
+///
+/// # fn f() -> std::io::Result<()> {
+/// let file = File::open("artifact.tar")?;
+/// file.set_xattr("user.build", b"1234")?;
+/// for name in file.list_xattr()? {
+///     println!("{:?} = {:?}", name, file.get_xattr(&name)?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub trait FileXattrExt {
+    /// Returns the value of the extended attribute `name`.
+    ///
+    /// Fails with `ENODATA` on Linux and `ENOATTR` on macOS if the file has
+    /// no such attribute.
+    #[unstable(feature = "unix_xattr", issue = "0")]
+    fn get_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Vec<u8>>;
+
+    /// Sets the extended attribute `name` to `value`, creating it if needed.
+    #[unstable(feature = "unix_xattr", issue = "0")]
+    fn set_xattr<N: AsRef<OsStr>>(&self, name: N, value: &[u8]) -> io::Result<()>;
+
+    /// Returns the names of all extended attributes of the file.
+    #[unstable(feature = "unix_xattr", issue = "0")]
+    fn list_xattr(&self) -> io::Result<Vec<OsString>>;
+
+    /// Removes the extended attribute `name`.
+    #[unstable(feature = "unix_xattr", issue = "0")]
+    fn remove_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()>;
+}
+
+/// Changing the owner of an open [`File`].
+///
+/// [`File`]: ../../../../std/fs/struct.File.html
+#[unstable(feature = "unix_chown", issue = "0")]
+pub trait FileOwnerExt {
+    /// Changes the owner and group of the file, leaving the ones passed as
+    /// `None` unchanged.
+    ///
+    /// See [`chown`] for details.
+    ///
+    /// [`chown`]: fn.chown.html
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_chown)]
+    /// use std::fs::File;
+    /// use std::os::unix::fs::FileOwnerExt;
+    ///
+    /// # fn f() -> std::io::Result<()> {
+    /// let file = File::open("shared.log")?;
+    /// file.chown(None, Some(100))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[unstable(feature = "unix_chown", issue = "0")]
+    fn chown(&self, uid: Option<u32>, gid: Option<u32>) -> io::Result<()>;
+}
+
+#[unstable(feature = "unix_chown", issue = "0")]
+impl FileOwnerExt for fs::File {
+    fn chown(&self, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+        self.as_inner().chown(uid, gid)
+    }
+}
+
+#[unstable(feature = "unix_xattr", issue = "0")]
+impl FileXattrExt for fs::File {
+    fn get_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Vec<u8>> {
+        sys::xattr::get(Target::Fd(self.as_inner().fd().raw()), name.as_ref())
+    }
+    fn set_xattr<N: AsRef<OsStr>>(&self, name: N, value: &[u8]) -> io::Result<()> {
+        sys::xattr::set(Target::Fd(self.as_inner().fd().raw()), name.as_ref(), value)
+    }
+    fn list_xattr(&self) -> io::Result<Vec<OsString>> {
+        sys::xattr::list(Target::Fd(self.as_inner().fd().raw()))
+    }
+    fn remove_xattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
+        sys::xattr::remove(Target::Fd(self.as_inner().fd().raw()), name.as_ref())
+    }
+}
+
 /// Unix-specific extensions to `Permissions`
 #[stable(feature = "fs_ext", since = "1.1.0")]
 pub trait PermissionsExt {
@@ -701,6 +805,181 @@ pub fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()>
     sys::fs::symlink(src.as_ref(), dst.as_ref())
 }
 
+/// Changes the owner and group of the file at `path`, following symbolic
+/// links.
+///
+/// An id passed as `None` is left unchanged. Changing the owner usually
+/// requires privileges, while the owner of a file can change its group to
+/// any group they're a member of.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(unix_chown)]
+/// use std::os::unix::fs;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// fs::chown("/srv/data", Some(1000), Some(1000))?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "unix_chown", issue = "0")]
+pub fn chown<P: AsRef<Path>>(path: P, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+    sys::fs::chown(path.as_ref(), uid, gid)
+}
+
+/// Like [`chown`], but changes the owner and group of a symbolic link
+/// itself.
+///
+/// [`chown`]: fn.chown.html
+#[unstable(feature = "unix_chown", issue = "0")]
+pub fn lchown<P: AsRef<Path>>(path: P, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+    sys::fs::lchown(path.as_ref(), uid, gid)
+}
+
+/// Changes the root directory of the current process to `dir`.
+///
+/// This doesn't change the current working directory, which should be
+/// changed to the new root (or a directory within it) afterwards. Changing
+/// the root directory requires privileges.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(unix_chroot)]
+/// use std::env;
+/// use std::os::unix::fs;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// fs::chroot("/sandbox")?;
+/// env::set_current_dir("/")?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "unix_chroot", issue = "0")]
+pub fn chroot<P: AsRef<Path>>(dir: P) -> io::Result<()> {
+    sys::fs::chroot(dir.as_ref())
+}
+
+/// Creates a named pipe (FIFO) at `path` with the permission bits `mode`.
+///
+/// As for other new files, the `umask` of the process is applied to `mode`.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(unix_mknod)]
+/// use std::os::unix::fs;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// fs::mkfifo("/tmp/requests", 0o600)?;
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "unix_mknod", issue = "0")]
+pub fn mkfifo<P: AsRef<Path>>(path: P, mode: u32) -> io::Result<()> {
+    sys::fs::mkfifo(path.as_ref(), mode)
+}
+
+/// Creates a filesystem node at `path`.
+///
+/// `mode` contains both the type of the node, one of `S_IFREG`, `S_IFCHR`,
+/// `S_IFBLK`, `S_IFIFO` and `S_IFSOCK`, and its permission bits. `dev` is the
+/// device number of character and block devices, as returned by
+/// [`MetadataExt::rdev`], and is ignored for other types. Creating devices
+/// requires privileges.
+///
+/// [`MetadataExt::rdev`]: trait.MetadataExt.html#tymethod.rdev
+#[unstable(feature = "unix_mknod", issue = "0")]
+pub fn mknod<P: AsRef<Path>>(path: P, mode: u32, dev: u64) -> io::Result<()> {
+    sys::fs::mknod(path.as_ref(), mode, dev)
+}
+
+/// Returns the value of the extended attribute `name` of the file at `path`,
+/// following symbolic links.
+///
+/// See [`FileXattrExt`] for details on extended attributes.
+///
+/// [`FileXattrExt`]: trait.FileXattrExt.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(unix_xattr)]
+/// use std::os::unix::fs;
+///
+/// # fn foo() -> std::io::Result<()> {
+/// fs::set_xattr("artifact.tar", "user.build", b"1234")?;
+/// assert_eq!(fs::get_xattr("artifact.tar", "user.build")?, b"1234");
+/// # Ok(())
+/// # }
+/// ```
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn get_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N) -> io::Result<Vec<u8>> {
+    sys::xattr::get(Target::Path(path.as_ref()), name.as_ref())
+}
+
+/// Like [`get_xattr`], but returns the attribute of a symbolic link itself.
+///
+/// [`get_xattr`]: fn.get_xattr.html
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn get_xattr_nofollow<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N)
+                                                           -> io::Result<Vec<u8>> {
+    sys::xattr::get(Target::Link(path.as_ref()), name.as_ref())
+}
+
+/// Sets the extended attribute `name` of the file at `path` to `value`,
+/// following symbolic links.
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn set_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N, value: &[u8])
+                                                  -> io::Result<()> {
+    sys::xattr::set(Target::Path(path.as_ref()), name.as_ref(), value)
+}
+
+/// Like [`set_xattr`], but sets the attribute of a symbolic link itself.
+///
+/// Linux only allows `trusted.*` and `security.*` attributes on symbolic
+/// links.
+///
+/// [`set_xattr`]: fn.set_xattr.html
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn set_xattr_nofollow<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N, value: &[u8])
+                                                           -> io::Result<()> {
+    sys::xattr::set(Target::Link(path.as_ref()), name.as_ref(), value)
+}
+
+/// Returns the names of all extended attributes of the file at `path`,
+/// following symbolic links.
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn list_xattr<P: AsRef<Path>>(path: P) -> io::Result<Vec<OsString>> {
+    sys::xattr::list(Target::Path(path.as_ref()))
+}
+
+/// Like [`list_xattr`], but lists the attributes of a symbolic link itself.
+///
+/// [`list_xattr`]: fn.list_xattr.html
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn list_xattr_nofollow<P: AsRef<Path>>(path: P) -> io::Result<Vec<OsString>> {
+    sys::xattr::list(Target::Link(path.as_ref()))
+}
+
+/// Removes the extended attribute `name` of the file at `path`, following
+/// symbolic links.
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn remove_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N) -> io::Result<()> {
+    sys::xattr::remove(Target::Path(path.as_ref()), name.as_ref())
+}
+
+/// Like [`remove_xattr`], but removes the attribute of a symbolic link
+/// itself.
+///
+/// [`remove_xattr`]: fn.remove_xattr.html
+#[unstable(feature = "unix_xattr", issue = "0")]
+pub fn remove_xattr_nofollow<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N)
+                                                              -> io::Result<()> {
+    sys::xattr::remove(Target::Link(path.as_ref()), name.as_ref())
+}
+
 #[stable(feature = "dir_builder", since = "1.6.0")]
 /// An extension trait for [`fs::DirBuilder`] for unix-specific options.
 ///
@@ -13,7 +13,9 @@
 #![stable(feature = "rust1", since = "1.0.0")]
 
 use fs;
+use net;
 use os::raw;
+use process;
 use sys;
 use io;
 use sys_common::{AsInner, FromInner, IntoInner};
@@ -92,6 +94,46 @@ impl IntoRawFd for fs::File {
     }
 }
 
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl AsRawFd for io::PipeReader {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_inner().fd().raw()
+    }
+}
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl FromRawFd for io::PipeReader {
+    unsafe fn from_raw_fd(fd: RawFd) -> io::PipeReader {
+        let fd = sys::fd::FileDesc::new(fd);
+        io::PipeReader::from_inner(sys::pipe::AnonPipe::from_inner(fd))
+    }
+}
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl IntoRawFd for io::PipeReader {
+    fn into_raw_fd(self) -> RawFd {
+        self.into_inner().into_fd().into_raw()
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl AsRawFd for io::PipeWriter {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_inner().fd().raw()
+    }
+}
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl FromRawFd for io::PipeWriter {
+    unsafe fn from_raw_fd(fd: RawFd) -> io::PipeWriter {
+        let fd = sys::fd::FileDesc::new(fd);
+        io::PipeWriter::from_inner(sys::pipe::AnonPipe::from_inner(fd))
+    }
+}
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl IntoRawFd for io::PipeWriter {
+    fn into_raw_fd(self) -> RawFd {
+        self.into_inner().into_fd().into_raw()
+    }
+}
+
 #[stable(feature = "asraw_stdio", since = "1.21.0")]
 impl AsRawFd for io::Stdin {
     fn as_raw_fd(&self) -> RawFd { libc::STDIN_FILENO }
@@ -106,3 +148,22 @@ impl AsRawFd for io::Stdout {
 impl AsRawFd for io::Stderr {
     fn as_raw_fd(&self) -> RawFd { libc::STDERR_FILENO }
 }
+
+// The standard streams and `File` check their descriptors through `sys`, the
+// other types owning one are listed here.
+macro_rules! impl_is_terminal {
+    ($($t:ty)*) => ($(
+        #[unstable(feature = "is_terminal", issue = "0")]
+        impl io::IsTerminal for $t {
+            fn is_terminal(&self) -> bool {
+                unsafe { libc::isatty(self.as_raw_fd()) != 0 }
+            }
+        }
+    )*)
+}
+
+impl_is_terminal! {
+    io::PipeReader io::PipeWriter
+    net::TcpStream super::net::UnixStream
+    process::ChildStdin process::ChildStdout process::ChildStderr
+}
@@ -11,7 +11,7 @@
 #![unstable(reason = "not public", issue = "0", feature = "fd")]
 
 use cmp;
-use io::{self, Read};
+use io::{self, Read, IoSlice, IoSliceMut, ReadBuf};
 use libc::{self, c_int, c_void, ssize_t};
 use mem;
 use sync::atomic::{AtomicBool, Ordering};
@@ -39,6 +39,33 @@ fn max_len() -> usize {
     }
 }
 
+// The maximum number of buffers that may be passed to a single `readv` or
+// `writev` call (`IOV_MAX`, or `UIO_MAXIOV` on Linux). Extra buffers are
+// simply left untouched, which is allowed as the operation may be short.
+#[cfg(any(target_os = "linux",
+          target_os = "android",
+          target_os = "emscripten",
+          target_os = "macos",
+          target_os = "ios",
+          target_os = "freebsd",
+          target_os = "dragonfly",
+          target_os = "netbsd",
+          target_os = "openbsd",
+          target_os = "bitrig"))]
+fn max_iov() -> usize { 1024 }
+
+#[cfg(not(any(target_os = "linux",
+              target_os = "android",
+              target_os = "emscripten",
+              target_os = "macos",
+              target_os = "ios",
+              target_os = "freebsd",
+              target_os = "dragonfly",
+              target_os = "netbsd",
+              target_os = "openbsd",
+              target_os = "bitrig")))]
+fn max_iov() -> usize { 16 }
+
 impl FileDesc {
     pub fn new(fd: c_int) -> FileDesc {
         FileDesc { fd: fd }
@@ -62,6 +89,27 @@ impl FileDesc {
         Ok(ret as usize)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        let ret = cvt(unsafe {
+            libc::readv(self.fd,
+                        bufs.as_ptr() as *const libc::iovec,
+                        cmp::min(bufs.len(), max_iov()) as c_int)
+        })?;
+        Ok(ret as usize)
+    }
+
+    pub fn read_buf(&self, buf: &mut ReadBuf) -> io::Result<()> {
+        let len = cmp::min(buf.remaining(), max_len());
+        let ret = cvt(unsafe {
+            libc::read(self.fd,
+                       buf.unfilled_mut().as_mut_ptr() as *mut c_void,
+                       len)
+        })?;
+        unsafe { buf.assume_init(ret as usize); }
+        buf.add_filled(ret as usize);
+        Ok(())
+    }
+
     pub fn read_to_end(&self, buf: &mut Vec<u8>) -> io::Result<usize> {
         let mut me = self;
         (&mut me).read_to_end(buf)
@@ -108,6 +156,15 @@ impl FileDesc {
         Ok(ret as usize)
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        let ret = cvt(unsafe {
+            libc::writev(self.fd,
+                         bufs.as_ptr() as *const libc::iovec,
+                         cmp::min(bufs.len(), max_iov()) as c_int)
+        })?;
+        Ok(ret as usize)
+    }
+
     pub fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
         #[cfg(target_os = "android")]
         use super::android::cvt_pwrite64;
@@ -194,6 +251,10 @@ impl FileDesc {
         }
     }
 
+    pub fn is_terminal(&self) -> bool {
+        unsafe { libc::isatty(self.fd) != 0 }
+    }
+
     pub fn duplicate(&self) -> io::Result<FileDesc> {
         // We want to atomically duplicate this file descriptor and set the
         // CLOEXEC flag, and currently that's done via F_DUPFD_CLOEXEC. This
@@ -249,6 +310,14 @@ impl<'a> Read for &'a FileDesc {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         (**self).read(buf)
     }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        (**self).read_vectored(bufs)
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> io::Result<()> {
+        (**self).read_buf(buf)
+    }
 }
 
 impl AsInner<c_int> for FileDesc {
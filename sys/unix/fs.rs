@@ -11,17 +11,19 @@
 use os::unix::prelude::*;
 
 use ffi::{CString, CStr, OsString, OsStr};
+use fs::{Advice, MmapMode, Reflink};
 use fmt;
-use io::{self, Error, ErrorKind, SeekFrom};
+use io::{self, Error, ErrorKind, SeekFrom, IoSlice, IoSliceMut, ReadBuf};
 use libc::{self, c_int, mode_t};
 use mem;
-use path::{Path, PathBuf};
+use path::{Component, Path, PathBuf};
 use ptr;
 use sync::Arc;
 use sys::fd::FileDesc;
+use sys::os;
 use sys::time::SystemTime;
 use sys::{cvt, cvt_r};
-use sys_common::{AsInner, FromInner};
+use sys_common::{AsInner, FromInner, IntoInner};
 
 #[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "l4re"))]
 use libc::{stat64, fstat64, lstat64, off64_t, ftruncate64, lseek64, dirent64, readdir64_r, open64};
@@ -40,12 +42,144 @@ use libc::{stat as stat64, fstat as fstat64, lstat as lstat64, off_t as off64_t,
               target_os = "l4re",
               target_os = "fuchsia")))]
 use libc::{readdir_r as readdir64_r};
+#[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "l4re"))]
+use libc::fstatat64;
+#[cfg(not(any(target_os = "linux", target_os = "emscripten", target_os = "l4re")))]
+use libc::fstatat as fstatat64;
+#[cfg(target_os = "linux")]
+use libc::{statvfs64, fstatvfs64};
+#[cfg(not(target_os = "linux"))]
+use libc::{statvfs as statvfs64, fstatvfs as fstatvfs64};
 
 pub struct File(FileDesc);
 
 #[derive(Clone)]
 pub struct FileAttr {
     stat: stat64,
+    #[cfg(target_os = "linux")]
+    statx_extra_fields: Option<StatxExtraFields>,
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Clone)]
+struct StatxExtraFields {
+    // This is needed to check if btime is supported by the filesystem.
+    stx_mask: u32,
+    stx_btime: statx_timestamp,
+}
+
+// `struct statx` and friends are not exposed by our version of `libc` yet, so
+// they're mirrored here from `linux/stat.h`.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct statx_timestamp {
+    tv_sec: i64,
+    tv_nsec: u32,
+    __reserved: i32,
+}
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct statx {
+    stx_mask: u32,
+    stx_blksize: u32,
+    stx_attributes: u64,
+    stx_nlink: u32,
+    stx_uid: u32,
+    stx_gid: u32,
+    stx_mode: u16,
+    __spare0: [u16; 1],
+    stx_ino: u64,
+    stx_size: u64,
+    stx_blocks: u64,
+    stx_attributes_mask: u64,
+    stx_atime: statx_timestamp,
+    stx_btime: statx_timestamp,
+    stx_ctime: statx_timestamp,
+    stx_mtime: statx_timestamp,
+    stx_rdev_major: u32,
+    stx_rdev_minor: u32,
+    stx_dev_major: u32,
+    stx_dev_minor: u32,
+    __spare2: [u64; 14],
+}
+
+#[cfg(target_os = "linux")]
+const STATX_ALL: u32 = 0xfff;
+#[cfg(target_os = "linux")]
+const STATX_BTIME: u32 = 0x800;
+
+// Fetches the metadata of `path` relative to `fd` with `statx`, which unlike
+// the `stat` family also reports the birth time of a file. Returns `None` if
+// `statx` isn't available, in which case the caller falls back to `stat64`.
+#[cfg(target_os = "linux")]
+fn try_statx(fd: c_int, path: *const libc::c_char, flags: c_int)
+             -> Option<io::Result<FileAttr>> {
+    use sync::atomic::{AtomicBool, Ordering};
+
+    // Glibc only exposes `statx` since 2.28 and the system call itself was
+    // added in Linux 4.11, and seccomp filters of some container runtimes
+    // reject it with `EPERM`. Remember once it turned out to be unusable so
+    // that only the first call pays for it.
+    weak! { fn statx(c_int, *const libc::c_char, c_int, libc::c_uint, *mut statx) -> c_int }
+    static STATX_UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+    if STATX_UNAVAILABLE.load(Ordering::Relaxed) {
+        return None
+    }
+    let statx_fn = match statx.get() {
+        Some(f) => f,
+        None => {
+            STATX_UNAVAILABLE.store(true, Ordering::Relaxed);
+            return None
+        }
+    };
+
+    let mut buf: statx = unsafe { mem::zeroed() };
+    if let Err(err) = cvt(unsafe { statx_fn(fd, path, flags, STATX_ALL, &mut buf) }) {
+        return match err.raw_os_error() {
+            Some(libc::ENOSYS) | Some(libc::EPERM) => {
+                STATX_UNAVAILABLE.store(true, Ordering::Relaxed);
+                None
+            }
+            _ => Some(Err(err)),
+        }
+    }
+
+    // Same as glibc's `gnu_dev_makedev`.
+    fn makedev(major: u32, minor: u32) -> u64 {
+        let (major, minor) = (major as u64, minor as u64);
+        ((major & 0xfffff000) << 32) | ((major & 0x00000fff) << 8) |
+        ((minor & 0xffffff00) << 12) | (minor & 0x000000ff)
+    }
+
+    let mut stat: stat64 = unsafe { mem::zeroed() };
+    stat.st_dev = makedev(buf.stx_dev_major, buf.stx_dev_minor) as _;
+    stat.st_ino = buf.stx_ino as _;
+    stat.st_nlink = buf.stx_nlink as _;
+    stat.st_mode = buf.stx_mode as _;
+    stat.st_uid = buf.stx_uid as _;
+    stat.st_gid = buf.stx_gid as _;
+    stat.st_rdev = makedev(buf.stx_rdev_major, buf.stx_rdev_minor) as _;
+    stat.st_size = buf.stx_size as _;
+    stat.st_blksize = buf.stx_blksize as _;
+    stat.st_blocks = buf.stx_blocks as _;
+    stat.st_atime = buf.stx_atime.tv_sec as _;
+    stat.st_atime_nsec = buf.stx_atime.tv_nsec as _;
+    stat.st_mtime = buf.stx_mtime.tv_sec as _;
+    stat.st_mtime_nsec = buf.stx_mtime.tv_nsec as _;
+    stat.st_ctime = buf.stx_ctime.tv_sec as _;
+    stat.st_ctime_nsec = buf.stx_ctime.tv_nsec as _;
+
+    Some(Ok(FileAttr {
+        stat: stat,
+        statx_extra_fields: Some(StatxExtraFields {
+            stx_mask: buf.stx_mask,
+            stx_btime: buf.stx_btime,
+        }),
+    }))
 }
 
 pub struct ReadDir {
@@ -92,7 +226,44 @@ pub struct FileType { mode: mode_t }
 #[derive(Debug)]
 pub struct DirBuilder { mode: mode_t }
 
+#[derive(Clone, Debug)]
+pub struct CopyOptions {
+    reflink: Reflink,
+    overwrite: bool,
+    copy_symlinks: bool,
+    preserve_timestamps: bool,
+    preserve_ownership: bool,
+    preserve_xattrs: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FileTimes {
+    accessed: Option<SystemTime>,
+    modified: Option<SystemTime>,
+}
+
+pub struct DirHandle {
+    fd: FileDesc,
+    // Only used to build the paths of entries returned by `read_dir`, the
+    // directory may have been moved since it was opened.
+    path: PathBuf,
+    follow_symlinks: bool,
+}
+
+#[derive(Clone)]
+pub struct FsStats {
+    stat: statvfs64,
+}
+
 impl FileAttr {
+    fn from_stat64(stat: stat64) -> FileAttr {
+        FileAttr {
+            stat: stat,
+            #[cfg(target_os = "linux")]
+            statx_extra_fields: None,
+        }
+    }
+
     pub fn size(&self) -> u64 { self.stat.st_size as u64 }
     pub fn perm(&self) -> FilePermissions {
         FilePermissions { mode: (self.stat.st_mode as mode_t) }
@@ -155,11 +326,33 @@ impl FileAttr {
         }))
     }
 
+    #[cfg(target_os = "linux")]
+    pub fn created(&self) -> io::Result<SystemTime> {
+        match self.statx_extra_fields {
+            Some(ref ext) if (ext.stx_mask & STATX_BTIME) != 0 => {
+                Ok(SystemTime::from(libc::timespec {
+                    tv_sec: ext.stx_btime.tv_sec as libc::time_t,
+                    tv_nsec: ext.stx_btime.tv_nsec as _,
+                }))
+            }
+            Some(_) => {
+                Err(io::Error::new(io::ErrorKind::Other,
+                                   "creation time is not available for the filesystem"))
+            }
+            None => {
+                Err(io::Error::new(io::ErrorKind::Other,
+                                   "creation time is not available on this platform \
+                                    currently"))
+            }
+        }
+    }
+
     #[cfg(not(any(target_os = "bitrig",
                   target_os = "freebsd",
                   target_os = "openbsd",
                   target_os = "macos",
-                  target_os = "ios")))]
+                  target_os = "ios",
+                  target_os = "linux")))]
     pub fn created(&self) -> io::Result<SystemTime> {
         Err(io::Error::new(io::ErrorKind::Other,
                            "creation time is not available on this platform \
@@ -197,6 +390,55 @@ impl FileType {
     pub fn is(&self, mode: mode_t) -> bool { self.mode & libc::S_IFMT == mode }
 }
 
+impl CopyOptions {
+    pub fn new() -> CopyOptions {
+        CopyOptions {
+            reflink: Reflink::Never,
+            overwrite: true,
+            copy_symlinks: false,
+            preserve_timestamps: false,
+            preserve_ownership: false,
+            preserve_xattrs: false,
+        }
+    }
+
+    pub fn reflink(&mut self, reflink: Reflink) { self.reflink = reflink; }
+    pub fn overwrite(&mut self, overwrite: bool) { self.overwrite = overwrite; }
+    pub fn copy_symlinks(&mut self, copy_symlinks: bool) { self.copy_symlinks = copy_symlinks; }
+    pub fn preserve_timestamps(&mut self, preserve: bool) { self.preserve_timestamps = preserve; }
+    pub fn preserve_ownership(&mut self, preserve: bool) { self.preserve_ownership = preserve; }
+    pub fn preserve_xattrs(&mut self, preserve: bool) { self.preserve_xattrs = preserve; }
+}
+
+impl FileTimes {
+    pub fn set_accessed(&mut self, t: SystemTime) { self.accessed = Some(t); }
+    pub fn set_modified(&mut self, t: SystemTime) { self.modified = Some(t); }
+
+    // Times that weren't set are left alone by `futimens` and `utimensat`.
+    fn to_timespecs(&self) -> [libc::timespec; 2] {
+        let to_timespec = |time: Option<SystemTime>| match time {
+            Some(time) => time.into_inner(),
+            None => libc::timespec { tv_sec: 0, tv_nsec: libc::UTIME_OMIT as _ },
+        };
+        [to_timespec(self.accessed), to_timespec(self.modified)]
+    }
+
+    // `futimes` and `utimes` can only set both times at once, so fill in the
+    // times that weren't set from the current metadata.
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    fn to_timevals(&self, attr: &FileAttr) -> io::Result<[libc::timeval; 2]> {
+        let to_timeval = |time: Option<SystemTime>, current: io::Result<SystemTime>| {
+            let ts = match time {
+                Some(time) => time.into_inner(),
+                None => current?.into_inner(),
+            };
+            Ok(libc::timeval { tv_sec: ts.tv_sec, tv_usec: (ts.tv_nsec / 1000) as _ })
+        };
+        Ok([to_timeval(self.accessed, attr.accessed())?,
+            to_timeval(self.modified, attr.modified())?])
+    }
+}
+
 impl FromInner<u32> for FilePermissions {
     fn from_inner(mode: u32) -> FilePermissions {
         FilePermissions { mode: mode as mode_t }
@@ -288,6 +530,22 @@ impl DirEntry {
         OsStr::from_bytes(self.name_bytes()).to_os_string()
     }
 
+    // Whether the entry is a directory, if that is known without a call to
+    // `stat`.
+    #[cfg(any(target_os = "solaris", target_os = "haiku"))]
+    fn is_dir_hint(&self) -> Option<bool> {
+        None
+    }
+
+    #[cfg(not(any(target_os = "solaris", target_os = "haiku")))]
+    fn is_dir_hint(&self) -> Option<bool> {
+        match self.entry.d_type {
+            libc::DT_UNKNOWN => None,
+            libc::DT_DIR => Some(true),
+            _ => Some(false),
+        }
+    }
+
     pub fn metadata(&self) -> io::Result<FileAttr> {
         lstat(&self.path())
     }
@@ -454,11 +712,21 @@ impl File {
     }
 
     pub fn file_attr(&self) -> io::Result<FileAttr> {
+        #[cfg(target_os = "linux")]
+        {
+            let empty = b"\0";
+            if let Some(ret) = try_statx(self.0.raw(),
+                                         empty.as_ptr() as *const libc::c_char,
+                                         libc::AT_EMPTY_PATH) {
+                return ret
+            }
+        }
+
         let mut stat: stat64 = unsafe { mem::zeroed() };
         cvt(unsafe {
             fstat64(self.0.raw(), &mut stat)
         })?;
-        Ok(FileAttr { stat: stat })
+        Ok(FileAttr::from_stat64(stat))
     }
 
     pub fn fsync(&self) -> io::Result<()> {
@@ -492,10 +760,191 @@ impl File {
         }).map(|_| ());
     }
 
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        self.fallocate(0, offset, len)
+    }
+
+    // `posix_fallocate` isn't available on macOS, whose `F_PREALLOCATE` can
+    // only allocate space at the end of the file, which then still has to be
+    // extended to cover it.
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    pub fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        let end = offset.checked_add(len).ok_or_else(|| {
+            Error::new(ErrorKind::InvalidInput, "allocation range overflows")
+        })?;
+        let size = self.file_attr()?.size();
+        if end <= size {
+            return Ok(())
+        }
+        let mut store = libc::fstore_t {
+            fst_flags: libc::F_ALLOCATECONTIG,
+            fst_posmode: libc::F_PEOFPOSMODE,
+            fst_offset: 0,
+            fst_length: to_off_t(end - size)?,
+            fst_bytesalloc: 0,
+        };
+        if unsafe { libc::fcntl(self.0.raw(), libc::F_PREALLOCATE, &store) } == -1 {
+            // There isn't enough contiguous space, fragment the allocation.
+            store.fst_flags = libc::F_ALLOCATEALL;
+            cvt(unsafe { libc::fcntl(self.0.raw(), libc::F_PREALLOCATE, &store) })?;
+        }
+        self.truncate(end)
+    }
+
+    #[cfg(any(target_os = "freebsd", target_os = "solaris"))]
+    pub fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        let (offset, len) = (to_off_t(offset)?, to_off_t(len)?);
+        loop {
+            // `posix_fallocate` returns the error instead of setting `errno`.
+            match unsafe { libc::posix_fallocate(self.0.raw(), offset, len) } {
+                0 => return Ok(()),
+                libc::EINTR => {}
+                err => return Err(Error::from_raw_os_error(err)),
+            }
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android",
+                  target_os = "macos", target_os = "ios",
+                  target_os = "freebsd", target_os = "solaris")))]
+    pub fn allocate(&self, _offset: u64, _len: u64) -> io::Result<()> {
+        Err(Error::new(ErrorKind::Unsupported,
+                       "preallocating files is not supported on this platform"))
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn punch_hole(&self, offset: u64, len: u64) -> io::Result<()> {
+        // Values from `linux/falloc.h`.
+        const FALLOC_FL_KEEP_SIZE: c_int = 0x01;
+        const FALLOC_FL_PUNCH_HOLE: c_int = 0x02;
+        self.fallocate(FALLOC_FL_PUNCH_HOLE | FALLOC_FL_KEEP_SIZE, offset, len)
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub fn punch_hole(&self, _offset: u64, _len: u64) -> io::Result<()> {
+        Err(Error::new(ErrorKind::Unsupported,
+                       "punching holes is not supported on this platform"))
+    }
+
+    // Filesystems without support for `mode` fail with `EOPNOTSUPP`, which is
+    // reported as `ErrorKind::Unsupported`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn fallocate(&self, mode: c_int, offset: u64, len: u64) -> io::Result<()> {
+        let (offset, len) = (to_off_t(offset)?, to_off_t(len)?);
+        cvt_r(|| unsafe { libc::fallocate(self.0.raw(), mode, offset, len) })?;
+        Ok(())
+    }
+
+    // Returns the start and end of the first region of data at or after
+    // `offset`, or `None` if only a hole is left. The cursor is moved while
+    // looking and restored afterwards, hence `&mut self`.
+    #[cfg(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios",
+              target_os = "freebsd", target_os = "solaris"))]
+    pub fn next_data(&mut self, offset: u64) -> io::Result<Option<(u64, u64)>> {
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        const SEEK_DATA: c_int = 3;
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        const SEEK_HOLE: c_int = 4;
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        const SEEK_HOLE: c_int = 3;
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        const SEEK_DATA: c_int = 4;
+
+        // Files can't extend beyond the largest offset.
+        if offset > i64::max_value() as u64 {
+            return Ok(None)
+        }
+        let pos = self.seek(SeekFrom::Current(0))?;
+        let result = self.lseek(offset as i64, SEEK_DATA).and_then(|start| {
+            // There's always an implicit hole at the end of the file.
+            let end = self.lseek(start as i64, SEEK_HOLE)?;
+            Ok(Some((start, end)))
+        });
+        self.seek(SeekFrom::Start(pos))?;
+        match result {
+            // There's no more data after `offset`.
+            Err(ref e) if e.raw_os_error() == Some(libc::ENXIO) => Ok(None),
+            // The filesystem (or kernel) doesn't support looking for data.
+            Err(ref e) if e.raw_os_error() == Some(libc::EINVAL) => {
+                Err(Error::new(ErrorKind::Unsupported,
+                               "finding holes is not supported by the filesystem"))
+            }
+            result => result,
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android",
+                  target_os = "macos", target_os = "ios",
+                  target_os = "freebsd", target_os = "solaris")))]
+    pub fn next_data(&mut self, _offset: u64) -> io::Result<Option<(u64, u64)>> {
+        Err(Error::new(ErrorKind::Unsupported,
+                       "finding holes is not supported on this platform"))
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.flock(libc::LOCK_SH)
+    }
+
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.flock(libc::LOCK_EX)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        self.flock(libc::LOCK_SH | libc::LOCK_NB)
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        self.flock(libc::LOCK_EX | libc::LOCK_NB)
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        self.flock(libc::LOCK_UN)
+    }
+
+    #[cfg(not(target_os = "solaris"))]
+    fn flock(&self, operation: c_int) -> io::Result<()> {
+        cvt_r(|| unsafe { libc::flock(self.0.raw(), operation) })?;
+        Ok(())
+    }
+
+    // Solaris has no `flock`, so fall back to locking the whole file with
+    // `fcntl`. These locks are owned by the process rather than by the open
+    // file description, so they don't exclude other handles in the same
+    // process and are dropped as soon as any handle to the file is closed.
+    #[cfg(target_os = "solaris")]
+    fn flock(&self, operation: c_int) -> io::Result<()> {
+        let mut lock: libc::flock = unsafe { mem::zeroed() };
+        lock.l_type = match operation & !libc::LOCK_NB {
+            libc::LOCK_SH => libc::F_RDLCK,
+            libc::LOCK_EX => libc::F_WRLCK,
+            _ => libc::F_UNLCK,
+        } as libc::c_short;
+        lock.l_whence = libc::SEEK_SET as libc::c_short;
+        let cmd = if operation & libc::LOCK_NB != 0 { libc::F_SETLK } else { libc::F_SETLKW };
+        match cvt_r(|| unsafe { libc::fcntl(self.0.raw(), cmd, &lock) }) {
+            Ok(_) => Ok(()),
+            // A conflicting lock may also be reported as `EACCES`.
+            Err(ref e) if e.raw_os_error() == Some(libc::EACCES) => {
+                Err(Error::from_raw_os_error(libc::EAGAIN))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.read(buf)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
+    pub fn read_buf(&self, buf: &mut ReadBuf) -> io::Result<()> {
+        self.0.read_buf(buf)
+    }
+
     pub fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
         self.0.read_at(buf, offset)
     }
@@ -504,6 +953,10 @@ impl File {
         self.0.write(buf)
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+
     pub fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
         self.0.write_at(buf, offset)
     }
@@ -518,6 +971,10 @@ impl File {
             SeekFrom::End(off) => (libc::SEEK_END, off),
             SeekFrom::Current(off) => (libc::SEEK_CUR, off),
         };
+        self.lseek(pos, whence)
+    }
+
+    fn lseek(&self, pos: i64, whence: c_int) -> io::Result<u64> {
         #[cfg(target_os = "emscripten")]
         let pos = pos as i32;
         let n = cvt(unsafe { lseek64(self.0.raw(), pos, whence) })?;
@@ -528,6 +985,10 @@ impl File {
         self.0.duplicate().map(File)
     }
 
+    pub fn is_terminal(&self) -> bool {
+        self.0.is_terminal()
+    }
+
     pub fn fd(&self) -> &FileDesc { &self.0 }
 
     pub fn into_fd(self) -> FileDesc { self.0 }
@@ -536,6 +997,39 @@ impl File {
         cvt_r(|| unsafe { libc::fchmod(self.0.raw(), perm.mode) })?;
         Ok(())
     }
+
+    pub fn chown(&self, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+        let (uid, gid) = owner_ids(uid, gid);
+        cvt_r(|| unsafe { libc::fchown(self.0.raw(), uid, gid) })?;
+        Ok(())
+    }
+
+    pub fn fs_stats(&self) -> io::Result<FsStats> {
+        let mut stat: statvfs64 = unsafe { mem::zeroed() };
+        cvt_r(|| unsafe { fstatvfs64(self.0.raw(), &mut stat) })?;
+        Ok(FsStats { stat: stat })
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
+        let times = times.to_timespecs();
+        cvt(unsafe { libc::futimens(self.0.raw(), times.as_ptr()) })?;
+        Ok(())
+    }
+
+    // `futimens` is only available since macOS 10.13 and iOS 11.
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
+        weak!(fn futimens(c_int, *const libc::timespec) -> c_int);
+        if let Some(futimens) = futimens.get() {
+            let times = times.to_timespecs();
+            cvt(unsafe { futimens(self.0.raw(), times.as_ptr()) })?;
+        } else {
+            let times = times.to_timevals(&self.file_attr()?)?;
+            cvt(unsafe { libc::futimes(self.0.raw(), times.as_ptr()) })?;
+        }
+        Ok(())
+    }
 }
 
 impl DirBuilder {
@@ -558,6 +1052,190 @@ fn cstr(path: &Path) -> io::Result<CString> {
     Ok(CString::new(path.as_os_str().as_bytes())?)
 }
 
+// Functions taking an `off_t` instead of an `off64_t` only support 32-bit
+// offsets on some 32-bit platforms.
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios",
+          target_os = "freebsd", target_os = "solaris"))]
+fn to_off_t(n: u64) -> io::Result<libc::off_t> {
+    let off = n as libc::off_t;
+    if off < 0 || off as u64 != n {
+        return Err(Error::from_raw_os_error(libc::EFBIG))
+    }
+    Ok(off)
+}
+
+fn openat_dir(dirfd: c_int, name: &CStr, follow_symlinks: bool) -> io::Result<FileDesc> {
+    let mut flags = libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC;
+    if !follow_symlinks {
+        flags |= libc::O_NOFOLLOW;
+    }
+    let fd = cvt_r(|| unsafe { libc::openat(dirfd, name.as_ptr(), flags, 0) })?;
+    Ok(FileDesc::new(fd))
+}
+
+impl DirHandle {
+    pub fn open(path: &Path, follow_symlinks: bool) -> io::Result<DirHandle> {
+        let fd = openat_dir(libc::AT_FDCWD, &cstr(path)?, true)?;
+        Ok(DirHandle {
+            fd: fd,
+            path: path.to_path_buf(),
+            follow_symlinks: follow_symlinks,
+        })
+    }
+
+    // Calls `f` with a directory descriptor and a name which together refer
+    // to `path`, relative to this directory.
+    //
+    // If symlinks may be followed the whole path is left to the kernel to
+    // resolve. Otherwise it is walked one component at a time without
+    // following symlinks or `..`, so that it can't lead out of the directory.
+    fn at<T, F>(&self, path: &Path, f: F) -> io::Result<T>
+        where F: FnOnce(c_int, &CStr) -> io::Result<T>
+    {
+        if self.follow_symlinks {
+            return f(self.fd.raw(), &cstr(path)?)
+        }
+
+        let mut parent: Option<FileDesc> = None;
+        let mut name = None;
+        for component in path.components() {
+            match component {
+                Component::Normal(next) => {
+                    if let Some(dir) = mem::replace(&mut name, Some(next)) {
+                        let dirfd = parent.as_ref().unwrap_or(&self.fd).raw();
+                        parent = Some(openat_dir(dirfd, &cstr(Path::new(dir))?, false)?);
+                    }
+                }
+                Component::CurDir => {}
+                Component::Prefix(..) | Component::RootDir | Component::ParentDir => {
+                    return Err(io::Error::new(ErrorKind::PermissionDenied,
+                                              "path leads outside of the directory"))
+                }
+            }
+        }
+        let dirfd = parent.as_ref().unwrap_or(&self.fd).raw();
+        f(dirfd, &cstr(Path::new(name.unwrap_or(OsStr::new("."))))?)
+    }
+
+    pub fn open_file(&self, path: &Path, opts: &OpenOptions) -> io::Result<File> {
+        let mut flags = libc::O_CLOEXEC |
+                        opts.get_access_mode()? |
+                        opts.get_creation_mode()? |
+                        (opts.custom_flags as c_int & !libc::O_ACCMODE);
+        if !self.follow_symlinks {
+            flags |= libc::O_NOFOLLOW;
+        }
+        self.at(path, |dirfd, name| {
+            let fd = cvt_r(|| unsafe {
+                libc::openat(dirfd, name.as_ptr(), flags, opts.mode as c_int)
+            })?;
+            Ok(File(FileDesc::new(fd)))
+        })
+    }
+
+    pub fn open_dir(&self, path: &Path) -> io::Result<DirHandle> {
+        let fd = self.at(path, |dirfd, name| openat_dir(dirfd, name, self.follow_symlinks))?;
+        Ok(DirHandle {
+            fd: fd,
+            path: self.path.join(path),
+            follow_symlinks: self.follow_symlinks,
+        })
+    }
+
+    pub fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.at(path, |dirfd, name| {
+            cvt(unsafe { libc::mkdirat(dirfd, name.as_ptr(), 0o777) })?;
+            Ok(())
+        })
+    }
+
+    pub fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.at(path, |dirfd, name| {
+            cvt(unsafe { libc::unlinkat(dirfd, name.as_ptr(), 0) })?;
+            Ok(())
+        })
+    }
+
+    pub fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        self.at(path, |dirfd, name| {
+            cvt(unsafe { libc::unlinkat(dirfd, name.as_ptr(), libc::AT_REMOVEDIR) })?;
+            Ok(())
+        })
+    }
+
+    pub fn rename(&self, from: &Path, to_dir: &DirHandle, to: &Path) -> io::Result<()> {
+        self.at(from, |old_dirfd, old| {
+            to_dir.at(to, |new_dirfd, new| {
+                cvt(unsafe {
+                    libc::renameat(old_dirfd, old.as_ptr(), new_dirfd, new.as_ptr())
+                })?;
+                Ok(())
+            })
+        })
+    }
+
+    pub fn symlink(&self, original: &Path, link: &Path) -> io::Result<()> {
+        let original = cstr(original)?;
+        self.at(link, |dirfd, name| {
+            cvt(unsafe { libc::symlinkat(original.as_ptr(), dirfd, name.as_ptr()) })?;
+            Ok(())
+        })
+    }
+
+    pub fn metadata(&self, path: &Path) -> io::Result<FileAttr> {
+        let flags = if self.follow_symlinks { 0 } else { libc::AT_SYMLINK_NOFOLLOW };
+        self.stat_at(path, flags)
+    }
+
+    pub fn symlink_metadata(&self, path: &Path) -> io::Result<FileAttr> {
+        self.stat_at(path, libc::AT_SYMLINK_NOFOLLOW)
+    }
+
+    fn stat_at(&self, path: &Path, flags: c_int) -> io::Result<FileAttr> {
+        self.at(path, |dirfd, name| {
+            #[cfg(target_os = "linux")]
+            {
+                if let Some(ret) = try_statx(dirfd, name.as_ptr(), flags) {
+                    return ret
+                }
+            }
+
+            let mut stat: stat64 = unsafe { mem::zeroed() };
+            cvt(unsafe {
+                fstatat64(dirfd, name.as_ptr(), &mut stat as *mut _ as *mut _, flags)
+            })?;
+            Ok(FileAttr::from_stat64(stat))
+        })
+    }
+
+    pub fn read_dir(&self, path: &Path) -> io::Result<ReadDir> {
+        let fd = self.at(path, |dirfd, name| openat_dir(dirfd, name, self.follow_symlinks))?;
+        let root = Arc::new(self.path.join(path));
+        unsafe {
+            let ptr = libc::fdopendir(fd.raw());
+            if ptr.is_null() {
+                Err(Error::last_os_error())
+            } else {
+                // The directory stream owns the descriptor now.
+                fd.into_raw();
+                Ok(ReadDir { dirp: Dir(ptr), root: root })
+            }
+        }
+    }
+
+    pub fn fd(&self) -> &FileDesc { &self.fd }
+}
+
+impl fmt::Debug for DirHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Dir")
+            .field("path", &self.path)
+            .field("fd", &self.fd.raw())
+            .finish()
+    }
+}
+
 impl FromInner<c_int> for File {
     fn from_inner(fd: c_int) -> File {
         File(FileDesc::new(fd))
@@ -656,12 +1334,186 @@ pub fn rename(old: &Path, new: &Path) -> io::Result<()> {
     Ok(())
 }
 
+pub fn rename_noreplace(old: &Path, new: &Path) -> io::Result<()> {
+    rename_flags(old, new, RenameFlag::NoReplace)
+}
+
+pub fn rename_exchange(old: &Path, new: &Path) -> io::Result<()> {
+    rename_flags(old, new, RenameFlag::Exchange)
+}
+
+enum RenameFlag {
+    NoReplace,
+    Exchange,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn rename_flags(old: &Path, new: &Path, flag: RenameFlag) -> io::Result<()> {
+    // From `linux/fs.h`, the flags are only exposed by glibc since 2.28.
+    const RENAME_NOREPLACE: libc::c_uint = 1 << 0;
+    const RENAME_EXCHANGE: libc::c_uint = 1 << 1;
+
+    let old = cstr(old)?;
+    let new = cstr(new)?;
+    let flags = match flag {
+        RenameFlag::NoReplace => RENAME_NOREPLACE,
+        RenameFlag::Exchange => RENAME_EXCHANGE,
+    };
+    // `renameat2` was added in Linux 3.15, older kernels fail with `ENOSYS`
+    // and filesystems which don't support the flag with `EINVAL`.
+    cvt(unsafe {
+        libc::syscall(libc::SYS_renameat2, libc::AT_FDCWD, old.as_ptr(),
+                      libc::AT_FDCWD, new.as_ptr(), flags)
+    })?;
+    Ok(())
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn rename_flags(old: &Path, new: &Path, flag: RenameFlag) -> io::Result<()> {
+    // From `sys/stdio.h`.
+    const RENAME_SWAP: libc::c_uint = 0x00000002;
+    const RENAME_EXCL: libc::c_uint = 0x00000004;
+
+    // `renamex_np` is only available since macOS 10.12 and iOS 10.
+    weak!(fn renamex_np(*const libc::c_char, *const libc::c_char, libc::c_uint) -> c_int);
+    let renamex_np = match renamex_np.get() {
+        Some(f) => f,
+        None => return Err(Error::from_raw_os_error(libc::ENOSYS)),
+    };
+
+    let old = cstr(old)?;
+    let new = cstr(new)?;
+    let flags = match flag {
+        RenameFlag::NoReplace => RENAME_EXCL,
+        RenameFlag::Exchange => RENAME_SWAP,
+    };
+    cvt(unsafe { renamex_np(old.as_ptr(), new.as_ptr(), flags) })?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios")))]
+fn rename_flags(_old: &Path, _new: &Path, _flag: RenameFlag) -> io::Result<()> {
+    Err(Error::new(ErrorKind::Unsupported,
+                   "atomic rename variants are not supported on this platform"))
+}
+
 pub fn set_perm(p: &Path, perm: FilePermissions) -> io::Result<()> {
     let p = cstr(p)?;
     cvt_r(|| unsafe { libc::chmod(p.as_ptr(), perm.mode) })?;
     Ok(())
 }
 
+// The `chown` family of functions leaves ids of -1 unchanged.
+fn owner_ids(uid: Option<u32>, gid: Option<u32>) -> (libc::uid_t, libc::gid_t) {
+    (uid.map_or(!0, |uid| uid as libc::uid_t), gid.map_or(!0, |gid| gid as libc::gid_t))
+}
+
+pub fn chown(p: &Path, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+    let p = cstr(p)?;
+    let (uid, gid) = owner_ids(uid, gid);
+    cvt_r(|| unsafe { libc::chown(p.as_ptr(), uid, gid) })?;
+    Ok(())
+}
+
+pub fn lchown(p: &Path, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+    let p = cstr(p)?;
+    let (uid, gid) = owner_ids(uid, gid);
+    cvt_r(|| unsafe { libc::lchown(p.as_ptr(), uid, gid) })?;
+    Ok(())
+}
+
+pub fn chroot(p: &Path) -> io::Result<()> {
+    let p = cstr(p)?;
+    cvt(unsafe { libc::chroot(p.as_ptr()) })?;
+    Ok(())
+}
+
+pub fn mkfifo(p: &Path, mode: u32) -> io::Result<()> {
+    let p = cstr(p)?;
+    cvt(unsafe { libc::mkfifo(p.as_ptr(), mode as mode_t) })?;
+    Ok(())
+}
+
+pub fn mknod(p: &Path, mode: u32, dev: u64) -> io::Result<()> {
+    let p = cstr(p)?;
+    cvt(unsafe { libc::mknod(p.as_ptr(), mode as mode_t, dev as libc::dev_t) })?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+pub fn set_times(p: &Path, times: FileTimes) -> io::Result<()> {
+    let p = cstr(p)?;
+    let times = times.to_timespecs();
+    cvt(unsafe { libc::utimensat(libc::AT_FDCWD, p.as_ptr(), times.as_ptr(), 0) })?;
+    Ok(())
+}
+
+// `utimensat` is only available since macOS 10.13 and iOS 11.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn set_times(p: &Path, times: FileTimes) -> io::Result<()> {
+    weak!(fn utimensat(c_int, *const libc::c_char, *const libc::timespec, c_int) -> c_int);
+    let c_path = cstr(p)?;
+    if let Some(utimensat) = utimensat.get() {
+        let times = times.to_timespecs();
+        cvt(unsafe { utimensat(libc::AT_FDCWD, c_path.as_ptr(), times.as_ptr(), 0) })?;
+    } else {
+        let times = times.to_timevals(&stat(p)?)?;
+        cvt(unsafe { libc::utimes(c_path.as_ptr(), times.as_ptr()) })?;
+    }
+    Ok(())
+}
+
+// Temporary files and directories are private to their owner.
+pub fn temp_file_options(opts: &mut OpenOptions, _delete_on_close: bool) {
+    opts.mode(0o600);
+}
+
+// Files replacing others through a rename get the usual permissions, which
+// are then adjusted to the ones of the replaced file.
+pub fn replacement_file_options(_opts: &mut OpenOptions) {}
+
+pub fn temp_dir_builder(builder: &mut DirBuilder) {
+    builder.set_mode(0o700);
+}
+
+// Opens an unnamed file in `dir` with `O_TMPFILE`, returning `None` if that
+// isn't supported by the kernel or the filesystem.
+#[cfg(target_os = "linux")]
+pub fn open_tmpfile(dir: &Path) -> io::Result<Option<File>> {
+    let dir = cstr(dir)?;
+    let flags = libc::O_TMPFILE | libc::O_RDWR | libc::O_CLOEXEC;
+    match cvt_r(|| unsafe { open64(dir.as_ptr(), flags, 0o600) }) {
+        Ok(fd) => Ok(Some(File(FileDesc::new(fd)))),
+        // Kernels before 3.11 ignore `O_TMPFILE` and try to open the directory
+        // itself for writing.
+        Err(ref e) if e.raw_os_error() == Some(libc::EISDIR) ||
+                      e.raw_os_error() == Some(libc::EOPNOTSUPP) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn open_tmpfile(_dir: &Path) -> io::Result<Option<File>> {
+    Ok(None)
+}
+
+// Flushes the entries of the directory `p`, so that a file renamed into it
+// is still there after a crash.
+pub fn sync_dir(p: &Path) -> io::Result<()> {
+    let p = cstr(p)?;
+    let dir = openat_dir(libc::AT_FDCWD, &p, true)?;
+    cvt_r(|| unsafe { os_fsync(dir.raw()) })?;
+    return Ok(());
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    unsafe fn os_fsync(fd: c_int) -> c_int {
+        libc::fcntl(fd, libc::F_FULLFSYNC)
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    unsafe fn os_fsync(fd: c_int) -> c_int { libc::fsync(fd) }
+}
+
 pub fn rmdir(p: &Path) -> io::Result<()> {
     let p = cstr(p)?;
     cvt(unsafe { libc::rmdir(p.as_ptr()) })?;
@@ -673,20 +1525,62 @@ pub fn remove_dir_all(path: &Path) -> io::Result<()> {
     if filetype.is_symlink() {
         unlink(path)
     } else {
-        remove_dir_all_recursive(path)
+        remove_dir_all_recursive(libc::AT_FDCWD, &cstr(path)?, true)
     }
 }
 
-fn remove_dir_all_recursive(path: &Path) -> io::Result<()> {
-    for child in readdir(path)? {
+// Removes the directory `name` in `parent_fd` along with its contents.
+//
+// Everything below the top level directory is accessed relative to the
+// descriptor of its parent directory, and directories are opened without
+// following symlinks. That way replacing any part of the tree with a symlink
+// while it's being removed can't lead to anything outside of the tree being
+// removed instead: the symlink itself is unlinked, or the removal fails.
+pub fn remove_dir_all_recursive(parent_fd: c_int, name: &CStr, top_level: bool)
+                                -> io::Result<()> {
+    let fd = match openat_dir(parent_fd, name, false) {
+        // Not (or no longer) a directory, remove whatever it is instead.
+        // The top level path was checked to be a directory by our caller,
+        // so if it changed since there's nothing sensible left to do.
+        Err(ref e) if !top_level && is_not_dir_error(e) => {
+            cvt(unsafe { libc::unlinkat(parent_fd, name.as_ptr(), 0) })?;
+            return Ok(())
+        }
+        result => result?,
+    };
+
+    let dirp = unsafe { libc::fdopendir(fd.raw()) };
+    if dirp.is_null() {
+        return Err(Error::last_os_error())
+    }
+    let fd = fd.into_raw();
+    let entries = ReadDir { dirp: Dir(dirp), root: Arc::new(PathBuf::new()) };
+    for child in entries {
         let child = child?;
-        if child.file_type()?.is_dir() {
-            remove_dir_all_recursive(&child.path())?;
-        } else {
-            unlink(&child.path())?;
+        let child_name = CString::new(child.name_bytes())?;
+        match child.is_dir_hint() {
+            Some(false) => {
+                cvt(unsafe { libc::unlinkat(fd, child_name.as_ptr(), 0) })?;
+            }
+            // Opening the child is what actually decides whether it is a
+            // directory, so it's fine if the hint is outdated by now.
+            Some(true) | None => remove_dir_all_recursive(fd, &child_name, false)?,
         }
     }
-    rmdir(path)
+
+    cvt(unsafe { libc::unlinkat(parent_fd, name.as_ptr(), libc::AT_REMOVEDIR) })?;
+    Ok(())
+}
+
+// The error returned by `open` with `O_DIRECTORY | O_NOFOLLOW` when the path
+// isn't a directory, or is a symlink.
+fn is_not_dir_error(e: &io::Error) -> bool {
+    match e.raw_os_error() {
+        Some(libc::ENOTDIR) | Some(libc::ELOOP) => true,
+        #[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
+        Some(libc::EMLINK) => true,
+        _ => false,
+    }
 }
 
 pub fn readlink(p: &Path) -> io::Result<PathBuf> {
@@ -731,20 +1625,81 @@ pub fn link(src: &Path, dst: &Path) -> io::Result<()> {
 
 pub fn stat(p: &Path) -> io::Result<FileAttr> {
     let p = cstr(p)?;
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(ret) = try_statx(libc::AT_FDCWD, p.as_ptr(), 0) {
+            return ret
+        }
+    }
+
     let mut stat: stat64 = unsafe { mem::zeroed() };
     cvt(unsafe {
         stat64(p.as_ptr(), &mut stat as *mut _ as *mut _)
     })?;
-    Ok(FileAttr { stat: stat })
+    Ok(FileAttr::from_stat64(stat))
 }
 
 pub fn lstat(p: &Path) -> io::Result<FileAttr> {
     let p = cstr(p)?;
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(ret) = try_statx(libc::AT_FDCWD, p.as_ptr(), libc::AT_SYMLINK_NOFOLLOW) {
+            return ret
+        }
+    }
+
     let mut stat: stat64 = unsafe { mem::zeroed() };
     cvt(unsafe {
         lstat64(p.as_ptr(), &mut stat as *mut _ as *mut _)
     })?;
-    Ok(FileAttr { stat: stat })
+    Ok(FileAttr::from_stat64(stat))
+}
+
+pub fn fs_stats(p: &Path) -> io::Result<FsStats> {
+    let p = cstr(p)?;
+    let mut stat: statvfs64 = unsafe { mem::zeroed() };
+    cvt_r(|| unsafe { statvfs64(p.as_ptr(), &mut stat) })?;
+    Ok(FsStats { stat: stat })
+}
+
+impl FsStats {
+    // Block counts are in units of the fragment size, which some systems
+    // leave at zero.
+    fn fragment_size(&self) -> u64 {
+        match self.stat.f_frsize as u64 {
+            0 => self.stat.f_bsize as u64,
+            n => n,
+        }
+    }
+
+    pub fn total_space(&self) -> u64 {
+        (self.stat.f_blocks as u64).saturating_mul(self.fragment_size())
+    }
+
+    pub fn free_space(&self) -> u64 {
+        (self.stat.f_bfree as u64).saturating_mul(self.fragment_size())
+    }
+
+    pub fn available_space(&self) -> u64 {
+        (self.stat.f_bavail as u64).saturating_mul(self.fragment_size())
+    }
+
+    pub fn total_inodes(&self) -> u64 { self.stat.f_files as u64 }
+    pub fn free_inodes(&self) -> u64 { self.stat.f_ffree as u64 }
+    pub fn available_inodes(&self) -> u64 { self.stat.f_favail as u64 }
+    pub fn block_size(&self) -> u64 { self.stat.f_bsize as u64 }
+    pub fn fs_id(&self) -> u64 { self.stat.f_fsid as u64 }
+    pub fn max_name_len(&self) -> u64 { self.stat.f_namemax as u64 }
+
+    pub fn is_read_only(&self) -> bool {
+        self.stat.f_flag & libc::ST_RDONLY as libc::c_ulong != 0
+    }
+
+    pub fn is_nosuid(&self) -> bool {
+        self.stat.f_flag & libc::ST_NOSUID as libc::c_ulong != 0
+    }
 }
 
 pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
@@ -761,6 +1716,174 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
     Ok(PathBuf::from(OsString::from_vec(buf)))
 }
 
+pub fn copy_with(from: &Path, to: &Path, opts: &CopyOptions) -> io::Result<u64> {
+    use fs::{File, OpenOptions};
+    use os::unix::fs::OpenOptionsExt;
+
+    let unsupported = || Err(Error::from_raw_os_error(libc::EOPNOTSUPP));
+    if opts.reflink == Reflink::Always && !cfg!(any(target_os = "linux",
+                                                    target_os = "android")) {
+        return unsupported()
+    }
+    if opts.preserve_xattrs && !cfg!(any(target_os = "linux", target_os = "android",
+                                         target_os = "macos", target_os = "ios")) {
+        return unsupported()
+    }
+
+    if opts.copy_symlinks {
+        let attr = lstat(from)?;
+        if attr.file_type().is_symlink() {
+            return copy_symlink(from, to, &attr, opts).map(|()| 0)
+        }
+    }
+
+    let mut reader = File::open(from)?;
+    let attr = reader.as_inner().file_attr()?;
+    if !attr.file_type().is_file() {
+        return Err(Error::new(ErrorKind::InvalidInput,
+                              "the source path is not an existing regular file"))
+    }
+
+    let mut wopts = OpenOptions::new();
+    wopts.write(true).mode(attr.perm().mode as u32);
+    if opts.overwrite {
+        wopts.create(true).truncate(true);
+    } else {
+        wopts.create_new(true);
+    }
+    let mut writer = wopts.open(to)?;
+    let (rfd, wfd) = (reader.as_raw_fd(), writer.as_raw_fd());
+
+    let copied = match opts.reflink {
+        Reflink::Never => io::copy(&mut reader, &mut writer)?,
+        Reflink::Auto => match reflink(rfd, wfd) {
+            Ok(()) => attr.size(),
+            Err(_) => io::copy(&mut reader, &mut writer)?,
+        },
+        Reflink::Always => {
+            reflink(rfd, wfd)?;
+            attr.size()
+        }
+    };
+
+    // Ownership goes first as changing it may clear the setuid and setgid
+    // bits, and the timestamps last since all other changes touch them.
+    if opts.preserve_ownership {
+        cvt_r(|| unsafe { libc::fchown(wfd, attr.stat.st_uid, attr.stat.st_gid) })?;
+    }
+    writer.as_inner().set_permissions(attr.perm())?;
+    if opts.preserve_xattrs {
+        copy_xattrs(rfd, wfd)?;
+    }
+    if opts.preserve_timestamps {
+        writer.as_inner().set_times(FileTimes {
+            accessed: Some(attr.accessed()?),
+            modified: Some(attr.modified()?),
+        })?;
+    }
+    Ok(copied)
+}
+
+// Extended attributes can't be set on symbolic links on most systems, so
+// they aren't copied here.
+fn copy_symlink(from: &Path, to: &Path, attr: &FileAttr, opts: &CopyOptions)
+                -> io::Result<()> {
+    let target = readlink(from)?;
+    match symlink(&target, to) {
+        Err(ref e) if e.kind() == ErrorKind::AlreadyExists && opts.overwrite => {
+            unlink(to)?;
+            symlink(&target, to)?;
+        }
+        result => result?,
+    }
+
+    let to = cstr(to)?;
+    if opts.preserve_ownership {
+        cvt_r(|| unsafe { libc::lchown(to.as_ptr(), attr.stat.st_uid, attr.stat.st_gid) })?;
+    }
+    if opts.preserve_timestamps {
+        let times = FileTimes {
+            accessed: Some(attr.accessed()?),
+            modified: Some(attr.modified()?),
+        };
+        set_symlink_times(&to, times, attr)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+fn set_symlink_times(p: &CStr, times: FileTimes, _attr: &FileAttr) -> io::Result<()> {
+    let times = times.to_timespecs();
+    cvt(unsafe {
+        libc::utimensat(libc::AT_FDCWD, p.as_ptr(), times.as_ptr(), libc::AT_SYMLINK_NOFOLLOW)
+    })?;
+    Ok(())
+}
+
+// As for `set_times`, releases without `utimensat` only have `lutimes`.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn set_symlink_times(p: &CStr, times: FileTimes, attr: &FileAttr) -> io::Result<()> {
+    weak!(fn utimensat(c_int, *const libc::c_char, *const libc::timespec, c_int) -> c_int);
+    if let Some(utimensat) = utimensat.get() {
+        let times = times.to_timespecs();
+        cvt(unsafe {
+            utimensat(libc::AT_FDCWD, p.as_ptr(), times.as_ptr(), libc::AT_SYMLINK_NOFOLLOW)
+        })?;
+    } else {
+        let times = times.to_timevals(attr)?;
+        cvt(unsafe { libc::lutimes(p.as_ptr(), times.as_ptr()) })?;
+    }
+    Ok(())
+}
+
+// Makes `writer` share the data of `reader` through the `FICLONE` ioctl, which
+// is supported by filesystems with copy-on-write extents such as Btrfs or XFS.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn reflink(reader: RawFd, writer: RawFd) -> io::Result<()> {
+    // `_IOW(0x94, 9, int)`, the direction bits are placed differently on a
+    // few architectures.
+    #[cfg(any(target_arch = "mips", target_arch = "mips64",
+              target_arch = "powerpc", target_arch = "powerpc64",
+              target_arch = "sparc64"))]
+    const FICLONE: u32 = 0x80049409;
+    #[cfg(not(any(target_arch = "mips", target_arch = "mips64",
+                  target_arch = "powerpc", target_arch = "powerpc64",
+                  target_arch = "sparc64")))]
+    const FICLONE: u32 = 0x40049409;
+
+    cvt(unsafe { libc::ioctl(writer, FICLONE as _, reader) })?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn reflink(_reader: RawFd, _writer: RawFd) -> io::Result<()> {
+    Err(Error::from_raw_os_error(libc::EOPNOTSUPP))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios"))]
+fn copy_xattrs(reader: RawFd, writer: RawFd) -> io::Result<()> {
+    use sys::xattr::{self, Target};
+
+    let names = match xattr::list(Target::Fd(reader)) {
+        Ok(names) => names,
+        // The source lives on a filesystem without extended attributes.
+        Err(ref e) if e.raw_os_error() == Some(libc::ENOTSUP) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for name in names {
+        let value = xattr::get(Target::Fd(reader), &name)?;
+        xattr::set(Target::Fd(writer), &name, &value)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios")))]
+fn copy_xattrs(_reader: RawFd, _writer: RawFd) -> io::Result<()> {
+    Err(Error::from_raw_os_error(libc::EOPNOTSUPP))
+}
+
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     use fs::{File, set_permissions};
     if !from.is_file() {
@@ -776,3 +1899,102 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     set_permissions(to, perm)?;
     Ok(ret)
 }
+
+pub struct Mmap {
+    // Mappings have to start at a page boundary, so the mapped region may
+    // start before the requested data at `ptr`.
+    base: *mut libc::c_void,
+    map_len: usize,
+    ptr: *mut u8,
+    len: usize,
+}
+
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    pub fn new(file: &File, mode: MmapMode, offset: u64, len: usize) -> io::Result<Mmap> {
+        // `mmap` rejects empty mappings, which are then never accessed
+        // anyway.
+        if len == 0 {
+            return Ok(Mmap {
+                base: ptr::null_mut(),
+                map_len: 0,
+                ptr: ptr::NonNull::dangling().as_ptr(),
+                len: 0,
+            })
+        }
+
+        let align = (offset % os::page_size() as u64) as usize;
+        let map_len = len.checked_add(align).ok_or_else(|| {
+            Error::new(ErrorKind::InvalidInput, "mapping length overflows")
+        })?;
+        let (prot, flags) = match mode {
+            MmapMode::ReadOnly => (libc::PROT_READ, libc::MAP_SHARED),
+            MmapMode::Shared => (libc::PROT_READ | libc::PROT_WRITE, libc::MAP_SHARED),
+            MmapMode::Private => (libc::PROT_READ | libc::PROT_WRITE, libc::MAP_PRIVATE),
+        };
+        let base = unsafe { mmap_at(map_len, prot, flags, file.0.raw(), offset - align as u64) };
+        if base == libc::MAP_FAILED {
+            return Err(Error::last_os_error())
+        }
+        Ok(Mmap {
+            base: base,
+            map_len: map_len,
+            ptr: unsafe { (base as *mut u8).offset(align as isize) },
+            len: len,
+        })
+    }
+
+    pub fn ptr(&self) -> *mut u8 { self.ptr }
+
+    pub fn len(&self) -> usize { self.len }
+
+    pub fn flush(&self, offset: usize, len: usize) -> io::Result<()> {
+        if len == 0 {
+            return Ok(())
+        }
+        // `msync` wants a page aligned address as well.
+        let start = self.ptr as usize + offset;
+        let align = start % os::page_size();
+        cvt(unsafe {
+            libc::msync((start - align) as *mut libc::c_void, len + align, libc::MS_SYNC)
+        })?;
+        Ok(())
+    }
+
+    pub fn advise(&self, advice: Advice) -> io::Result<()> {
+        if self.map_len == 0 {
+            return Ok(())
+        }
+        let advice = match advice {
+            Advice::Normal => libc::MADV_NORMAL,
+            Advice::Random => libc::MADV_RANDOM,
+            Advice::Sequential => libc::MADV_SEQUENTIAL,
+            Advice::WillNeed => libc::MADV_WILLNEED,
+            Advice::DontNeed => libc::MADV_DONTNEED,
+        };
+        cvt(unsafe { libc::madvise(self.base, self.map_len, advice) })?;
+        Ok(())
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.map_len != 0 {
+            unsafe { libc::munmap(self.base, self.map_len); }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+unsafe fn mmap_at(len: usize, prot: c_int, flags: c_int, fd: c_int, offset: u64)
+                  -> *mut libc::c_void {
+    libc::mmap64(ptr::null_mut(), len, prot, flags, fd, offset as libc::off64_t)
+}
+
+#[cfg(not(target_os = "linux"))]
+unsafe fn mmap_at(len: usize, prot: c_int, flags: c_int, fd: c_int, offset: u64)
+                  -> *mut libc::c_void {
+    libc::mmap(ptr::null_mut(), len, prot, flags, fd, offset as libc::off_t)
+}
@@ -0,0 +1,368 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Filesystem change notification through inotify.
+//!
+//! inotify only watches single directories, so recursive watches add a watch
+//! for every directory below the root, including ones created later on. The
+//! paths of these watches are kept up to date when directories are renamed.
+
+use cmp;
+use collections::{HashMap, VecDeque};
+use ffi::{CString, OsStr};
+use fs;
+use io;
+use libc::{self, c_int};
+use mem;
+use os::linux::fs::EventKind;
+use os::unix::prelude::*;
+use path::{Path, PathBuf};
+use ptr;
+use sys::cvt;
+use sys::fd::FileDesc;
+use time::{Duration, Instant};
+
+// Values from `sys/inotify.h`.
+const IN_MODIFY: u32 = 0x0000_0002;
+const IN_ATTRIB: u32 = 0x0000_0004;
+const IN_MOVED_FROM: u32 = 0x0000_0040;
+const IN_MOVED_TO: u32 = 0x0000_0080;
+const IN_CREATE: u32 = 0x0000_0100;
+const IN_DELETE: u32 = 0x0000_0200;
+const IN_DELETE_SELF: u32 = 0x0000_0400;
+const IN_Q_OVERFLOW: u32 = 0x0000_4000;
+const IN_IGNORED: u32 = 0x0000_8000;
+const IN_ISDIR: u32 = 0x4000_0000;
+
+const WATCH_MASK: u32 = IN_MODIFY | IN_ATTRIB | IN_MOVED_FROM | IN_MOVED_TO | IN_CREATE |
+                        IN_DELETE | IN_DELETE_SELF;
+
+// The fixed size part of the events read from an inotify descriptor, which is
+// followed by a NUL padded name of `len` bytes.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct inotify_event {
+    wd: c_int,
+    mask: u32,
+    cookie: u32,
+    len: u32,
+}
+
+#[derive(Debug)]
+pub struct Event {
+    pub kind: EventKind,
+    pub path: Option<PathBuf>,
+    pub from: Option<PathBuf>,
+    pub cookie: u32,
+    pub is_dir: bool,
+}
+
+struct Watch {
+    path: PathBuf,
+    recursive: bool,
+    // Whether the watch was added by the user, rather than for a directory
+    // below a recursively watched one.
+    root: bool,
+}
+
+pub struct Watcher {
+    fd: FileDesc,
+    watches: HashMap<c_int, Watch>,
+    paths: HashMap<PathBuf, c_int>,
+    buf: Vec<u8>,
+    queue: VecDeque<Event>,
+}
+
+impl Watcher {
+    pub fn new() -> io::Result<Watcher> {
+        let fd = cvt(unsafe { libc::inotify_init1(libc::O_CLOEXEC) })?;
+        Ok(Watcher {
+            fd: FileDesc::new(fd),
+            watches: HashMap::new(),
+            paths: HashMap::new(),
+            // Large enough for dozens of events with names of the maximal
+            // length.
+            buf: vec![0; 16 * 1024],
+            queue: VecDeque::new(),
+        })
+    }
+
+    pub fn fd(&self) -> &FileDesc { &self.fd }
+
+    pub fn watch(&mut self, path: &Path, recursive: bool) -> io::Result<()> {
+        self.add_watch(path, recursive, true, false)
+    }
+
+    pub fn unwatch(&mut self, path: &Path) -> io::Result<()> {
+        let wd = match self.paths.get(path) {
+            Some(&wd) => wd,
+            None => {
+                return Err(io::Error::new(io::ErrorKind::NotFound, "path is not being watched"))
+            }
+        };
+        let mut wds = vec![wd];
+        if self.watches[&wd].recursive {
+            wds.extend(self.watches.iter()
+                           .filter(|&(_, watch)| !watch.root && watch.path.starts_with(path))
+                           .map(|(&wd, _)| wd));
+        }
+        let mut result = Ok(());
+        for wd in wds {
+            if let Some(watch) = self.watches.remove(&wd) {
+                self.paths.remove(&watch.path);
+            }
+            match cvt(unsafe { libc::inotify_rm_watch(self.fd.raw(), wd) }) {
+                Ok(_) => {}
+                // The kernel already removed the watch as its directory was
+                // deleted, and the `IN_IGNORED` event is still queued.
+                Err(ref e) if e.raw_os_error() == Some(libc::EINVAL) => {}
+                Err(e) => {
+                    if result.is_ok() {
+                        result = Err(e);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    pub fn recv(&mut self) -> io::Result<Event> {
+        loop {
+            if let Some(event) = self.queue.pop_front() {
+                return Ok(event)
+            }
+            self.read_events()?;
+        }
+    }
+
+    pub fn recv_timeout(&mut self, timeout: Duration) -> io::Result<Option<Event>> {
+        let start = Instant::now();
+        loop {
+            if let Some(event) = self.queue.pop_front() {
+                return Ok(Some(event))
+            }
+            let elapsed = start.elapsed();
+            let remaining = if elapsed < timeout { timeout - elapsed } else { Duration::new(0, 0) };
+            if !self.poll(remaining)? {
+                return Ok(None)
+            }
+            self.read_events()?;
+        }
+    }
+
+    // Waits until events can be read, returning `false` on timeout.
+    fn poll(&self, timeout: Duration) -> io::Result<bool> {
+        let mut pollfd = libc::pollfd {
+            fd: self.fd.raw(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // Round up, so that a short timeout doesn't become a busy loop.
+        let millis = timeout.as_secs()
+            .saturating_mul(1_000)
+            .saturating_add((timeout.subsec_nanos() as u64 + 999_999) / 1_000_000);
+        let millis = cmp::min(millis, c_int::max_value() as u64) as c_int;
+        loop {
+            match unsafe { libc::poll(&mut pollfd, 1, millis) } {
+                -1 => {
+                    let err = io::Error::last_os_error();
+                    if err.kind() != io::ErrorKind::Interrupted {
+                        return Err(err)
+                    }
+                }
+                n => return Ok(n > 0),
+            }
+        }
+    }
+
+    fn read_events(&mut self) -> io::Result<()> {
+        let len = loop {
+            match self.fd.read(&mut self.buf) {
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                result => break result?,
+            }
+        };
+
+        let header = mem::size_of::<inotify_event>();
+        let mut unpaired_dirs = Vec::new();
+        let mut result = Ok(());
+        let mut pos = 0;
+        while pos + header <= len {
+            let raw = unsafe {
+                ptr::read_unaligned(self.buf[pos..].as_ptr() as *const inotify_event)
+            };
+            let name = {
+                let name = &self.buf[pos + header..pos + header + raw.len as usize];
+                let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+                OsStr::from_bytes(&name[..len]).to_os_string()
+            };
+            pos += header + raw.len as usize;
+
+            if let Err(e) = self.handle_event(&raw, Path::new(&name), &mut unpaired_dirs) {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+
+        // Directories moved out of recursively watched trees are no longer
+        // watched through them.
+        for (wd, path) in unpaired_dirs {
+            let moved_out = match self.watches.get(&wd) {
+                Some(watch) => !watch.root && watch.path == path,
+                None => false,
+            };
+            if moved_out {
+                let _ = self.unwatch(&path);
+            }
+        }
+        result
+    }
+
+    fn handle_event(&mut self, raw: &inotify_event, name: &Path,
+                    unpaired_dirs: &mut Vec<(c_int, PathBuf)>) -> io::Result<()> {
+        if raw.mask & IN_Q_OVERFLOW != 0 {
+            self.push(EventKind::Overflow, None, 0, false);
+            return Ok(())
+        }
+        if raw.mask & IN_IGNORED != 0 {
+            if let Some(watch) = self.watches.remove(&raw.wd) {
+                self.paths.remove(&watch.path);
+            }
+            return Ok(())
+        }
+        let (path, recursive, root) = match self.watches.get(&raw.wd) {
+            // Events about the watched file itself have no name, and joining
+            // an empty one would append a separator.
+            Some(watch) if name.as_os_str().is_empty() => {
+                (watch.path.clone(), watch.recursive, watch.root)
+            }
+            Some(watch) => (watch.path.join(name), watch.recursive, watch.root),
+            // The watch was removed and this event was already queued.
+            None => return Ok(()),
+        };
+        let is_dir = raw.mask & IN_ISDIR != 0;
+
+        if raw.mask & IN_CREATE != 0 {
+            self.push(EventKind::Create, Some(path.clone()), 0, is_dir);
+            if is_dir && recursive {
+                return self.add_subtree(&path)
+            }
+        } else if raw.mask & IN_MODIFY != 0 {
+            self.push(EventKind::Modify, Some(path), 0, is_dir);
+        } else if raw.mask & IN_ATTRIB != 0 {
+            self.push(EventKind::Attrib, Some(path), 0, is_dir);
+        } else if raw.mask & IN_DELETE != 0 {
+            self.push(EventKind::Delete, Some(path), 0, is_dir);
+        } else if raw.mask & IN_DELETE_SELF != 0 {
+            // Directories below the root were already reported by their
+            // parent.
+            if root {
+                self.push(EventKind::Delete, Some(path), 0, is_dir);
+            }
+        } else if raw.mask & IN_MOVED_FROM != 0 {
+            self.push(EventKind::MovedFrom, Some(path.clone()), raw.cookie, is_dir);
+            let wd = if is_dir { self.paths.get(&path).cloned() } else { None };
+            if let Some(wd) = wd {
+                unpaired_dirs.push((wd, path));
+            }
+        } else if raw.mask & IN_MOVED_TO != 0 {
+            // Both halves of a rename are queued together by the kernel.
+            let paired = match self.queue.back() {
+                Some(event) => event.kind == EventKind::MovedFrom && event.cookie == raw.cookie,
+                None => false,
+            };
+            if paired {
+                let mut event = self.queue.pop_back().unwrap();
+                let from = event.path.take().unwrap();
+                unpaired_dirs.retain(|&(_, ref dir)| *dir != from);
+                if is_dir {
+                    self.rename_watches(&from, &path);
+                }
+                event.kind = EventKind::Rename;
+                event.from = Some(from);
+                event.path = Some(path.clone());
+                self.queue.push_back(event);
+            } else {
+                self.push(EventKind::MovedTo, Some(path.clone()), raw.cookie, is_dir);
+            }
+            if is_dir && recursive && !self.paths.contains_key(&path) {
+                return self.add_subtree(&path)
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, kind: EventKind, path: Option<PathBuf>, cookie: u32, is_dir: bool) {
+        self.queue.push_back(Event {
+            kind: kind,
+            path: path,
+            from: None,
+            cookie: cookie,
+            is_dir: is_dir,
+        });
+    }
+
+    // Watches a directory which appeared below a recursively watched one.
+    // Entries created in it before the watch was added are reported as
+    // created as well.
+    fn add_subtree(&mut self, path: &Path) -> io::Result<()> {
+        match self.add_watch(path, true, false, true) {
+            // The directory is already gone again.
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    fn add_watch(&mut self, path: &Path, recursive: bool, root: bool, report: bool)
+                 -> io::Result<()> {
+        let c_path = CString::new(path.as_os_str().as_bytes())?;
+        let wd = cvt(unsafe {
+            libc::inotify_add_watch(self.fd.raw(), c_path.as_ptr(), WATCH_MASK)
+        })?;
+        // Watching the same file again returns the same descriptor.
+        if let Some(old) = self.watches.insert(wd, Watch {
+            path: path.to_path_buf(),
+            recursive: recursive,
+            root: root,
+        }) {
+            self.paths.remove(&old.path);
+        }
+        self.paths.insert(path.to_path_buf(), wd);
+
+        if !recursive || !fs::metadata(path)?.is_dir() {
+            return Ok(())
+        }
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            // Symbolic links to directories aren't followed.
+            let is_dir = entry.file_type()?.is_dir();
+            if report {
+                self.push(EventKind::Create, Some(entry.path()), 0, is_dir);
+            }
+            if is_dir {
+                self.add_watch(&entry.path(), true, false, report)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn rename_watches(&mut self, from: &Path, to: &Path) {
+        let moved: Vec<_> = self.paths.keys().filter(|p| p.starts_with(from)).cloned().collect();
+        for old in moved {
+            let wd = self.paths.remove(&old).unwrap();
+            let new = to.join(old.strip_prefix(from).unwrap());
+            if let Some(watch) = self.watches.get_mut(&wd) {
+                watch.path = new.clone();
+            }
+            self.paths.insert(new, wd);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! In-kernel copies for `io::copy` on Linux.
+//!
+//! When both ends of a copy are known to be backed by file descriptors the
+//! data doesn't have to make a round trip through userspace. Depending on
+//! what the descriptors refer to one of the following is used, in order of
+//! preference:
+//!
+//! * `copy_file_range` when copying from one regular file to another, which
+//!   lets the filesystem share extents or do a server-side copy.
+//! * `sendfile` when the source is a regular file, for any kind of sink.
+//! * `splice` when either end is a pipe.
+//!
+//! If none of these applies, or the kernel rejects the call before any data
+//! was transferred (old kernels, cross-filesystem copies, exotic file types,
+//! ...), the generic userspace loop is used instead.
+
+use fs::File;
+use io::{self, Read, Write};
+use libc::{self, c_int};
+use mem;
+use net::TcpStream;
+use os::unix::io::{AsRawFd, RawFd};
+use os::unix::net::UnixStream;
+use process::{ChildStdin, ChildStdout, ChildStderr};
+use ptr;
+use sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(target_os = "linux")]
+use libc::{fstat64, stat64};
+#[cfg(target_os = "android")]
+use libc::{fstat as fstat64, stat as stat64};
+
+// Each system call is asked to move at most this many bytes. This keeps the
+// length comfortably within the limits of `ssize_t` on 32-bit platforms and
+// of the kernel's own `MAX_RW_COUNT`.
+const MAX_CHUNK: usize = 0x4000_0000;
+
+pub fn copy_spec<R: Read + ?Sized, W: Write + ?Sized>(read: &mut R,
+                                                      write: &mut W)
+                                                      -> io::Result<u64> {
+    SpecCopy::copy(Copier { read: read, write: write })
+}
+
+struct Copier<'a, 'b, R: Read + ?Sized + 'a, W: Write + ?Sized + 'b> {
+    read: &'a mut R,
+    write: &'b mut W,
+}
+
+trait SpecCopy {
+    fn copy(self) -> io::Result<u64>;
+}
+
+impl<'a, 'b, R: Read + ?Sized, W: Write + ?Sized> SpecCopy for Copier<'a, 'b, R, W> {
+    default fn copy(self) -> io::Result<u64> {
+        io::generic_copy(self.read, self.write)
+    }
+}
+
+impl<'a, 'b, R: CopyRead + ?Sized, W: CopyWrite + ?Sized> SpecCopy for Copier<'a, 'b, R, W> {
+    fn copy(self) -> io::Result<u64> {
+        let reader = self.read.as_raw_fd();
+        let writer = self.write.as_raw_fd();
+        let src = FdMeta::of(reader);
+        let dst = FdMeta::of(writer);
+
+        let mut result = CopyResult::Fallback;
+        if src == FdMeta::Regular && dst == FdMeta::Regular {
+            result = copy_regular_files(reader, writer);
+        }
+        if let CopyResult::Fallback = result {
+            if src == FdMeta::Regular {
+                result = sendfile_splice(SpliceMode::Sendfile, reader, writer);
+            }
+        }
+        if let CopyResult::Fallback = result {
+            if src == FdMeta::Pipe || dst == FdMeta::Pipe {
+                result = sendfile_splice(SpliceMode::Splice, reader, writer);
+            }
+        }
+
+        match result {
+            CopyResult::Ended(n) => Ok(n),
+            CopyResult::Error(e) => Err(e),
+            CopyResult::Fallback => io::generic_copy(self.read, self.write),
+        }
+    }
+}
+
+/// Readers whose data can be moved by the kernel directly.
+trait CopyRead: Read {
+    fn as_raw_fd(&self) -> RawFd;
+}
+
+/// Writers whose data can be moved by the kernel directly.
+trait CopyWrite: Write {
+    fn as_raw_fd(&self) -> RawFd;
+}
+
+macro_rules! impl_copy_fd {
+    ($($t:ty),*) => ($(
+        impl CopyRead for $t {
+            fn as_raw_fd(&self) -> RawFd { AsRawFd::as_raw_fd(self) }
+        }
+        impl CopyWrite for $t {
+            fn as_raw_fd(&self) -> RawFd { AsRawFd::as_raw_fd(self) }
+        }
+        impl<'a> CopyRead for &'a $t {
+            fn as_raw_fd(&self) -> RawFd { AsRawFd::as_raw_fd(*self) }
+        }
+        impl<'a> CopyWrite for &'a $t {
+            fn as_raw_fd(&self) -> RawFd { AsRawFd::as_raw_fd(*self) }
+        }
+    )*)
+}
+
+impl_copy_fd! { File, TcpStream, UnixStream }
+
+impl CopyWrite for ChildStdin {
+    fn as_raw_fd(&self) -> RawFd { AsRawFd::as_raw_fd(self) }
+}
+
+impl CopyRead for ChildStdout {
+    fn as_raw_fd(&self) -> RawFd { AsRawFd::as_raw_fd(self) }
+}
+
+impl CopyRead for ChildStderr {
+    fn as_raw_fd(&self) -> RawFd { AsRawFd::as_raw_fd(self) }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum FdMeta {
+    Regular,
+    Pipe,
+    Socket,
+    Other,
+}
+
+impl FdMeta {
+    fn of(fd: RawFd) -> FdMeta {
+        let mut stat: stat64 = unsafe { mem::zeroed() };
+        if unsafe { fstat64(fd, &mut stat) } == -1 {
+            return FdMeta::Other
+        }
+        match stat.st_mode & libc::S_IFMT {
+            libc::S_IFREG => FdMeta::Regular,
+            libc::S_IFIFO => FdMeta::Pipe,
+            libc::S_IFSOCK => FdMeta::Socket,
+            _ => FdMeta::Other,
+        }
+    }
+}
+
+enum CopyResult {
+    /// The source reached EOF after the given number of bytes.
+    Ended(u64),
+    /// A hard error occurred, possibly after some data was copied.
+    Error(io::Error),
+    /// The kernel can't perform this copy and no data was moved, so a
+    /// different method has to be used.
+    Fallback,
+}
+
+// Errors which, when returned before anything was copied, mean that this
+// particular method is unavailable for the descriptors involved rather than
+// that the copy itself failed.
+fn should_fall_back(err: &io::Error) -> bool {
+    match err.raw_os_error() {
+        Some(libc::ENOSYS) |
+        Some(libc::EXDEV) |
+        Some(libc::EINVAL) |
+        Some(libc::EPERM) |
+        Some(libc::EOPNOTSUPP) |
+        Some(libc::EBADF) => true,
+        _ => false,
+    }
+}
+
+unsafe fn copy_file_range(fd_in: c_int,
+                          off_in: *mut libc::loff_t,
+                          fd_out: c_int,
+                          off_out: *mut libc::loff_t,
+                          len: libc::size_t,
+                          flags: libc::c_uint) -> libc::c_long {
+    libc::syscall(libc::SYS_copy_file_range, fd_in, off_in, fd_out, off_out, len, flags)
+}
+
+fn copy_regular_files(reader: RawFd, writer: RawFd) -> CopyResult {
+    // Kernels prior to 4.5 don't have `copy_file_range`; remember that to
+    // avoid a pointless system call on every copy.
+    static HAS_COPY_FILE_RANGE: AtomicBool = AtomicBool::new(true);
+
+    if !HAS_COPY_FILE_RANGE.load(Ordering::Relaxed) {
+        return CopyResult::Fallback
+    }
+
+    let mut written = 0u64;
+    loop {
+        // Passing null offsets makes the kernel use, and advance, the file
+        // offsets of both descriptors just like `read` and `write` would.
+        let ret = unsafe {
+            copy_file_range(reader, ptr::null_mut(), writer, ptr::null_mut(), MAX_CHUNK, 0)
+        };
+        match ret {
+            // Some pseudo filesystems (e.g. procfs) report their files as
+            // empty and the kernel then copies nothing, so a zero-length
+            // result on the first call isn't trusted to mean EOF.
+            0 if written == 0 => return CopyResult::Fallback,
+            0 => return CopyResult::Ended(written),
+            -1 => {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    continue
+                }
+                if err.raw_os_error() == Some(libc::ENOSYS) {
+                    HAS_COPY_FILE_RANGE.store(false, Ordering::Relaxed);
+                }
+                if written == 0 && should_fall_back(&err) {
+                    return CopyResult::Fallback
+                }
+                return CopyResult::Error(err)
+            }
+            n => written += n as u64,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum SpliceMode {
+    Sendfile,
+    Splice,
+}
+
+fn sendfile_splice(mode: SpliceMode, reader: RawFd, writer: RawFd) -> CopyResult {
+    static HAS_SENDFILE: AtomicBool = AtomicBool::new(true);
+    static HAS_SPLICE: AtomicBool = AtomicBool::new(true);
+
+    let available = match mode {
+        SpliceMode::Sendfile => &HAS_SENDFILE,
+        SpliceMode::Splice => &HAS_SPLICE,
+    };
+    if !available.load(Ordering::Relaxed) {
+        return CopyResult::Fallback
+    }
+
+    let mut written = 0u64;
+    loop {
+        let ret = unsafe {
+            match mode {
+                SpliceMode::Sendfile => {
+                    libc::sendfile(writer, reader, ptr::null_mut(), MAX_CHUNK) as isize
+                }
+                SpliceMode::Splice => {
+                    libc::splice(reader, ptr::null_mut(), writer, ptr::null_mut(),
+                                 MAX_CHUNK, 0) as isize
+                }
+            }
+        };
+        match ret {
+            0 if written == 0 => return CopyResult::Fallback,
+            0 => return CopyResult::Ended(written),
+            -1 => {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    continue
+                }
+                if err.raw_os_error() == Some(libc::ENOSYS) {
+                    available.store(false, Ordering::Relaxed);
+                }
+                if written == 0 && should_fall_back(&err) {
+                    return CopyResult::Fallback
+                }
+                return CopyResult::Error(err)
+            }
+            n => written += n as u64,
+        }
+    }
+}
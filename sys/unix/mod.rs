@@ -46,6 +46,11 @@ pub mod ext;
 pub mod fast_thread_local;
 pub mod fd;
 pub mod fs;
+#[cfg(target_os = "linux")]
+pub mod inotify;
+pub mod io;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod kernel_copy;
 pub mod memchr;
 pub mod mutex;
 #[cfg(not(target_os = "l4re"))]
@@ -66,6 +71,7 @@ pub mod thread;
 pub mod thread_local;
 pub mod time;
 pub mod stdio;
+pub mod xattr;
 
 #[cfg(not(test))]
 pub fn init() {
@@ -108,12 +114,29 @@ pub fn decode_error_kind(errno: i32) -> ErrorKind {
         libc::EINVAL => ErrorKind::InvalidInput,
         libc::ETIMEDOUT => ErrorKind::TimedOut,
         libc::EEXIST => ErrorKind::AlreadyExists,
+        libc::ENOTDIR => ErrorKind::NotADirectory,
+        libc::EISDIR => ErrorKind::IsADirectory,
+        libc::ENOTEMPTY => ErrorKind::DirectoryNotEmpty,
+        libc::EROFS => ErrorKind::ReadOnlyFilesystem,
+        libc::ENOSPC => ErrorKind::StorageFull,
+        libc::EDQUOT => ErrorKind::QuotaExceeded,
+        libc::EFBIG => ErrorKind::FileTooLarge,
+        libc::EBUSY => ErrorKind::ResourceBusy,
+        libc::EXDEV => ErrorKind::CrossesDevices,
+        libc::EMLINK => ErrorKind::TooManyLinks,
+        libc::ENAMETOOLONG => ErrorKind::InvalidFilename,
+        libc::E2BIG => ErrorKind::ArgumentListTooLong,
+        libc::EDEADLK => ErrorKind::Deadlock,
+        libc::EHOSTUNREACH => ErrorKind::HostUnreachable,
+        libc::ENETUNREACH => ErrorKind::NetworkUnreachable,
+        libc::ENOSYS => ErrorKind::Unsupported,
 
         // These two constants can have the same value on some systems,
         // but different values on others, so we can't use a match
         // clause
         x if x == libc::EAGAIN || x == libc::EWOULDBLOCK =>
             ErrorKind::WouldBlock,
+        x if x == libc::EOPNOTSUPP || x == libc::ENOTSUP => ErrorKind::Unsupported,
 
         _ => ErrorKind::Other,
     }
@@ -8,12 +8,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use io;
+use io::{self, IoSlice, IoSliceMut};
 use libc::{self, c_int};
 use mem;
 use sync::atomic::{AtomicBool, ATOMIC_BOOL_INIT, Ordering};
 use sys::fd::FileDesc;
 use sys::{cvt, cvt_r};
+use sys_common::FromInner;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Anonymous pipes
@@ -66,19 +67,43 @@ pub fn anon_pipe() -> io::Result<(AnonPipe, AnonPipe)> {
     Ok((AnonPipe(fd0), AnonPipe(fd1)))
 }
 
+/// Creates the pipe handed out by `io::pipe`, which is the same kind of pipe
+/// that is used to talk to child processes.
+pub fn pipe() -> io::Result<(AnonPipe, AnonPipe)> {
+    anon_pipe()
+}
+
 impl AnonPipe {
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.read(buf)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+
     pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
         self.0.write(buf)
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+
+    pub fn duplicate(&self) -> io::Result<AnonPipe> {
+        self.0.duplicate().map(AnonPipe)
+    }
+
     pub fn fd(&self) -> &FileDesc { &self.0 }
     pub fn into_fd(self) -> FileDesc { self.0 }
 }
 
+impl FromInner<FileDesc> for AnonPipe {
+    fn from_inner(fd: FileDesc) -> AnonPipe {
+        AnonPipe(fd)
+    }
+}
+
 pub fn read2(p1: AnonPipe,
              v1: &mut Vec<u8>,
              p2: AnonPipe,
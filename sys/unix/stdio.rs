@@ -19,6 +19,10 @@ pub struct Stderr(());
 impl Stdin {
     pub fn new() -> io::Result<Stdin> { Ok(Stdin(())) }
 
+    pub fn is_terminal(&self) -> bool {
+        unsafe { libc::isatty(libc::STDIN_FILENO) != 0 }
+    }
+
     pub fn read(&self, data: &mut [u8]) -> io::Result<usize> {
         let fd = FileDesc::new(libc::STDIN_FILENO);
         let ret = fd.read(data);
@@ -30,6 +34,10 @@ impl Stdin {
 impl Stdout {
     pub fn new() -> io::Result<Stdout> { Ok(Stdout(())) }
 
+    pub fn is_terminal(&self) -> bool {
+        unsafe { libc::isatty(libc::STDOUT_FILENO) != 0 }
+    }
+
     pub fn write(&self, data: &[u8]) -> io::Result<usize> {
         let fd = FileDesc::new(libc::STDOUT_FILENO);
         let ret = fd.write(data);
@@ -45,6 +53,10 @@ impl Stdout {
 impl Stderr {
     pub fn new() -> io::Result<Stderr> { Ok(Stderr(())) }
 
+    pub fn is_terminal(&self) -> bool {
+        unsafe { libc::isatty(libc::STDERR_FILENO) != 0 }
+    }
+
     pub fn write(&self, data: &[u8]) -> io::Result<usize> {
         let fd = FileDesc::new(libc::STDERR_FILENO);
         let ret = fd.write(data);
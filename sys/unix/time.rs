@@ -117,7 +117,7 @@ mod inner {
     use libc;
     use sync::Once;
     use sys::cvt;
-    use sys_common::mul_div_u64;
+    use sys_common::{mul_div_u64, IntoInner};
     use time::Duration;
 
     use super::NSEC_PER_SEC;
@@ -213,6 +213,12 @@ mod inner {
         }
     }
 
+    impl IntoInner<libc::timespec> for SystemTime {
+        fn into_inner(self) -> libc::timespec {
+            self.t.t
+        }
+    }
+
     impl fmt::Debug for SystemTime {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             f.debug_struct("SystemTime")
@@ -251,6 +257,7 @@ mod inner {
     use fmt;
     use libc;
     use sys::cvt;
+    use sys_common::IntoInner;
     use time::Duration;
 
     use super::Timespec;
@@ -328,6 +335,12 @@ mod inner {
         }
     }
 
+    impl IntoInner<libc::timespec> for SystemTime {
+        fn into_inner(self) -> libc::timespec {
+            self.t.t
+        }
+    }
+
     impl fmt::Debug for SystemTime {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             f.debug_struct("SystemTime")
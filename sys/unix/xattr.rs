@@ -0,0 +1,258 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Extended attributes on Linux and macOS.
+//!
+//! Both platforms have the same set of functions, but the macOS ones take an
+//! extra position (only meaningful for resource forks) and options argument
+//! and the flags for setting an attribute differ, which the wrappers at the
+//! bottom of this module hide. Other platforms report `ENOTSUP`.
+
+use libc::c_int;
+use path::Path;
+
+pub use self::imp::{list, get, set, remove};
+
+/// The file whose attributes are accessed.
+#[derive(Copy, Clone)]
+pub enum Target<'a> {
+    Fd(c_int),
+    /// A path, following symbolic links.
+    Path(&'a Path),
+    /// A path, accessing the attributes of a symbolic link itself.
+    Link(&'a Path),
+}
+
+#[cfg(any(target_os = "linux", target_os = "android",
+          target_os = "macos", target_os = "ios"))]
+mod imp {
+    use ffi::{CString, OsStr, OsString};
+    use io;
+    use libc::{self, c_char, c_int, c_void, ssize_t};
+    use os::unix::ffi::{OsStrExt, OsStringExt};
+    use ptr;
+    use sys::cvt;
+    use super::Target;
+    use super::raw::*;
+
+    enum RawTarget {
+        Fd(c_int),
+        Path(CString),
+        Link(CString),
+    }
+
+    fn to_raw(target: Target) -> io::Result<RawTarget> {
+        Ok(match target {
+            Target::Fd(fd) => RawTarget::Fd(fd),
+            Target::Path(p) => RawTarget::Path(CString::new(p.as_os_str().as_bytes())?),
+            Target::Link(p) => RawTarget::Link(CString::new(p.as_os_str().as_bytes())?),
+        })
+    }
+
+    fn name_cstr(name: &OsStr) -> io::Result<CString> {
+        Ok(CString::new(name.as_bytes())?)
+    }
+
+    /// Returns the names of all extended attributes of `target`.
+    pub fn list(target: Target) -> io::Result<Vec<OsString>> {
+        let target = to_raw(target)?;
+        let names = read_sized(|buf, len| unsafe {
+            let buf = buf as *mut c_char;
+            match target {
+                RawTarget::Fd(fd) => flistxattr(fd, buf, len),
+                RawTarget::Path(ref p) => listxattr(p.as_ptr(), buf, len),
+                RawTarget::Link(ref p) => llistxattr(p.as_ptr(), buf, len),
+            }
+        })?;
+        // The names are each terminated by a NUL byte.
+        Ok(names.split(|&b| b == 0)
+                .filter(|name| !name.is_empty())
+                .map(|name| OsString::from_vec(name.to_vec()))
+                .collect())
+    }
+
+    pub fn get(target: Target, name: &OsStr) -> io::Result<Vec<u8>> {
+        let target = to_raw(target)?;
+        let name = name_cstr(name)?;
+        read_sized(|buf, len| unsafe {
+            let buf = buf as *mut c_void;
+            match target {
+                RawTarget::Fd(fd) => fgetxattr(fd, name.as_ptr(), buf, len),
+                RawTarget::Path(ref p) => getxattr(p.as_ptr(), name.as_ptr(), buf, len),
+                RawTarget::Link(ref p) => lgetxattr(p.as_ptr(), name.as_ptr(), buf, len),
+            }
+        })
+    }
+
+    pub fn set(target: Target, name: &OsStr, value: &[u8]) -> io::Result<()> {
+        let target = to_raw(target)?;
+        let name = name_cstr(name)?;
+        let (ptr, len) = (value.as_ptr() as *const c_void, value.len());
+        cvt(unsafe {
+            match target {
+                RawTarget::Fd(fd) => fsetxattr(fd, name.as_ptr(), ptr, len),
+                RawTarget::Path(ref p) => setxattr(p.as_ptr(), name.as_ptr(), ptr, len),
+                RawTarget::Link(ref p) => lsetxattr(p.as_ptr(), name.as_ptr(), ptr, len),
+            }
+        })?;
+        Ok(())
+    }
+
+    pub fn remove(target: Target, name: &OsStr) -> io::Result<()> {
+        let target = to_raw(target)?;
+        let name = name_cstr(name)?;
+        cvt(unsafe {
+            match target {
+                RawTarget::Fd(fd) => fremovexattr(fd, name.as_ptr()),
+                RawTarget::Path(ref p) => removexattr(p.as_ptr(), name.as_ptr()),
+                RawTarget::Link(ref p) => lremovexattr(p.as_ptr(), name.as_ptr()),
+            }
+        })?;
+        Ok(())
+    }
+
+    // Attributes may change between asking for the size of a value and
+    // reading it, in which case the read fails with `ERANGE` and is simply
+    // retried. A size of zero would be another size query, so an empty
+    // value is returned right away instead.
+    fn read_sized<F>(mut f: F) -> io::Result<Vec<u8>>
+        where F: FnMut(*mut u8, usize) -> ssize_t
+    {
+        loop {
+            let len = cvt(f(ptr::null_mut(), 0))? as usize;
+            if len == 0 {
+                return Ok(Vec::new())
+            }
+            let mut buf = Vec::with_capacity(len);
+            match cvt(f(buf.as_mut_ptr(), len)) {
+                Ok(n) if n as usize <= len => {
+                    unsafe { buf.set_len(n as usize); }
+                    return Ok(buf)
+                }
+                Ok(_) => {}
+                Err(ref e) if e.raw_os_error() == Some(libc::ERANGE) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android",
+              target_os = "macos", target_os = "ios")))]
+mod imp {
+    use ffi::{OsStr, OsString};
+    use io;
+    use libc;
+    use super::Target;
+
+    fn unsupported<T>() -> io::Result<T> {
+        Err(io::Error::from_raw_os_error(libc::ENOTSUP))
+    }
+
+    pub fn list(_target: Target) -> io::Result<Vec<OsString>> {
+        unsupported()
+    }
+
+    pub fn get(_target: Target, _name: &OsStr) -> io::Result<Vec<u8>> {
+        unsupported()
+    }
+
+    pub fn set(_target: Target, _name: &OsStr, _value: &[u8]) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn remove(_target: Target, _name: &OsStr) -> io::Result<()> {
+        unsupported()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod raw {
+    use libc::{self, c_char, c_int, c_void};
+
+    pub use libc::{listxattr, llistxattr, flistxattr, getxattr, lgetxattr, fgetxattr,
+                   removexattr, lremovexattr, fremovexattr};
+
+    pub unsafe fn setxattr(path: *const c_char, name: *const c_char, value: *const c_void,
+                           len: usize) -> c_int {
+        libc::setxattr(path, name, value, len, 0)
+    }
+
+    pub unsafe fn lsetxattr(path: *const c_char, name: *const c_char, value: *const c_void,
+                            len: usize) -> c_int {
+        libc::lsetxattr(path, name, value, len, 0)
+    }
+
+    pub unsafe fn fsetxattr(fd: c_int, name: *const c_char, value: *const c_void, len: usize)
+                            -> c_int {
+        libc::fsetxattr(fd, name, value, len, 0)
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+mod raw {
+    use libc::{self, c_char, c_int, c_void, ssize_t};
+
+    const XATTR_NOFOLLOW: c_int = 0x0001;
+
+    pub unsafe fn listxattr(path: *const c_char, buf: *mut c_char, len: usize) -> ssize_t {
+        libc::listxattr(path, buf, len, 0)
+    }
+
+    pub unsafe fn llistxattr(path: *const c_char, buf: *mut c_char, len: usize) -> ssize_t {
+        libc::listxattr(path, buf, len, XATTR_NOFOLLOW)
+    }
+
+    pub unsafe fn flistxattr(fd: c_int, buf: *mut c_char, len: usize) -> ssize_t {
+        libc::flistxattr(fd, buf, len, 0)
+    }
+
+    pub unsafe fn getxattr(path: *const c_char, name: *const c_char, buf: *mut c_void,
+                           len: usize) -> ssize_t {
+        libc::getxattr(path, name, buf, len, 0, 0)
+    }
+
+    pub unsafe fn lgetxattr(path: *const c_char, name: *const c_char, buf: *mut c_void,
+                            len: usize) -> ssize_t {
+        libc::getxattr(path, name, buf, len, 0, XATTR_NOFOLLOW)
+    }
+
+    pub unsafe fn fgetxattr(fd: c_int, name: *const c_char, buf: *mut c_void, len: usize)
+                            -> ssize_t {
+        libc::fgetxattr(fd, name, buf, len, 0, 0)
+    }
+
+    pub unsafe fn setxattr(path: *const c_char, name: *const c_char, value: *const c_void,
+                           len: usize) -> c_int {
+        libc::setxattr(path, name, value, len, 0, 0)
+    }
+
+    pub unsafe fn lsetxattr(path: *const c_char, name: *const c_char, value: *const c_void,
+                            len: usize) -> c_int {
+        libc::setxattr(path, name, value, len, 0, XATTR_NOFOLLOW)
+    }
+
+    pub unsafe fn fsetxattr(fd: c_int, name: *const c_char, value: *const c_void, len: usize)
+                            -> c_int {
+        libc::fsetxattr(fd, name, value, len, 0, 0)
+    }
+
+    pub unsafe fn removexattr(path: *const c_char, name: *const c_char) -> c_int {
+        libc::removexattr(path, name, 0)
+    }
+
+    pub unsafe fn lremovexattr(path: *const c_char, name: *const c_char) -> c_int {
+        libc::removexattr(path, name, XATTR_NOFOLLOW)
+    }
+
+    pub unsafe fn fremovexattr(fd: c_int, name: *const c_char) -> c_int {
+        libc::fremovexattr(fd, name, 0)
+    }
+}
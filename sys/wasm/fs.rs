@@ -9,9 +9,10 @@
 // except according to those terms.
 
 use ffi::OsString;
+use fs::{Advice, MmapMode, Reflink};
 use fmt;
 use hash::{Hash, Hasher};
-use io::{self, SeekFrom};
+use io::{self, SeekFrom, IoSlice, IoSliceMut, ReadBuf};
 use path::{Path, PathBuf};
 use sys::time::SystemTime;
 use sys::{unsupported, Void};
@@ -20,6 +21,8 @@ pub struct File(Void);
 
 pub struct FileAttr(Void);
 
+pub struct FsStats(Void);
+
 pub struct ReadDir(Void);
 
 pub struct DirEntry(Void);
@@ -34,6 +37,12 @@ pub struct FileType(Void);
 #[derive(Debug)]
 pub struct DirBuilder { }
 
+#[derive(Clone, Debug)]
+pub struct CopyOptions { }
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FileTimes { }
+
 impl FileAttr {
     pub fn size(&self) -> u64 {
         match self.0 {}
@@ -194,6 +203,10 @@ impl File {
         match self.0 {}
     }
 
+    pub fn fs_stats(&self) -> io::Result<FsStats> {
+        match self.0 {}
+    }
+
     pub fn fsync(&self) -> io::Result<()> {
         match self.0 {}
     }
@@ -206,14 +219,58 @@ impl File {
         match self.0 {}
     }
 
+    pub fn allocate(&self, _offset: u64, _len: u64) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn punch_hole(&self, _offset: u64, _len: u64) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn next_data(&mut self, _offset: u64) -> io::Result<Option<(u64, u64)>> {
+        match self.0 {}
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn read(&self, _buf: &mut [u8]) -> io::Result<usize> {
         match self.0 {}
     }
 
+    pub fn read_vectored(&self, _bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        match self.0 {}
+    }
+
+    pub fn read_buf(&self, _buf: &mut ReadBuf) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn write(&self, _buf: &[u8]) -> io::Result<usize> {
         match self.0 {}
     }
 
+    pub fn write_vectored(&self, _bufs: &[IoSlice]) -> io::Result<usize> {
+        match self.0 {}
+    }
+
     pub fn flush(&self) -> io::Result<()> {
         match self.0 {}
     }
@@ -226,15 +283,92 @@ impl File {
         match self.0 {}
     }
 
+    pub fn is_terminal(&self) -> bool {
+        match self.0 {}
+    }
+
     pub fn set_permissions(&self, _perm: FilePermissions) -> io::Result<()> {
         match self.0 {}
     }
 
+    pub fn set_times(&self, _times: FileTimes) -> io::Result<()> {
+        match self.0 {}
+    }
+
     pub fn diverge(&self) -> ! {
         match self.0 {}
     }
 }
 
+pub struct DirHandle(Void);
+
+impl DirHandle {
+    pub fn open(_path: &Path, _follow_symlinks: bool) -> io::Result<DirHandle> {
+        unsupported()
+    }
+
+    pub fn open_file(&self, _path: &Path, _opts: &OpenOptions) -> io::Result<File> {
+        match self.0 {}
+    }
+
+    pub fn open_dir(&self, _path: &Path) -> io::Result<DirHandle> {
+        match self.0 {}
+    }
+
+    pub fn create_dir(&self, _path: &Path) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn remove_file(&self, _path: &Path) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn remove_dir(&self, _path: &Path) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn rename(&self, _from: &Path, _to_dir: &DirHandle, _to: &Path) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn symlink(&self, _original: &Path, _link: &Path) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn metadata(&self, _path: &Path) -> io::Result<FileAttr> {
+        match self.0 {}
+    }
+
+    pub fn symlink_metadata(&self, _path: &Path) -> io::Result<FileAttr> {
+        match self.0 {}
+    }
+
+    pub fn read_dir(&self, _path: &Path) -> io::Result<ReadDir> {
+        match self.0 {}
+    }
+}
+
+impl fmt::Debug for DirHandle {
+    fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {}
+    }
+}
+
+impl CopyOptions {
+    pub fn new() -> CopyOptions { CopyOptions { } }
+    pub fn reflink(&mut self, _reflink: Reflink) {}
+    pub fn overwrite(&mut self, _overwrite: bool) {}
+    pub fn copy_symlinks(&mut self, _copy_symlinks: bool) {}
+    pub fn preserve_timestamps(&mut self, _preserve: bool) {}
+    pub fn preserve_ownership(&mut self, _preserve: bool) {}
+    pub fn preserve_xattrs(&mut self, _preserve: bool) {}
+}
+
+impl FileTimes {
+    pub fn set_accessed(&mut self, _t: SystemTime) {}
+    pub fn set_modified(&mut self, _t: SystemTime) {}
+}
+
 impl DirBuilder {
     pub fn new() -> DirBuilder {
         DirBuilder { }
@@ -263,10 +397,36 @@ pub fn rename(_old: &Path, _new: &Path) -> io::Result<()> {
     unsupported()
 }
 
+pub fn rename_noreplace(_old: &Path, _new: &Path) -> io::Result<()> {
+    unsupported()
+}
+
+pub fn rename_exchange(_old: &Path, _new: &Path) -> io::Result<()> {
+    unsupported()
+}
+
 pub fn set_perm(_p: &Path, perm: FilePermissions) -> io::Result<()> {
     match perm.0 {}
 }
 
+pub fn set_times(_p: &Path, _times: FileTimes) -> io::Result<()> {
+    unsupported()
+}
+
+pub fn temp_file_options(_opts: &mut OpenOptions, _delete_on_close: bool) {}
+
+pub fn replacement_file_options(_opts: &mut OpenOptions) {}
+
+pub fn temp_dir_builder(_builder: &mut DirBuilder) {}
+
+pub fn open_tmpfile(_dir: &Path) -> io::Result<Option<File>> {
+    unsupported()
+}
+
+pub fn sync_dir(_p: &Path) -> io::Result<()> {
+    unsupported()
+}
+
 pub fn rmdir(_p: &Path) -> io::Result<()> {
     unsupported()
 }
@@ -295,6 +455,62 @@ pub fn lstat(_p: &Path) -> io::Result<FileAttr> {
     unsupported()
 }
 
+pub fn fs_stats(_p: &Path) -> io::Result<FsStats> {
+    unsupported()
+}
+
+impl Clone for FsStats {
+    fn clone(&self) -> FsStats {
+        match self.0 {}
+    }
+}
+
+impl FsStats {
+    pub fn total_space(&self) -> u64 {
+        match self.0 {}
+    }
+
+    pub fn free_space(&self) -> u64 {
+        match self.0 {}
+    }
+
+    pub fn available_space(&self) -> u64 {
+        match self.0 {}
+    }
+
+    pub fn total_inodes(&self) -> u64 {
+        match self.0 {}
+    }
+
+    pub fn free_inodes(&self) -> u64 {
+        match self.0 {}
+    }
+
+    pub fn available_inodes(&self) -> u64 {
+        match self.0 {}
+    }
+
+    pub fn block_size(&self) -> u64 {
+        match self.0 {}
+    }
+
+    pub fn fs_id(&self) -> u64 {
+        match self.0 {}
+    }
+
+    pub fn max_name_len(&self) -> u64 {
+        match self.0 {}
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        match self.0 {}
+    }
+
+    pub fn is_nosuid(&self) -> bool {
+        match self.0 {}
+    }
+}
+
 pub fn canonicalize(_p: &Path) -> io::Result<PathBuf> {
     unsupported()
 }
@@ -302,3 +518,31 @@ pub fn canonicalize(_p: &Path) -> io::Result<PathBuf> {
 pub fn copy(_from: &Path, _to: &Path) -> io::Result<u64> {
     unsupported()
 }
+
+pub fn copy_with(_from: &Path, _to: &Path, _opts: &CopyOptions) -> io::Result<u64> {
+    unsupported()
+}
+
+pub struct Mmap(Void);
+
+impl Mmap {
+    pub fn new(file: &File, _mode: MmapMode, _offset: u64, _len: usize) -> io::Result<Mmap> {
+        match file.0 {}
+    }
+
+    pub fn ptr(&self) -> *mut u8 {
+        match self.0 {}
+    }
+
+    pub fn len(&self) -> usize {
+        match self.0 {}
+    }
+
+    pub fn flush(&self, _offset: usize, _len: usize) -> io::Result<()> {
+        match self.0 {}
+    }
+
+    pub fn advise(&self, _advice: Advice) -> io::Result<()> {
+        match self.0 {}
+    }
+}
@@ -44,6 +44,7 @@ pub mod cmath;
 pub mod condvar;
 pub mod env;
 pub mod fs;
+pub mod io;
 pub mod memchr;
 pub mod mutex;
 pub mod net;
@@ -68,7 +69,7 @@ pub fn unsupported<T>() -> io::Result<T> {
 }
 
 pub fn unsupported_err() -> io::Error {
-    io::Error::new(io::ErrorKind::Other,
+    io::Error::new(io::ErrorKind::Unsupported,
                    "operation not supported on wasm yet")
 }
 
@@ -23,6 +23,10 @@ impl Stdin {
     pub fn read(&self, _data: &mut [u8]) -> io::Result<usize> {
         match self.0 {}
     }
+
+    pub fn is_terminal(&self) -> bool {
+        match self.0 {}
+    }
 }
 
 impl Stdout {
@@ -30,6 +34,10 @@ impl Stdout {
         Ok(Stdout)
     }
 
+    pub fn is_terminal(&self) -> bool {
+        false
+    }
+
     pub fn write(&self, data: &[u8]) -> io::Result<usize> {
         // If runtime debugging is enabled at compile time we'll invoke some
         // runtime functions that are defined in our src/etc/wasm32-shim.js
@@ -57,6 +65,10 @@ impl Stderr {
         Ok(Stderr)
     }
 
+    pub fn is_terminal(&self) -> bool {
+        false
+    }
+
     pub fn write(&self, data: &[u8]) -> io::Result<usize> {
         // See comments in stdout for what's going on here.
         if !super::DEBUG {
@@ -67,6 +67,7 @@ pub type LPSTARTUPINFO = *mut STARTUPINFO;
 pub type LPVOID = *mut c_void;
 pub type LPWCH = *mut WCHAR;
 pub type LPWIN32_FIND_DATAW = *mut WIN32_FIND_DATAW;
+pub type LPWSABUF = *mut WSABUF;
 pub type LPWSADATA = *mut WSADATA;
 pub type LPWSAPROTOCOL_INFO = *mut WSAPROTOCOL_INFO;
 pub type LPSTR = *mut CHAR;
@@ -75,6 +76,7 @@ pub type LPFILETIME = *mut FILETIME;
 
 pub type PCONDITION_VARIABLE = *mut CONDITION_VARIABLE;
 pub type PLARGE_INTEGER = *mut c_longlong;
+pub type PULARGE_INTEGER = *mut c_ulonglong;
 pub type PSRWLOCK = *mut SRWLOCK;
 
 pub type SOCKET = ::os::windows::raw::SOCKET;
@@ -115,8 +117,23 @@ pub const FILE_GENERIC_WRITE: DWORD = STANDARD_RIGHTS_WRITE | FILE_WRITE_DATA |
 
 pub const FILE_FLAG_OPEN_REPARSE_POINT: DWORD = 0x00200000;
 pub const FILE_FLAG_BACKUP_SEMANTICS: DWORD = 0x02000000;
+pub const FILE_FLAG_DELETE_ON_CLOSE: DWORD = 0x04000000;
+
+pub const PAGE_READONLY: DWORD = 0x02;
+pub const PAGE_READWRITE: DWORD = 0x04;
+pub const PAGE_WRITECOPY: DWORD = 0x08;
+pub const FILE_MAP_COPY: DWORD = 0x0001;
+pub const FILE_MAP_WRITE: DWORD = 0x0002;
+pub const FILE_MAP_READ: DWORD = 0x0004;
 pub const SECURITY_SQOS_PRESENT: DWORD = 0x00100000;
 
+pub const FILE_READ_ONLY_VOLUME: DWORD = 0x00080000;
+
+pub const MAX_PATH: usize = 260;
+
+pub const LOCKFILE_FAIL_IMMEDIATELY: DWORD = 0x00000001;
+pub const LOCKFILE_EXCLUSIVE_LOCK: DWORD = 0x00000002;
+
 pub const FIONBIO: c_ulong = 0x8004667e;
 
 #[repr(C)]
@@ -149,12 +166,14 @@ pub const WSAEINVAL: c_int = 10022;
 pub const WSAEWOULDBLOCK: c_int = 10035;
 pub const WSAEADDRINUSE: c_int = 10048;
 pub const WSAEADDRNOTAVAIL: c_int = 10049;
+pub const WSAENETUNREACH: c_int = 10051;
 pub const WSAECONNABORTED: c_int = 10053;
 pub const WSAECONNRESET: c_int = 10054;
 pub const WSAENOTCONN: c_int = 10057;
 pub const WSAESHUTDOWN: c_int = 10058;
 pub const WSAETIMEDOUT: c_int = 10060;
 pub const WSAECONNREFUSED: c_int = 10061;
+pub const WSAEHOSTUNREACH: c_int = 10065;
 
 pub const MAX_PROTOCOL_CHAIN: DWORD = 7;
 
@@ -165,6 +184,8 @@ pub const IO_REPARSE_TAG_SYMLINK: DWORD = 0xa000000c;
 pub const IO_REPARSE_TAG_MOUNT_POINT: DWORD = 0xa0000003;
 pub const SYMLINK_FLAG_RELATIVE: DWORD = 0x00000001;
 pub const FSCTL_SET_REPARSE_POINT: DWORD = 0x900a4;
+pub const FSCTL_SET_SPARSE: DWORD = 0x900c4;
+pub const FSCTL_SET_ZERO_DATA: DWORD = 0x980c8;
 
 pub const SYMBOLIC_LINK_FLAG_DIRECTORY: DWORD = 0x1;
 pub const SYMBOLIC_LINK_FLAG_ALLOW_UNPRIVILEGED_CREATE: DWORD = 0x2;
@@ -178,20 +199,38 @@ pub const HANDLE_FLAG_INHERIT: DWORD = 0x00000001;
 
 pub const PROGRESS_CONTINUE: DWORD = 0;
 
+pub const COPY_FILE_FAIL_IF_EXISTS: DWORD = 0x00000001;
+pub const COPY_FILE_COPY_SYMLINK: DWORD = 0x00000800;
+
 pub const ERROR_FILE_NOT_FOUND: DWORD = 2;
 pub const ERROR_PATH_NOT_FOUND: DWORD = 3;
 pub const ERROR_ACCESS_DENIED: DWORD = 5;
 pub const ERROR_INVALID_HANDLE: DWORD = 6;
+pub const ERROR_NOT_SAME_DEVICE: DWORD = 17;
 pub const ERROR_NO_MORE_FILES: DWORD = 18;
+pub const ERROR_WRITE_PROTECT: DWORD = 19;
+pub const ERROR_LOCK_VIOLATION: DWORD = 33;
 pub const ERROR_HANDLE_EOF: DWORD = 38;
+pub const ERROR_HANDLE_DISK_FULL: DWORD = 39;
+pub const ERROR_NOT_SUPPORTED: DWORD = 50;
 pub const ERROR_FILE_EXISTS: DWORD = 80;
 pub const ERROR_INVALID_PARAMETER: DWORD = 87;
 pub const ERROR_BROKEN_PIPE: DWORD = 109;
+pub const ERROR_DISK_FULL: DWORD = 112;
 pub const ERROR_CALL_NOT_IMPLEMENTED: DWORD = 120;
 pub const ERROR_INSUFFICIENT_BUFFER: DWORD = 122;
+pub const ERROR_INVALID_NAME: DWORD = 123;
+pub const ERROR_DIR_NOT_EMPTY: DWORD = 145;
+pub const ERROR_BUSY: DWORD = 170;
 pub const ERROR_ALREADY_EXISTS: DWORD = 183;
+pub const ERROR_FILENAME_EXCED_RANGE: DWORD = 206;
+pub const ERROR_FILE_TOO_LARGE: DWORD = 223;
 pub const ERROR_NO_DATA: DWORD = 232;
 pub const ERROR_ENVVAR_NOT_FOUND: DWORD = 203;
+pub const ERROR_DIRECTORY: DWORD = 267;
+pub const ERROR_POSSIBLE_DEADLOCK: DWORD = 1131;
+pub const ERROR_TOO_MANY_LINKS: DWORD = 1142;
+pub const ERROR_DISK_QUOTA_EXCEEDED: DWORD = 1295;
 pub const ERROR_OPERATION_ABORTED: DWORD = 995;
 pub const ERROR_IO_PENDING: DWORD = 997;
 pub const ERROR_TIMEOUT: DWORD = 0x5B4;
@@ -324,6 +363,13 @@ pub struct WSADATA {
     pub szSystemStatus: [u8; WSASYS_STATUS_LEN + 1],
 }
 
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct WSABUF {
+    pub len: ULONG,
+    pub buf: *mut CHAR,
+}
+
 #[repr(C)]
 pub struct WSAPROTOCOL_INFO {
     pub dwServiceFlags1: DWORD,
@@ -409,6 +455,12 @@ pub struct FILE_BASIC_INFO {
     pub FileAttributes: DWORD,
 }
 
+#[repr(C)]
+pub struct FILE_ZERO_DATA_INFORMATION {
+    pub FileOffset: LARGE_INTEGER,
+    pub BeyondFinalZero: LARGE_INTEGER,
+}
+
 #[repr(C)]
 pub struct FILE_END_OF_FILE_INFO {
     pub EndOfFile: LARGE_INTEGER,
@@ -561,6 +613,21 @@ pub struct FILETIME {
     pub dwHighDateTime: DWORD,
 }
 
+#[repr(C)]
+pub struct SYSTEM_INFO {
+    pub wProcessorArchitecture: WORD,
+    pub wReserved: WORD,
+    pub dwPageSize: DWORD,
+    pub lpMinimumApplicationAddress: LPVOID,
+    pub lpMaximumApplicationAddress: LPVOID,
+    pub dwActiveProcessorMask: ULONG_PTR,
+    pub dwNumberOfProcessors: DWORD,
+    pub dwProcessorType: DWORD,
+    pub dwAllocationGranularity: DWORD,
+    pub wProcessorLevel: WORD,
+    pub wProcessorRevision: WORD,
+}
+
 #[repr(C)]
 pub struct OVERLAPPED {
     pub Internal: *mut c_ulong,
@@ -890,6 +957,20 @@ extern "system" {
                       g: GROUP,
                       dwFlags: DWORD) -> SOCKET;
     pub fn ioctlsocket(s: SOCKET, cmd: c_long, argp: *mut c_ulong) -> c_int;
+    pub fn WSASend(s: SOCKET,
+                   lpBuffers: LPWSABUF,
+                   dwBufferCount: DWORD,
+                   lpNumberOfBytesSent: LPDWORD,
+                   dwFlags: DWORD,
+                   lpOverlapped: LPVOID,
+                   lpCompletionRoutine: LPVOID) -> c_int;
+    pub fn WSARecv(s: SOCKET,
+                   lpBuffers: LPWSABUF,
+                   dwBufferCount: DWORD,
+                   lpNumberOfBytesRecvd: LPDWORD,
+                   lpFlags: LPDWORD,
+                   lpOverlapped: LPVOID,
+                   lpCompletionRoutine: LPVOID) -> c_int;
     pub fn InitializeCriticalSection(CriticalSection: *mut CRITICAL_SECTION);
     pub fn EnterCriticalSection(CriticalSection: *mut CRITICAL_SECTION);
     pub fn TryEnterCriticalSection(CriticalSection: *mut CRITICAL_SECTION) -> BOOLEAN;
@@ -1041,6 +1122,11 @@ extern "system" {
                   address: *mut SOCKADDR,
                   address_len: *mut c_int)
                   -> SOCKET;
+    pub fn CreatePipe(hReadPipe: LPHANDLE,
+                      hWritePipe: LPHANDLE,
+                      lpPipeAttributes: LPSECURITY_ATTRIBUTES,
+                      nSize: DWORD)
+                      -> BOOL;
     pub fn DuplicateHandle(hSourceProcessHandle: HANDLE,
                            hSourceHandle: HANDLE,
                            hTargetProcessHandle: HANDLE,
@@ -1076,6 +1162,64 @@ extern "system" {
                             dwMoveMethod: DWORD)
                             -> BOOL;
     pub fn FlushFileBuffers(hFile: HANDLE) -> BOOL;
+    pub fn LockFileEx(hFile: HANDLE,
+                      dwFlags: DWORD,
+                      dwReserved: DWORD,
+                      nNumberOfBytesToLockLow: DWORD,
+                      nNumberOfBytesToLockHigh: DWORD,
+                      lpOverlapped: LPOVERLAPPED)
+                      -> BOOL;
+    pub fn SetFileTime(hFile: HANDLE,
+                       lpCreationTime: *const FILETIME,
+                       lpLastAccessTime: *const FILETIME,
+                       lpLastWriteTime: *const FILETIME)
+                       -> BOOL;
+    pub fn GetSystemInfo(lpSystemInfo: *mut SYSTEM_INFO);
+    pub fn CreateFileMappingW(hFile: HANDLE,
+                              lpFileMappingAttributes: LPSECURITY_ATTRIBUTES,
+                              flProtect: DWORD,
+                              dwMaximumSizeHigh: DWORD,
+                              dwMaximumSizeLow: DWORD,
+                              lpName: LPCWSTR)
+                              -> HANDLE;
+    pub fn MapViewOfFile(hFileMappingObject: HANDLE,
+                         dwDesiredAccess: DWORD,
+                         dwFileOffsetHigh: DWORD,
+                         dwFileOffsetLow: DWORD,
+                         dwNumberOfBytesToMap: SIZE_T)
+                         -> LPVOID;
+    pub fn FlushViewOfFile(lpBaseAddress: LPCVOID, dwNumberOfBytesToFlush: SIZE_T) -> BOOL;
+    pub fn UnmapViewOfFile(lpBaseAddress: LPCVOID) -> BOOL;
+    pub fn GetVolumePathNameW(lpszFileName: LPCWSTR,
+                              lpszVolumePathName: LPWSTR,
+                              cchBufferLength: DWORD)
+                              -> BOOL;
+    pub fn GetDiskFreeSpaceW(lpRootPathName: LPCWSTR,
+                             lpSectorsPerCluster: LPDWORD,
+                             lpBytesPerSector: LPDWORD,
+                             lpNumberOfFreeClusters: LPDWORD,
+                             lpTotalNumberOfClusters: LPDWORD)
+                             -> BOOL;
+    pub fn GetDiskFreeSpaceExW(lpDirectoryName: LPCWSTR,
+                               lpFreeBytesAvailableToCaller: PULARGE_INTEGER,
+                               lpTotalNumberOfBytes: PULARGE_INTEGER,
+                               lpTotalNumberOfFreeBytes: PULARGE_INTEGER)
+                               -> BOOL;
+    pub fn GetVolumeInformationW(lpRootPathName: LPCWSTR,
+                                 lpVolumeNameBuffer: LPWSTR,
+                                 nVolumeNameSize: DWORD,
+                                 lpVolumeSerialNumber: LPDWORD,
+                                 lpMaximumComponentLength: LPDWORD,
+                                 lpFileSystemFlags: LPDWORD,
+                                 lpFileSystemNameBuffer: LPWSTR,
+                                 nFileSystemNameSize: DWORD)
+                                 -> BOOL;
+    pub fn UnlockFile(hFile: HANDLE,
+                      dwFileOffsetLow: DWORD,
+                      dwFileOffsetHigh: DWORD,
+                      nNumberOfBytesToUnlockLow: DWORD,
+                      nNumberOfBytesToUnlockHigh: DWORD)
+                      -> BOOL;
     pub fn CreateFileW(lpFileName: LPCWSTR,
                        dwDesiredAccess: DWORD,
                        dwShareMode: DWORD,
@@ -13,6 +13,7 @@
 use fs;
 use os::windows::raw;
 use net;
+use process;
 use sys_common::{self, AsInner, FromInner, IntoInner};
 use sys;
 use io;
@@ -108,6 +109,50 @@ impl IntoRawHandle for fs::File {
     }
 }
 
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl AsRawHandle for io::PipeReader {
+    fn as_raw_handle(&self) -> RawHandle {
+        self.as_inner().handle().raw() as RawHandle
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl FromRawHandle for io::PipeReader {
+    unsafe fn from_raw_handle(handle: RawHandle) -> io::PipeReader {
+        let handle = sys::handle::Handle::new(handle as c::HANDLE);
+        io::PipeReader::from_inner(sys::pipe::AnonPipe::from_inner(handle))
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl IntoRawHandle for io::PipeReader {
+    fn into_raw_handle(self) -> RawHandle {
+        self.into_inner().into_handle().into_raw() as *mut _
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl AsRawHandle for io::PipeWriter {
+    fn as_raw_handle(&self) -> RawHandle {
+        self.as_inner().handle().raw() as RawHandle
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl FromRawHandle for io::PipeWriter {
+    unsafe fn from_raw_handle(handle: RawHandle) -> io::PipeWriter {
+        let handle = sys::handle::Handle::new(handle as c::HANDLE);
+        io::PipeWriter::from_inner(sys::pipe::AnonPipe::from_inner(handle))
+    }
+}
+
+#[unstable(feature = "anonymous_pipe", issue = "0")]
+impl IntoRawHandle for io::PipeWriter {
+    fn into_raw_handle(self) -> RawHandle {
+        self.into_inner().into_handle().into_raw() as *mut _
+    }
+}
+
 /// Extract raw sockets.
 #[stable(feature = "rust1", since = "1.0.0")]
 pub trait AsRawSocket {
@@ -207,3 +252,29 @@ impl IntoRawSocket for net::UdpSocket {
         self.into_inner().into_socket().into_inner()
     }
 }
+
+// The standard streams and `File` check their handles through `sys`, the
+// other types owning one are listed here.
+macro_rules! impl_is_terminal {
+    ($($t:ty)*) => ($(
+        #[unstable(feature = "is_terminal", issue = "0")]
+        impl io::IsTerminal for $t {
+            fn is_terminal(&self) -> bool {
+                sys::handle::RawHandle::new(self.as_raw_handle() as c::HANDLE).is_console()
+            }
+        }
+    )*)
+}
+
+impl_is_terminal! {
+    io::PipeReader io::PipeWriter
+    process::ChildStdin process::ChildStdout process::ChildStderr
+}
+
+/// Sockets are never consoles.
+#[unstable(feature = "is_terminal", issue = "0")]
+impl io::IsTerminal for net::TcpStream {
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
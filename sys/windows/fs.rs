@@ -10,9 +10,11 @@
 
 use os::windows::prelude::*;
 
+use cmp;
 use ffi::OsString;
+use fs::{Advice, MmapMode, Reflink};
 use fmt;
-use io::{self, Error, SeekFrom};
+use io::{self, Error, SeekFrom, IoSlice, IoSliceMut, ReadBuf};
 use mem;
 use path::{Path, PathBuf};
 use ptr;
@@ -21,7 +23,7 @@ use sync::Arc;
 use sys::handle::Handle;
 use sys::time::SystemTime;
 use sys::{c, cvt};
-use sys_common::FromInner;
+use sys_common::{FromInner, IntoInner};
 
 use super::to_u16s;
 
@@ -82,6 +84,31 @@ pub struct FilePermissions { attrs: c::DWORD }
 #[derive(Debug)]
 pub struct DirBuilder;
 
+#[derive(Clone, Debug)]
+pub struct CopyOptions {
+    reflink: Reflink,
+    overwrite: bool,
+    copy_symlinks: bool,
+    preserve_ownership: bool,
+}
+
+#[derive(Clone)]
+pub struct FsStats {
+    total: u64,
+    free: u64,
+    available: u64,
+    cluster_size: u64,
+    serial_number: c::DWORD,
+    max_component_len: c::DWORD,
+    flags: c::DWORD,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FileTimes {
+    accessed: Option<SystemTime>,
+    modified: Option<SystemTime>,
+}
+
 impl fmt::Debug for ReadDir {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // This will only be called from std::fs::ReadDir, which will add a "ReadDir()" frame.
@@ -293,6 +320,95 @@ impl File {
         Ok(())
     }
 
+    // Space for the whole size of a file is allocated when it's extended,
+    // unless the file was made sparse.
+    pub fn allocate(&self, offset: u64, len: u64) -> io::Result<()> {
+        let end = offset.checked_add(len).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "allocation range overflows")
+        })?;
+        if end > self.file_attr()?.size() {
+            self.truncate(end)?;
+        }
+        Ok(())
+    }
+
+    pub fn punch_hole(&self, offset: u64, len: u64) -> io::Result<()> {
+        let end = offset.checked_add(len).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "hole range overflows")
+        })?;
+        let mut info = c::FILE_ZERO_DATA_INFORMATION {
+            FileOffset: offset as c::LARGE_INTEGER,
+            BeyondFinalZero: end as c::LARGE_INTEGER,
+        };
+        let mut bytes = 0;
+        unsafe {
+            // Zeroed ranges are only deallocated in sparse files.
+            cvt(c::DeviceIoControl(self.handle.raw(),
+                                   c::FSCTL_SET_SPARSE,
+                                   ptr::null_mut(), 0,
+                                   ptr::null_mut(), 0,
+                                   &mut bytes,
+                                   ptr::null_mut()))?;
+            cvt(c::DeviceIoControl(self.handle.raw(),
+                                   c::FSCTL_SET_ZERO_DATA,
+                                   &mut info as *mut _ as c::LPVOID,
+                                   mem::size_of_val(&info) as c::DWORD,
+                                   ptr::null_mut(), 0,
+                                   &mut bytes,
+                                   ptr::null_mut()))?;
+        }
+        Ok(())
+    }
+
+    pub fn next_data(&mut self, _offset: u64) -> io::Result<Option<(u64, u64)>> {
+        Err(Error::from_raw_os_error(c::ERROR_NOT_SUPPORTED as i32))
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.lock(0)
+    }
+
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.lock(c::LOCKFILE_EXCLUSIVE_LOCK)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<()> {
+        self.lock(c::LOCKFILE_FAIL_IMMEDIATELY)
+    }
+
+    pub fn try_lock_exclusive(&self) -> io::Result<()> {
+        self.lock(c::LOCKFILE_EXCLUSIVE_LOCK | c::LOCKFILE_FAIL_IMMEDIATELY)
+    }
+
+    fn lock(&self, flags: c::DWORD) -> io::Result<()> {
+        // Lock the whole file, including anything appended later on.
+        let res = unsafe {
+            let mut overlapped: c::OVERLAPPED = mem::zeroed();
+            cvt(c::LockFileEx(self.handle.raw(), flags, 0,
+                              c::DWORD::max_value(), c::DWORD::max_value(),
+                              &mut overlapped))
+        };
+        match res {
+            Ok(_) => Ok(()),
+            Err(ref e) if e.raw_os_error() == Some(c::ERROR_LOCK_VIOLATION as i32) => {
+                Err(io::Error::from(io::ErrorKind::WouldBlock))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        cvt(unsafe {
+            c::UnlockFile(self.handle.raw(), 0, 0,
+                          c::DWORD::max_value(), c::DWORD::max_value())
+        })?;
+        Ok(())
+    }
+
+    pub fn fs_stats(&self) -> io::Result<FsStats> {
+        fs_stats(&get_path(self)?)
+    }
+
     pub fn file_attr(&self) -> io::Result<FileAttr> {
         unsafe {
             let mut info: c::BY_HANDLE_FILE_INFORMATION = mem::zeroed();
@@ -320,6 +436,18 @@ impl File {
         self.handle.read(buf)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        io::default_read_vectored(|b| self.read(b), bufs)
+    }
+
+    pub fn read_buf(&self, buf: &mut ReadBuf) -> io::Result<()> {
+        // `ReadFile` only ever writes to the buffer it is given.
+        let n = self.handle.read(unsafe { buf.unfilled_mut() })?;
+        unsafe { buf.assume_init(n); }
+        buf.add_filled(n);
+        Ok(())
+    }
+
     pub fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
         self.handle.read_at(buf, offset)
     }
@@ -328,6 +456,10 @@ impl File {
         self.handle.write(buf)
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        io::default_write_vectored(|b| self.write(b), bufs)
+    }
+
     pub fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
         self.handle.write_at(buf, offset)
     }
@@ -357,6 +489,10 @@ impl File {
         })
     }
 
+    pub fn is_terminal(&self) -> bool {
+        self.handle.is_console()
+    }
+
     pub fn handle(&self) -> &Handle { &self.handle }
 
     pub fn into_handle(self) -> Handle { self.handle }
@@ -432,6 +568,58 @@ impl File {
         })?;
         Ok(())
     }
+
+    pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
+        let accessed = times.accessed.map(|t| t.into_inner());
+        let modified = times.modified.map(|t| t.into_inner());
+        // `SetFileTime` gives the all-zero and all-one `FILETIME`s a special
+        // meaning instead of treating them as timestamps.
+        let is_special = |t: &Option<c::FILETIME>| match *t {
+            Some(t) => (t.dwLowDateTime == 0 && t.dwHighDateTime == 0) ||
+                       (t.dwLowDateTime == !0 && t.dwHighDateTime == !0),
+            None => false,
+        };
+        if is_special(&accessed) || is_special(&modified) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "cannot set file timestamp to this value"));
+        }
+        let as_ptr = |t: &Option<c::FILETIME>| match *t {
+            Some(ref t) => t as *const c::FILETIME,
+            None => ptr::null(),
+        };
+        cvt(unsafe {
+            c::SetFileTime(self.handle.raw(),
+                           ptr::null(),
+                           as_ptr(&accessed),
+                           as_ptr(&modified))
+        })?;
+        Ok(())
+    }
+}
+
+impl CopyOptions {
+    pub fn new() -> CopyOptions {
+        CopyOptions {
+            reflink: Reflink::Never,
+            overwrite: true,
+            copy_symlinks: false,
+            preserve_ownership: false,
+        }
+    }
+
+    pub fn reflink(&mut self, reflink: Reflink) { self.reflink = reflink; }
+    pub fn overwrite(&mut self, overwrite: bool) { self.overwrite = overwrite; }
+    pub fn copy_symlinks(&mut self, copy_symlinks: bool) { self.copy_symlinks = copy_symlinks; }
+    // `CopyFileExW` always copies the timestamps as well as the extended
+    // attributes and alternate data streams of a file.
+    pub fn preserve_timestamps(&mut self, _preserve: bool) {}
+    pub fn preserve_ownership(&mut self, preserve: bool) { self.preserve_ownership = preserve; }
+    pub fn preserve_xattrs(&mut self, _preserve: bool) {}
+}
+
+impl FileTimes {
+    pub fn set_accessed(&mut self, t: SystemTime) { self.accessed = Some(t); }
+    pub fn set_modified(&mut self, t: SystemTime) { self.modified = Some(t); }
 }
 
 impl FromInner<c::HANDLE> for File {
@@ -543,6 +731,58 @@ impl FileType {
     }
 }
 
+// There's no equivalent of the `openat` family of functions in the Win32
+// API, so directory handles aren't supported (yet).
+#[derive(Debug)]
+pub enum DirHandle {}
+
+impl DirHandle {
+    pub fn open(_path: &Path, _follow_symlinks: bool) -> io::Result<DirHandle> {
+        Err(io::Error::new(io::ErrorKind::Other,
+                           "directory handles are not supported on Windows"))
+    }
+
+    pub fn open_file(&self, _path: &Path, _opts: &OpenOptions) -> io::Result<File> {
+        match *self {}
+    }
+
+    pub fn open_dir(&self, _path: &Path) -> io::Result<DirHandle> {
+        match *self {}
+    }
+
+    pub fn create_dir(&self, _path: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn remove_file(&self, _path: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn remove_dir(&self, _path: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn rename(&self, _from: &Path, _to_dir: &DirHandle, _to: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn symlink(&self, _original: &Path, _link: &Path) -> io::Result<()> {
+        match *self {}
+    }
+
+    pub fn metadata(&self, _path: &Path) -> io::Result<FileAttr> {
+        match *self {}
+    }
+
+    pub fn symlink_metadata(&self, _path: &Path) -> io::Result<FileAttr> {
+        match *self {}
+    }
+
+    pub fn read_dir(&self, _path: &Path) -> io::Result<ReadDir> {
+        match *self {}
+    }
+}
+
 impl DirBuilder {
     pub fn new() -> DirBuilder { DirBuilder }
 
@@ -590,6 +830,43 @@ pub fn rename(old: &Path, new: &Path) -> io::Result<()> {
     Ok(())
 }
 
+pub fn rename_noreplace(old: &Path, new: &Path) -> io::Result<()> {
+    let old = to_u16s(old)?;
+    let new = to_u16s(new)?;
+    cvt(unsafe { c::MoveFileExW(old.as_ptr(), new.as_ptr(), 0) })?;
+    Ok(())
+}
+
+pub fn rename_exchange(_old: &Path, _new: &Path) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported,
+                       "exchanging files is not supported on this platform"))
+}
+
+// Temporary files are shared for deletion so that they can be persisted by
+// renaming them while they're still open.
+pub fn temp_file_options(opts: &mut OpenOptions, delete_on_close: bool) {
+    replacement_file_options(opts);
+    if delete_on_close {
+        opts.custom_flags(c::FILE_FLAG_DELETE_ON_CLOSE);
+    }
+}
+
+pub fn replacement_file_options(opts: &mut OpenOptions) {
+    opts.share_mode(c::FILE_SHARE_READ | c::FILE_SHARE_WRITE | c::FILE_SHARE_DELETE);
+}
+
+pub fn temp_dir_builder(_builder: &mut DirBuilder) {}
+
+pub fn open_tmpfile(_dir: &Path) -> io::Result<Option<File>> {
+    Ok(None)
+}
+
+// Directories can't be flushed on Windows, where NTFS journals renames
+// itself.
+pub fn sync_dir(_p: &Path) -> io::Result<()> {
+    Ok(())
+}
+
 pub fn rmdir(p: &Path) -> io::Result<()> {
     let p = to_u16s(p)?;
     cvt(unsafe { c::RemoveDirectoryW(p.as_ptr()) })?;
@@ -700,6 +977,63 @@ pub fn set_perm(p: &Path, perm: FilePermissions) -> io::Result<()> {
     }
 }
 
+pub fn set_times(p: &Path, times: FileTimes) -> io::Result<()> {
+    let mut opts = OpenOptions::new();
+    opts.access_mode(c::FILE_WRITE_ATTRIBUTES);
+    // This flag is so we can open directories too
+    opts.custom_flags(c::FILE_FLAG_BACKUP_SEMANTICS);
+    let file = File::open(p, &opts)?;
+    file.set_times(times)
+}
+
+pub fn fs_stats(p: &Path) -> io::Result<FsStats> {
+    let p = to_u16s(p)?;
+    // The functions below only accept the root of a volume, which is never
+    // longer than the path of a file on it, unless the path is relative.
+    let mut root = vec![0u16; cmp::max(p.len(), c::MAX_PATH + 1)];
+    cvt(unsafe { c::GetVolumePathNameW(p.as_ptr(), root.as_mut_ptr(), root.len() as c::DWORD) })?;
+
+    let mut stats = FsStats {
+        total: 0,
+        free: 0,
+        available: 0,
+        cluster_size: 0,
+        serial_number: 0,
+        max_component_len: 0,
+        flags: 0,
+    };
+    unsafe {
+        cvt(c::GetDiskFreeSpaceExW(root.as_ptr(), &mut stats.available, &mut stats.total,
+                                   &mut stats.free))?;
+        let (mut sectors_per_cluster, mut bytes_per_sector) = (0, 0);
+        let (mut free_clusters, mut total_clusters) = (0, 0);
+        cvt(c::GetDiskFreeSpaceW(root.as_ptr(), &mut sectors_per_cluster, &mut bytes_per_sector,
+                                 &mut free_clusters, &mut total_clusters))?;
+        stats.cluster_size = sectors_per_cluster as u64 * bytes_per_sector as u64;
+        cvt(c::GetVolumeInformationW(root.as_ptr(), ptr::null_mut(), 0,
+                                     &mut stats.serial_number, &mut stats.max_component_len,
+                                     &mut stats.flags, ptr::null_mut(), 0))?;
+    }
+    Ok(stats)
+}
+
+impl FsStats {
+    pub fn total_space(&self) -> u64 { self.total }
+    pub fn free_space(&self) -> u64 { self.free }
+    pub fn available_space(&self) -> u64 { self.available }
+
+    // NTFS and FAT don't have a fixed number of file records.
+    pub fn total_inodes(&self) -> u64 { 0 }
+    pub fn free_inodes(&self) -> u64 { 0 }
+    pub fn available_inodes(&self) -> u64 { 0 }
+
+    pub fn block_size(&self) -> u64 { self.cluster_size }
+    pub fn fs_id(&self) -> u64 { self.serial_number as u64 }
+    pub fn max_name_len(&self) -> u64 { self.max_component_len as u64 }
+    pub fn is_read_only(&self) -> bool { self.flags & c::FILE_READ_ONLY_VOLUME != 0 }
+    pub fn is_nosuid(&self) -> bool { false }
+}
+
 fn get_path(f: &File) -> io::Result<PathBuf> {
     super::fill_utf16_buf(|buf, sz| unsafe {
         c::GetFinalPathNameByHandleW(f.handle.raw(), buf, sz,
@@ -720,6 +1054,24 @@ pub fn canonicalize(p: &Path) -> io::Result<PathBuf> {
 }
 
 pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
+    copy_file_ex(from, to, 0)
+}
+
+pub fn copy_with(from: &Path, to: &Path, opts: &CopyOptions) -> io::Result<u64> {
+    if opts.reflink == Reflink::Always || opts.preserve_ownership {
+        return Err(Error::from_raw_os_error(c::ERROR_NOT_SUPPORTED as i32))
+    }
+    let mut flags = 0;
+    if !opts.overwrite {
+        flags |= c::COPY_FILE_FAIL_IF_EXISTS;
+    }
+    if opts.copy_symlinks {
+        flags |= c::COPY_FILE_COPY_SYMLINK;
+    }
+    copy_file_ex(from, to, flags)
+}
+
+fn copy_file_ex(from: &Path, to: &Path, flags: c::DWORD) -> io::Result<u64> {
     unsafe extern "system" fn callback(
         _TotalFileSize: c::LARGE_INTEGER,
         _TotalBytesTransferred: c::LARGE_INTEGER,
@@ -739,7 +1091,7 @@ pub fn copy(from: &Path, to: &Path) -> io::Result<u64> {
     let mut size = 0i64;
     cvt(unsafe {
         c::CopyFileExW(pfrom.as_ptr(), pto.as_ptr(), Some(callback),
-                       &mut size as *mut _ as *mut _, ptr::null_mut(), 0)
+                       &mut size as *mut _ as *mut _, ptr::null_mut(), flags)
     })?;
     Ok(size as u64)
 }
@@ -797,3 +1149,85 @@ fn symlink_junction_inner(target: &Path, junction: &Path) -> io::Result<()> {
                                ptr::null_mut())).map(|_| ())
     }
 }
+
+pub struct Mmap {
+    // Views have to start at a multiple of the allocation granularity, so
+    // the view may start before the requested data at `ptr`.
+    base: c::LPVOID,
+    ptr: *mut u8,
+    len: usize,
+}
+
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    pub fn new(file: &File, mode: MmapMode, offset: u64, len: usize) -> io::Result<Mmap> {
+        // Empty files can't be mapped at all.
+        if len == 0 {
+            return Ok(Mmap {
+                base: ptr::null_mut(),
+                ptr: ptr::NonNull::dangling().as_ptr(),
+                len: 0,
+            })
+        }
+
+        let granularity = unsafe {
+            let mut info: c::SYSTEM_INFO = mem::zeroed();
+            c::GetSystemInfo(&mut info);
+            info.dwAllocationGranularity as u64
+        };
+        let align = (offset % granularity) as usize;
+        let start = offset - align as u64;
+        let map_len = len.checked_add(align).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "mapping length overflows")
+        })?;
+        let (protect, access) = match mode {
+            MmapMode::ReadOnly => (c::PAGE_READONLY, c::FILE_MAP_READ),
+            MmapMode::Shared => (c::PAGE_READWRITE, c::FILE_MAP_WRITE),
+            MmapMode::Private => (c::PAGE_WRITECOPY, c::FILE_MAP_COPY),
+        };
+        unsafe {
+            let mapping = c::CreateFileMappingW(file.handle.raw(), ptr::null_mut(), protect,
+                                                0, 0, ptr::null());
+            if mapping.is_null() {
+                return Err(Error::last_os_error())
+            }
+            // The view keeps the mapping object alive.
+            let mapping = Handle::new(mapping);
+            let base = c::MapViewOfFile(mapping.raw(), access, (start >> 32) as c::DWORD,
+                                        start as c::DWORD, map_len);
+            if base.is_null() {
+                return Err(Error::last_os_error())
+            }
+            Ok(Mmap { base: base, ptr: (base as *mut u8).offset(align as isize), len: len })
+        }
+    }
+
+    pub fn ptr(&self) -> *mut u8 { self.ptr }
+
+    pub fn len(&self) -> usize { self.len }
+
+    pub fn flush(&self, offset: usize, len: usize) -> io::Result<()> {
+        if len == 0 {
+            return Ok(())
+        }
+        cvt(unsafe {
+            c::FlushViewOfFile(self.ptr.offset(offset as isize) as c::LPCVOID, len)
+        })?;
+        Ok(())
+    }
+
+    // Windows has no equivalent for most kinds of advice.
+    pub fn advise(&self, _advice: Advice) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if !self.base.is_null() {
+            unsafe { c::UnmapViewOfFile(self.base); }
+        }
+    }
+}
@@ -79,6 +79,11 @@ impl RawHandle {
 
     pub fn raw(&self) -> c::HANDLE { self.0 }
 
+    pub fn is_console(&self) -> bool {
+        let mut mode = 0;
+        unsafe { c::GetConsoleMode(self.0, &mut mode) != 0 }
+    }
+
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         let mut read = 0;
         let len = cmp::min(buf.len(), <c::DWORD>::max_value() as usize) as c::DWORD;
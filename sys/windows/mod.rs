@@ -35,6 +35,7 @@ pub mod ext;
 pub mod fast_thread_local;
 pub mod fs;
 pub mod handle;
+pub mod io;
 pub mod memchr;
 pub mod mutex;
 pub mod net;
@@ -65,6 +66,21 @@ pub fn decode_error_kind(errno: i32) -> ErrorKind {
         c::ERROR_PATH_NOT_FOUND => return ErrorKind::NotFound,
         c::ERROR_NO_DATA => return ErrorKind::BrokenPipe,
         c::ERROR_OPERATION_ABORTED => return ErrorKind::TimedOut,
+        c::ERROR_DIRECTORY => return ErrorKind::NotADirectory,
+        c::ERROR_DIR_NOT_EMPTY => return ErrorKind::DirectoryNotEmpty,
+        c::ERROR_WRITE_PROTECT => return ErrorKind::ReadOnlyFilesystem,
+        c::ERROR_DISK_FULL => return ErrorKind::StorageFull,
+        c::ERROR_HANDLE_DISK_FULL => return ErrorKind::StorageFull,
+        c::ERROR_DISK_QUOTA_EXCEEDED => return ErrorKind::QuotaExceeded,
+        c::ERROR_FILE_TOO_LARGE => return ErrorKind::FileTooLarge,
+        c::ERROR_BUSY => return ErrorKind::ResourceBusy,
+        c::ERROR_NOT_SAME_DEVICE => return ErrorKind::CrossesDevices,
+        c::ERROR_TOO_MANY_LINKS => return ErrorKind::TooManyLinks,
+        c::ERROR_INVALID_NAME => return ErrorKind::InvalidFilename,
+        c::ERROR_FILENAME_EXCED_RANGE => return ErrorKind::InvalidFilename,
+        c::ERROR_POSSIBLE_DEADLOCK => return ErrorKind::Deadlock,
+        c::ERROR_CALL_NOT_IMPLEMENTED => return ErrorKind::Unsupported,
+        c::ERROR_NOT_SUPPORTED => return ErrorKind::Unsupported,
         _ => {}
     }
 
@@ -79,6 +95,8 @@ pub fn decode_error_kind(errno: i32) -> ErrorKind {
         c::WSAENOTCONN => ErrorKind::NotConnected,
         c::WSAEWOULDBLOCK => ErrorKind::WouldBlock,
         c::WSAETIMEDOUT => ErrorKind::TimedOut,
+        c::WSAEHOSTUNREACH => ErrorKind::HostUnreachable,
+        c::WSAENETUNREACH => ErrorKind::NetworkUnreachable,
 
         _ => ErrorKind::Other,
     }
@@ -11,7 +11,7 @@
 #![unstable(issue = "0", feature = "windows_net")]
 
 use cmp;
-use io::{self, Read};
+use io::{self, Read, IoSlice, IoSliceMut, ReadBuf};
 use libc::{c_int, c_void, c_ulong, c_long};
 use mem;
 use net::{SocketAddr, Shutdown};
@@ -217,10 +217,54 @@ impl Socket {
         self.recv_with_flags(buf, 0)
     }
 
+    pub fn read_buf(&self, buf: &mut ReadBuf) -> io::Result<()> {
+        // `recv` only ever writes to the buffer it is given.
+        let n = self.recv_with_flags(unsafe { buf.unfilled_mut() }, 0)?;
+        unsafe { buf.assume_init(n); }
+        buf.add_filled(n);
+        Ok(())
+    }
+
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        // As with `recv_with_flags`, a shut down socket maps to EOF.
+        let len = cmp::min(bufs.len(), c::DWORD::max_value() as usize) as c::DWORD;
+        let mut nread = 0;
+        let mut flags = 0;
+        unsafe {
+            let ret = c::WSARecv(self.0,
+                                 bufs.as_mut_ptr() as *mut c::WSABUF,
+                                 len,
+                                 &mut nread,
+                                 &mut flags,
+                                 ptr::null_mut(),
+                                 ptr::null_mut());
+            match ret {
+                -1 if c::WSAGetLastError() == c::WSAESHUTDOWN => Ok(0),
+                -1 => Err(last_error()),
+                _ => Ok(nread as usize),
+            }
+        }
+    }
+
     pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.recv_with_flags(buf, c::MSG_PEEK)
     }
 
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        let len = cmp::min(bufs.len(), c::DWORD::max_value() as usize) as c::DWORD;
+        let mut nwritten = 0;
+        unsafe {
+            cvt(c::WSASend(self.0,
+                           bufs.as_ptr() as *const c::WSABUF as *mut c::WSABUF,
+                           len,
+                           &mut nwritten,
+                           0,
+                           ptr::null_mut(),
+                           ptr::null_mut()))?;
+        }
+        Ok(nwritten as usize)
+    }
+
     fn recv_from_with_flags(&self, buf: &mut [u8], flags: c_int)
                             -> io::Result<(usize, SocketAddr)> {
         let mut storage: c::SOCKADDR_STORAGE_LH = unsafe { mem::zeroed() };
@@ -331,6 +375,14 @@ impl<'a> Read for &'a Socket {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         (**self).read(buf)
     }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        (**self).read_vectored(bufs)
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf) -> io::Result<()> {
+        (**self).read_buf(buf)
+    }
 }
 
 impl Drop for Socket {
@@ -11,7 +11,7 @@
 use os::windows::prelude::*;
 
 use ffi::OsStr;
-use io;
+use io::{self, IoSlice, IoSliceMut};
 use mem;
 use path::Path;
 use ptr;
@@ -19,9 +19,11 @@ use slice;
 use sync::atomic::Ordering::SeqCst;
 use sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT};
 use sys::c;
+use sys::cvt;
 use sys::fs::{File, OpenOptions};
 use sys::handle::Handle;
 use sys::hashmap_random_keys;
+use sys_common::FromInner;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Anonymous pipes
@@ -157,6 +159,19 @@ pub fn anon_pipe(ours_readable: bool) -> io::Result<Pipes> {
     }
 }
 
+/// Creates the pipe handed out by `io::pipe`.
+///
+/// Unlike `anon_pipe` this is a plain anonymous pipe from `CreatePipe`.
+/// Neither end supports overlapped I/O, which is fine as both are only ever
+/// used synchronously, and neither is inheritable; handing one to a child
+/// process duplicates it like any other handle.
+pub fn pipe() -> io::Result<(AnonPipe, AnonPipe)> {
+    let mut reader = ptr::null_mut();
+    let mut writer = ptr::null_mut();
+    cvt(unsafe { c::CreatePipe(&mut reader, &mut writer, ptr::null_mut(), 0) })?;
+    Ok((AnonPipe { inner: Handle::new(reader) }, AnonPipe { inner: Handle::new(writer) }))
+}
+
 fn random_number() -> usize {
     static N: AtomicUsize = ATOMIC_USIZE_INIT;
     loop {
@@ -172,13 +187,31 @@ impl AnonPipe {
     pub fn handle(&self) -> &Handle { &self.inner }
     pub fn into_handle(self) -> Handle { self.inner }
 
+    pub fn duplicate(&self) -> io::Result<AnonPipe> {
+        self.inner.duplicate(0, false, c::DUPLICATE_SAME_ACCESS).map(|inner| AnonPipe { inner })
+    }
+
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.inner.read(buf)
     }
 
+    pub fn read_vectored(&self, bufs: &mut [IoSliceMut]) -> io::Result<usize> {
+        io::default_read_vectored(|b| self.read(b), bufs)
+    }
+
     pub fn write(&self, buf: &[u8]) -> io::Result<usize> {
         self.inner.write(buf)
     }
+
+    pub fn write_vectored(&self, bufs: &[IoSlice]) -> io::Result<usize> {
+        io::default_write_vectored(|b| self.write(b), bufs)
+    }
+}
+
+impl FromInner<Handle> for AnonPipe {
+    fn from_inner(inner: Handle) -> AnonPipe {
+        AnonPipe { inner }
+    }
 }
 
 pub fn read2(p1: AnonPipe,
@@ -47,6 +47,13 @@ pub fn get(handle: c::DWORD) -> io::Result<Output> {
     }
 }
 
+fn is_console(handle: c::DWORD) -> bool {
+    match get(handle) {
+        Ok(Output::Console(_)) => true,
+        _ => false,
+    }
+}
+
 fn write(handle: c::DWORD, data: &[u8]) -> io::Result<usize> {
     let handle = match try!(get(handle)) {
         Output::Console(c) => c,
@@ -98,6 +105,10 @@ impl Stdin {
         })
     }
 
+    pub fn is_terminal(&self) -> bool {
+        is_console(c::STD_INPUT_HANDLE)
+    }
+
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
         let handle = match try!(get(c::STD_INPUT_HANDLE)) {
             Output::Console(c) => c,
@@ -157,6 +168,10 @@ impl Stdout {
         Ok(Stdout)
     }
 
+    pub fn is_terminal(&self) -> bool {
+        is_console(c::STD_OUTPUT_HANDLE)
+    }
+
     pub fn write(&self, data: &[u8]) -> io::Result<usize> {
         write(c::STD_OUTPUT_HANDLE, data)
     }
@@ -171,6 +186,10 @@ impl Stderr {
         Ok(Stderr)
     }
 
+    pub fn is_terminal(&self) -> bool {
+        is_console(c::STD_ERROR_HANDLE)
+    }
+
     pub fn write(&self, data: &[u8]) -> io::Result<usize> {
         write(c::STD_ERROR_HANDLE, data)
     }
@@ -14,7 +14,7 @@ use mem;
 use sync::Once;
 use sys::c;
 use sys::cvt;
-use sys_common::mul_div_u64;
+use sys_common::{mul_div_u64, IntoInner};
 use time::Duration;
 use convert::TryInto;
 
@@ -173,6 +173,12 @@ impl From<c::FILETIME> for SystemTime {
     }
 }
 
+impl IntoInner<c::FILETIME> for SystemTime {
+    fn into_inner(self) -> c::FILETIME {
+        self.t
+    }
+}
+
 fn dur2intervals(d: &Duration) -> i64 {
     d.as_secs()
         .checked_mul(INTERVALS_PER_SEC)
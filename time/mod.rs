@@ -26,7 +26,7 @@ use error::Error;
 use fmt;
 use ops::{Add, Sub, AddAssign, SubAssign};
 use sys::time;
-use sys_common::FromInner;
+use sys_common::{AsInner, FromInner};
 
 #[stable(feature = "time", since = "1.3.0")]
 pub use self::duration::Duration;
@@ -446,6 +446,12 @@ impl FromInner<time::SystemTime> for SystemTime {
     }
 }
 
+impl AsInner<time::SystemTime> for SystemTime {
+    fn as_inner(&self) -> &time::SystemTime {
+        &self.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Instant, SystemTime, Duration, UNIX_EPOCH};